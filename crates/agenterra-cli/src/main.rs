@@ -3,22 +3,49 @@
 
 // Internal imports (std, crate)
 use reqwest::Url;
+use std::io::IsTerminal;
 use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
 
-use dialoguer::{theme::ColorfulTheme, Input, Select};
+use dialoguer::{
+    theme::{ColorfulTheme, SimpleTheme, Theme},
+    Input, Select,
+};
 use notify::{recommended_watcher, RecursiveMode, Watcher};
 use tokio::sync::mpsc;
 
 // External imports (alphabetized)
-use agenterra_core::{TemplateKind, TemplateManager, TemplateOptions};
+use agenterra_core::{
+    openapi::DEFAULT_MAX_SPEC_BYTES, GraphQlContext, Layout, OpenApiContext, PostmanContext,
+    SpecSource, TemplateKind, TemplateManager, TemplateOptions, ToolsContext,
+};
 use anyhow::Context;
 use clap::Parser;
 use tokio::fs;
+use tracing_subscriber::{filter::EnvFilter, fmt, prelude::*};
+
+/// Log filter for the `--log-to` file when `RUST_LOG` isn't set: agenterra's
+/// own debug output, and warnings and up from its dependencies
+const DEFAULT_LOG_FILE_FILTER: &str = "warn,agenterra=debug,agenterra_core=debug";
+
+/// Project name used when none is given and no output directory is set
+const DEFAULT_PROJECT_NAME: &str = "agenterra_mcp_server";
+
+/// Whether output may be colored and decorated, decided once at startup
+static COLOR: OnceLock<bool> = OnceLock::new();
 
 #[derive(Parser)]
 #[command(name = "agenterra")]
 #[command(author, version, about, long_about = None)]
 struct Cli {
+    /// Print plain output without colors or emoji (also set by `NO_COLOR`, and
+    /// implied when stdout is not a terminal)
+    #[arg(long, global = true)]
+    pub no_color: bool,
+    /// Also write logs, without colors, to this file (overwritten each run);
+    /// `RUST_LOG` applies to it too, defaulting to agenterra's debug logs
+    #[arg(long, global = true, value_name = "PATH")]
+    pub log_to: Option<PathBuf>,
     #[command(subcommand)]
     pub command: Commands,
 }
@@ -28,20 +55,22 @@ struct Cli {
 pub enum Commands {
     /// Scaffold a new MCP server from an OpenAPI spec
     Scaffold {
-        /// Project name
-        #[arg(long, default_value = "agenterra_mcp_server")]
-        project_name: String,
+        /// Project name (defaults to a name derived from the spec title)
+        #[arg(long)]
+        project_name: Option<String>,
         /// Path or URL to OpenAPI schema (YAML or JSON)
         ///
-        /// Can be a local file path or an HTTP/HTTPS URL
+        /// Can be a local file path, an HTTP/HTTPS URL or an entry of a zip archive
         /// Example: --schema-path path/to/schema.yaml
         /// Example: --schema-path https://example.com/openapi.json
-        #[arg(long)]
-        schema_path: String,
+        /// Example: --schema-path specs.zip#openapi.yaml
+        #[arg(long, required_unless_present = "tools_file")]
+        schema_path: Option<String>,
         /// Template to use for code generation (e.g., rust_axum, python_fastapi)
         #[arg(long, default_value = "rust_axum")]
         template_kind: String,
-        /// Custom template directory (only used with --template-kind=custom)
+        /// Custom template directory (only used with --template-kind=custom, or with a
+        /// template kind agenterra doesn't know, which is then treated as custom)
         #[arg(long)]
         template_dir: Option<PathBuf>,
         /// Output directory for generated code
@@ -56,20 +85,145 @@ pub enum Commands {
         /// Base URL of the OpenAPI specification (Optional)
         #[arg(long)]
         base_url: Option<Url>,
+        /// Use the Nth (0-based) entry of the spec's `servers` as the API URL (default: the first)
+        #[arg(long, value_name = "N")]
+        server_index: Option<usize>,
         /// Watch schema file for changes and rebuild automatically
         #[arg(long)]
         watch: bool,
+        /// With --watch, regenerate only the operations that changed
+        #[arg(long, requires = "watch")]
+        only_changed: bool,
+        /// Keep hand edits in an existing output directory: only create missing handlers
+        /// and rewrite the `AGENTERRA:BEGIN`/`END` regions of other files
+        #[arg(long)]
+        append_only: bool,
+        /// File whose contents are prepended as a comment header to every generated source file
+        #[arg(long)]
+        header_file: Option<PathBuf>,
+        /// Instructions the generated MCP server gives agents
+        #[arg(long, conflicts_with = "agent_instructions_file")]
+        agent_instructions: Option<String>,
+        /// File whose contents become the instructions the generated MCP server gives agents
+        #[arg(long, value_name = "PATH")]
+        agent_instructions_file: Option<PathBuf>,
+        /// Also generate a Dockerfile for the scaffolded server
+        #[arg(long)]
+        with_dockerfile: bool,
+        /// Also generate a `.env.example` listing the server's environment variables
+        #[arg(long)]
+        with_env_example: bool,
+        /// Also write an `mcp.json` server descriptor listing the generated tools
+        #[arg(long)]
+        emit_mcp_descriptor: bool,
+        /// Also write a standalone OpenAPI document per operation to `openapi/<operation>.json`
+        #[arg(long)]
+        emit_operation_fragments: bool,
+        /// Generate a Cargo workspace with the server crate under `crates/`
+        #[arg(long)]
+        workspace: bool,
+        /// Treat --schema-path as a Postman v2.1 collection and convert it to OpenAPI
+        #[arg(long)]
+        from_postman: bool,
+        /// Treat --schema-path as a GraphQL schema (SDL) and generate a tool per query and mutation
+        #[arg(long, conflicts_with = "from_postman")]
+        from_graphql: bool,
+        /// Generate the tools listed in this JSON file instead of reading an OpenAPI spec
+        #[arg(long, conflicts_with_all = ["schema_path", "from_postman", "from_graphql"])]
+        tools_file: Option<PathBuf>,
+        /// Only generate operations whose path matches this glob (repeatable, e.g. `/pets/**`)
+        #[arg(long = "include-path")]
+        include_paths: Vec<String>,
+        /// Skip operations whose path matches this glob (repeatable, e.g. `/admin/**`)
+        #[arg(long = "exclude-path")]
+        exclude_paths: Vec<String>,
+        /// Render only this manifest template (by source, e.g. `Cargo.toml.tera`) into the output directory
+        #[arg(long)]
+        only_file: Option<String>,
+        /// Print the template context of this operation as JSON instead of generating
+        #[arg(long, value_name = "OPERATION_ID")]
+        dump_context: Option<String>,
+        /// Fail instead of falling back to `String`/`Value` for schemas without a precise type
+        #[arg(long)]
+        fail_on_unmapped_type: bool,
+        /// Remove `x-` vendor extensions from the generated schema files
+        #[arg(long)]
+        strip_vendor_extensions: bool,
+        /// Remove descriptions, titles and examples from the generated schema files
+        #[arg(long)]
+        minify_schemas: bool,
+        /// Maximum number of operations rendered at once (default: number of CPUs)
+        #[arg(long, value_name = "N")]
+        max_concurrency: Option<usize>,
+        /// Fail when the template's manifest language doesn't match --template-kind,
+        /// path parameters don't match their path templates or an operation has no 2xx response
+        #[arg(long)]
+        strict: bool,
+        /// Regenerate even if the spec, template and options are unchanged since
+        /// the last generation into the output directory
+        #[arg(long)]
+        force: bool,
+        /// Fail unless `cargo clippy -- -D warnings` passes on the generated Rust code
+        #[arg(long)]
+        lint_output: bool,
+        /// Also write the spec with all external `$ref`s inlined to this path
+        #[arg(long)]
+        emit_bundled_spec: Option<PathBuf>,
+        /// Maximum size in bytes of a spec fetched from a URL or read from stdin
+        /// (default: 32 MiB)
+        #[arg(long, value_name = "BYTES")]
+        max_spec_bytes: Option<u64>,
+        /// Apply this patch to the spec before generating: a JSON Patch (RFC 6902)
+        /// array of operations or a JSON merge patch (RFC 7386) object
+        #[arg(long, value_name = "PATH")]
+        spec_patch: Option<PathBuf>,
     },
     /// Interactive scaffolding flow
     Init,
     /// List available template kinds
     ListTemplates,
+    /// Print an overview of an OpenAPI spec: metadata, operations and tags
+    Info {
+        /// Path or URL to OpenAPI schema (YAML or JSON)
+        #[arg(long)]
+        schema_path: String,
+        /// Output format
+        #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
+    },
+    /// List the operations that would be generated from an OpenAPI spec
+    ListOperations {
+        /// Path or URL to OpenAPI schema (YAML or JSON)
+        #[arg(long)]
+        schema_path: String,
+        /// Output format
+        #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
+    },
+    /// Regenerate a scaffolded project with the current template and print the changes as a patch
+    Upgrade {
+        /// Directory of the previously scaffolded project
+        project_dir: PathBuf,
+        /// Template directory to upgrade to (defaults to the one the project was generated with)
+        #[arg(long)]
+        template_dir: Option<PathBuf>,
+        /// Write the patch to this file instead of stdout
+        #[arg(long, value_name = "PATH")]
+        output: Option<PathBuf>,
+    },
+}
+
+/// Output format for listing commands
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutputFormat {
+    Text,
+    Json,
 }
 
 /// Arguments needed to scaffold a project
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Default)]
 struct ScaffoldArgs {
-    project_name: String,
+    project_name: Option<String>,
     schema_path: String,
     template_kind: String,
     template_dir: Option<PathBuf>,
@@ -77,22 +231,176 @@ struct ScaffoldArgs {
     log_file: Option<String>,
     port: Option<u16>,
     base_url: Option<Url>,
+    server_index: Option<usize>,
     watch: bool,
+    only_changed: bool,
+    append_only: bool,
+    header_file: Option<PathBuf>,
+    agent_instructions: Option<String>,
+    agent_instructions_file: Option<PathBuf>,
+    with_dockerfile: bool,
+    with_env_example: bool,
+    emit_mcp_descriptor: bool,
+    emit_operation_fragments: bool,
+    workspace: bool,
+    from_postman: bool,
+    from_graphql: bool,
+    /// `schema_path` is a tools file rather than a spec
+    from_tools_file: bool,
+    include_paths: Vec<String>,
+    exclude_paths: Vec<String>,
+    only_file: Option<String>,
+    dump_context: Option<String>,
+    fail_on_unmapped_type: bool,
+    strip_vendor_extensions: bool,
+    minify_schemas: bool,
+    max_concurrency: Option<usize>,
+    strict: bool,
+    force: bool,
+    lint_output: bool,
+    emit_bundled_spec: Option<PathBuf>,
+    spec_patch: Option<PathBuf>,
+    max_spec_bytes: Option<u64>,
+}
+
+impl ScaffoldArgs {
+    /// Resolve output directory - use project_name if not specified
+    fn output_path(&self) -> PathBuf {
+        self.output_dir.clone().unwrap_or_else(|| {
+            PathBuf::from(self.project_name.as_deref().unwrap_or(DEFAULT_PROJECT_NAME))
+        })
+    }
+
+    /// Parse the template kind argument
+    fn template_kind(&self) -> anyhow::Result<TemplateKind> {
+        self.template_kind
+            .parse()
+            .map_err(|e| anyhow::anyhow!("Invalid template '{}' : {e}", self.template_kind))
+    }
+
+    /// Build the generator config for these arguments
+    fn config(&self) -> agenterra_core::Config {
+        agenterra_core::Config {
+            // Empty lets the generator derive the name from the spec title
+            project_name: self.project_name.clone().unwrap_or_default(),
+            openapi_schema_path: self.schema_path.clone(),
+            output_dir: self.output_path().to_string_lossy().to_string(),
+            template_kind: self.template_kind.clone(),
+            template_dir: self
+                .template_dir
+                .as_ref()
+                .map(|p| p.to_string_lossy().to_string()),
+            include_all: true,
+            include_operations: Vec::new(),
+            exclude_operations: Vec::new(),
+            base_url: self.base_url.clone(),
+        }
+    }
+
+    /// Load the spec, converting it first when it is a Postman collection, GraphQL
+    /// schema or tools file
+    async fn spec(&self) -> anyhow::Result<OpenApiContext> {
+        if self.from_postman {
+            let collection = PostmanContext::from_file(&self.schema_path)
+                .await
+                .context("Failed to load Postman collection")?;
+            Ok(collection.to_openapi())
+        } else if self.from_graphql {
+            let schema = GraphQlContext::from_file(&self.schema_path)
+                .await
+                .context("Failed to load GraphQL schema")?;
+            Ok(schema.to_openapi())
+        } else if self.from_tools_file {
+            let tools = ToolsContext::from_file(&self.schema_path)
+                .await
+                .context("Failed to load tools file")?;
+            Ok(tools.to_openapi())
+        } else {
+            load_spec(&self.schema_path, self.max_spec_bytes).await
+        }
+    }
+
+    /// Build the template options for these arguments
+    fn template_options(&self) -> anyhow::Result<TemplateOptions> {
+        let file_header = match &self.header_file {
+            Some(path) => Some(
+                std::fs::read_to_string(path)
+                    .with_context(|| format!("Failed to read header file {}", path.display()))?,
+            ),
+            None => None,
+        };
+        let agent_instructions = match &self.agent_instructions_file {
+            Some(path) => Some(std::fs::read_to_string(path).with_context(|| {
+                format!("Failed to read agent instructions file {}", path.display())
+            })?),
+            None => self.agent_instructions.clone(),
+        };
+        let spec_patch = match &self.spec_patch {
+            Some(path) => {
+                let content = std::fs::read_to_string(path)
+                    .with_context(|| format!("Failed to read spec patch {}", path.display()))?;
+                let patch: serde_json::Value = serde_json::from_str(&content)
+                    .with_context(|| format!("Spec patch {} is not valid JSON", path.display()))?;
+                Some(patch)
+            }
+            None => None,
+        };
+        Ok(TemplateOptions {
+            server_port: self.port,
+            log_file: self.log_file.clone(),
+            file_header,
+            agent_instructions: agent_instructions.map(serde_json::Value::String),
+            with_dockerfile: self.with_dockerfile,
+            with_env_example: self.with_env_example,
+            emit_mcp_descriptor: self.emit_mcp_descriptor,
+            emit_operation_fragments: self.emit_operation_fragments,
+            layout: if self.workspace {
+                Layout::Workspace
+            } else {
+                Layout::SingleCrate
+            },
+            include_paths: self.include_paths.clone(),
+            exclude_paths: self.exclude_paths.clone(),
+            fail_on_unmapped_type: self.fail_on_unmapped_type,
+            strip_vendor_extensions: self.strip_vendor_extensions,
+            minify_schemas: self.minify_schemas,
+            max_concurrency: self.max_concurrency,
+            server_index: self.server_index,
+            append_only: self.append_only,
+            strict: self.strict,
+            force: self.force,
+            lint_output: self.lint_output,
+            spec_patch,
+            ..Default::default()
+        })
+    }
+}
+
+/// Fail fast if files can't be created in `dir`, before the spec is loaded and
+/// contexts are built
+async fn ensure_writable(dir: &Path) -> anyhow::Result<()> {
+    let probe = dir.join(format!(".agenterra-write-probe-{}", std::process::id()));
+    fs::write(&probe, b"").await.map_err(|e| {
+        anyhow::anyhow!("Output directory {} is not writable: {}", dir.display(), e)
+    })?;
+    fs::remove_file(&probe).await.ok();
+    Ok(())
 }
 
 /// Execute the scaffold flow with the provided arguments
 async fn run_scaffold(args: &ScaffoldArgs) -> anyhow::Result<()> {
     // Parse template
-    let template_kind_enum: TemplateKind = args
-        .template_kind
-        .parse()
-        .map_err(|e| anyhow::anyhow!("Invalid template '{}' : {e}", args.template_kind))?;
+    let template_kind_enum = args.template_kind()?;
 
     // Resolve output directory - use project_name if not specified
-    let output_path = args
-        .output_dir
-        .clone()
-        .unwrap_or_else(|| PathBuf::from(&args.project_name));
+    let output_path = args.output_path();
+
+    tracing::info!(
+        template = template_kind_enum.as_str(),
+        schema = %args.schema_path,
+        output_dir = %output_path.display(),
+        "Starting generation"
+    );
 
     // Debug log template and paths
     println!(
@@ -114,6 +422,7 @@ async fn run_scaffold(args: &ScaffoldArgs) -> anyhow::Result<()> {
             .await
             .map_err(|e| anyhow::anyhow!("Failed to create output directory: {}", e))?;
     }
+    ensure_writable(&output_path).await?;
 
     // List available templates for debugging
     println!("Available templates:");
@@ -126,8 +435,12 @@ async fn run_scaffold(args: &ScaffoldArgs) -> anyhow::Result<()> {
         template_manager.template_dir().display()
     );
 
-    // Create directories for all template file destinations
+    // Create directories for all template file destinations; with --workspace,
+    // files belonging to a member crate are placed under crates/ by the generator
     for file in &template_manager.manifest().files {
+        if args.workspace && file.crate_name.is_some() {
+            continue;
+        }
         if let Some(parent) = Path::new(&file.destination).parent() {
             let dir = output_path.join(parent);
             if !dir.exists() {
@@ -140,88 +453,244 @@ async fn run_scaffold(args: &ScaffoldArgs) -> anyhow::Result<()> {
     }
 
     // Load the OpenAPI schema from either a file or URL
-    let schema_path = &args.schema_path;
-    println!("Loading OpenAPI schema from: {}", schema_path);
-
-    // Check if the schema_path is a URL or a file path
-    let schema_obj = if schema_path.starts_with("http://") || schema_path.starts_with("https://") {
-        // It's a URL, use from_url
-        let response = reqwest::get(schema_path.as_str()).await.map_err(|e| {
-            anyhow::anyhow!("Failed to fetch OpenAPI schema from {}: {}", schema_path, e)
-        })?;
-
-        if !response.status().is_success() {
-            return Err(anyhow::anyhow!(
-                "Failed to fetch OpenAPI schema from {}: HTTP {}",
-                schema_path,
-                response.status()
-            ));
+    println!("Loading OpenAPI schema from: {}", args.schema_path);
+    let mut schema_obj = args.spec().await?;
+    if let Some(path) = &args.emit_bundled_spec {
+        schema_obj = emit_bundled_spec(args, &schema_obj, path).await?;
+    }
+
+    // Create config and template options
+    let config = args.config();
+    let template_opts = args.template_options()?;
+
+    if let Some(source) = &args.only_file {
+        let files = template_manager
+            .render_one(source, &schema_obj, &config, Some(template_opts))
+            .await?;
+        for (path, content) in files {
+            let dest = output_path.join(&path);
+            if let Some(parent) = dest.parent() {
+                fs::create_dir_all(parent).await?;
+            }
+            fs::write(&dest, content).await?;
+            println!("Rendered {} -> {}", source, dest.display());
         }
+        return Ok(());
+    }
 
-        let content = response
-            .text()
-            .await
-            .map_err(|e| anyhow::anyhow!("Failed to read response from {}: {}", schema_path, e))?;
+    // Generate the server using the template manager
+    let report = template_manager
+        .generate(&schema_obj, &config, Some(template_opts))
+        .await?;
+    if report.skipped {
+        println!(
+            "No changes since the last generation in {}, skipping (use --force to regenerate)",
+            output_path.display()
+        );
+        return Ok(());
+    }
+    for warning in &report.warnings {
+        eprintln!("{}{}", decoration("⚠️  ", "warning: "), warning);
+    }
 
-        // Parse the content as OpenAPI schema
-        let temp_dir = tempfile::tempdir()?;
-        let temp_file = temp_dir.path().join("openapi_schema.json");
-        tokio::fs::write(&temp_file, &content).await?;
+    println!(
+        "{}Successfully generated server in: {}",
+        decoration("✅ ", ""),
+        output_path.display()
+    );
+    Ok(())
+}
 
-        agenterra_core::openapi::OpenApiContext::from_file(&temp_file)
-            .await
-            .map_err(|e| {
-                anyhow::anyhow!("Failed to parse OpenAPI schema from {}: {}\nSee docs/CONFIGURATION.md#troubleshooting", schema_path, e)
-            })?
+/// Print the template context of one operation as JSON, keeping stdout free
+/// of progress output so it can be piped
+async fn dump_operation_context(args: &ScaffoldArgs, operation_id: &str) -> anyhow::Result<()> {
+    let template_manager = TemplateManager::new(args.template_kind()?, args.template_dir.clone())
+        .await
+        .context("Failed to initialize template manager")?;
+    let spec = args.spec().await?;
+    let context = template_manager
+        .operation_context(
+            &spec,
+            operation_id,
+            &args.config(),
+            Some(args.template_options()?),
+        )
+        .await?;
+    println!("{}", serde_json::to_string_pretty(&context)?);
+    Ok(())
+}
+
+/// Inline the spec's external refs and write the result to `path`, as YAML
+/// when the input spec is a `.yaml`/`.yml` file and as JSON otherwise
+async fn emit_bundled_spec(
+    args: &ScaffoldArgs,
+    spec: &OpenApiContext,
+    path: &Path,
+) -> anyhow::Result<OpenApiContext> {
+    let schema_path = Path::new(&args.schema_path);
+    let bundled = match args.schema_path.parse()? {
+        SpecSource::File(file) => {
+            spec.bundle(file.parent().map(Path::to_path_buf).unwrap_or_default())
+                .await
+        }
+        SpecSource::Url(url) => spec.bundle_url(&url).await,
+        _ => spec.bundle(".").await,
+    }
+    .context("Failed to bundle OpenAPI schema")?;
+
+    let is_yaml = !args.from_postman
+        && !args.from_graphql
+        && schema_path
+            .extension()
+            .is_some_and(|ext| ext == "yaml" || ext == "yml");
+    let content = if is_yaml {
+        serde_yaml::to_string(&bundled.json)?
     } else {
-        // It's a file path
-        agenterra_core::openapi::OpenApiContext::from_file(schema_path)
-            .await
-            .map_err(|e| {
-                anyhow::anyhow!(
-                    "Failed to load OpenAPI schema: {}\nSee docs/CONFIGURATION.md#troubleshooting",
-                    e
-                )
-            })?
+        serde_json::to_string_pretty(&bundled.json)?
     };
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent).await?;
+    }
+    fs::write(path, content)
+        .await
+        .with_context(|| format!("Failed to write bundled spec to {}", path.display()))?;
+    println!("Wrote bundled spec to: {}", path.display());
+    Ok(bundled)
+}
 
-    // Create config with template
-    let config = agenterra_core::Config {
-        project_name: args.project_name.clone(),
-        openapi_schema_path: schema_path.to_string(),
-        output_dir: output_path.to_string_lossy().to_string(),
-        template_kind: args.template_kind.clone(),
-        template_dir: args
-            .template_dir
-            .as_ref()
-            .map(|p| p.to_string_lossy().to_string()),
-        include_all: true,
-        include_operations: Vec::new(),
-        exclude_operations: Vec::new(),
-        base_url: args.base_url.clone(),
-    };
+/// Load an OpenAPI spec from a local file, an HTTP(S) URL or stdin (`-`),
+/// reading at most `max_bytes` (32 MiB by default) from URLs and stdin
+async fn load_spec(schema_path: &str, max_bytes: Option<u64>) -> anyhow::Result<OpenApiContext> {
+    let source: SpecSource = schema_path.parse()?;
+    let max_bytes = max_bytes.unwrap_or(DEFAULT_MAX_SPEC_BYTES);
+    source.load_with_limit(max_bytes).await.with_context(|| {
+        format!(
+            "Failed to load OpenAPI schema from {}\nSee docs/CONFIGURATION.md#troubleshooting",
+            source
+        )
+    })
+}
 
-    // Create template options
-    let template_opts = TemplateOptions {
-        server_port: args.port,
-        log_file: args.log_file.clone(),
-        ..Default::default()
+/// Number of tags shown by `info` in text format
+const INFO_TOP_TAGS: usize = 10;
+
+/// Print an overview of the spec at `schema_path`
+async fn spec_info(schema_path: &str, format: OutputFormat) -> anyhow::Result<()> {
+    let summary = load_spec(schema_path, None).await?.summary();
+
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&summary)?),
+        OutputFormat::Text => {
+            println!("Title:       {}", summary.title.as_deref().unwrap_or("-"));
+            println!("Version:     {}", summary.version.as_deref().unwrap_or("-"));
+            if let Some(description) = &summary.description {
+                println!("Description: {}", description);
+            }
+            if !summary.servers.is_empty() {
+                println!("Servers:     {}", summary.servers.join(", "));
+            }
+            let by_method: Vec<String> = summary
+                .operations_by_method
+                .iter()
+                .map(|(method, count)| format!("{} {}", method, count))
+                .collect();
+            println!(
+                "Operations:  {} ({})",
+                summary.operations,
+                by_method.join(", ")
+            );
+            println!("Schemas:     {}", summary.component_schemas);
+            if !summary.tags.is_empty() {
+                let tags: Vec<String> = summary
+                    .tags
+                    .iter()
+                    .take(INFO_TOP_TAGS)
+                    .map(|tag| format!("{} ({})", tag.name, tag.operations))
+                    .collect();
+                println!("Top tags:    {}", tags.join(", "));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Print the operations agenterra picks up from a spec, flagging synthesized IDs
+async fn list_operations(schema_path: &str, format: OutputFormat) -> anyhow::Result<()> {
+    let spec = load_spec(schema_path, None).await?;
+    let explicit: std::collections::HashSet<(&str, &str)> = spec
+        .paths()
+        .filter(|(_, _, op)| op.get("operationId").is_some())
+        .map(|(path, method, _)| (path, method))
+        .collect();
+    let operations = spec.parse_operations().await?;
+
+    match format {
+        OutputFormat::Json => {
+            let listing: Vec<serde_json::Value> = operations
+                .iter()
+                .map(|op| {
+                    serde_json::json!({
+                        "method": op.method.to_uppercase(),
+                        "path": op.path,
+                        "operation_id": op.id,
+                        "synthesized": !explicit.contains(&(op.path.as_str(), op.method.as_str())),
+                    })
+                })
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&listing)?);
+        }
+        OutputFormat::Text => {
+            for op in &operations {
+                let synthesized = !explicit.contains(&(op.path.as_str(), op.method.as_str()));
+                println!(
+                    "{:<7} {:<40} {}{}",
+                    op.method.to_uppercase(),
+                    op.path,
+                    op.id,
+                    if synthesized { " (synthesized)" } else { "" }
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Regenerate only the operations that changed since the previous spec,
+/// falling back to a full scaffold on structural changes
+async fn regenerate_changed(
+    args: &ScaffoldArgs,
+    previous: &mut Option<OpenApiContext>,
+) -> anyhow::Result<()> {
+    let spec = args.spec().await?;
+    let changed = match previous.as_ref() {
+        Some(prev) => {
+            let strategy = args.template_options()?.operation_id_strategy;
+            spec.changed_operations_with(prev, strategy).await?
+        }
+        None => None,
     };
 
-    // Generate the server using the template manager
-    template_manager
-        .generate(&schema_obj, &config, Some(template_opts))
-        .await?;
+    match changed {
+        Some(ids) if ids.is_empty() => println!("No operation changes detected."),
+        Some(ids) => {
+            println!("Regenerating changed operations: {}", ids.join(", "));
+            let template_manager =
+                TemplateManager::new(args.template_kind()?, args.template_dir.clone())
+                    .await
+                    .context("Failed to initialize template manager")?;
+            template_manager
+                .generate_operations(&spec, &args.config(), Some(args.template_options()?), &ids)
+                .await?;
+        }
+        None => run_scaffold(args).await?,
+    }
 
-    println!(
-        "✅ Successfully generated server in: {}",
-        output_path.display()
-    );
+    *previous = Some(spec);
     Ok(())
 }
 
 async fn watch_and_scaffold(args: ScaffoldArgs) -> anyhow::Result<()> {
-    if args.schema_path.starts_with("http://") || args.schema_path.starts_with("https://") {
+    if !matches!(args.schema_path.parse()?, SpecSource::File(_)) {
         println!("--watch is only supported for local schema files");
         return run_scaffold(&args).await;
     }
@@ -232,21 +701,42 @@ async fn watch_and_scaffold(args: ScaffoldArgs) -> anyhow::Result<()> {
         let _ = tx.blocking_send(res);
     })?;
     watcher.watch(Path::new(&schema), RecursiveMode::NonRecursive)?;
+    // Template edits are picked up too; the manager reloads changed templates
+    let template_dir = match &args.template_dir {
+        Some(dir) => {
+            let dir = dir.canonicalize()?;
+            watcher.watch(&dir, RecursiveMode::Recursive)?;
+            Some(dir)
+        }
+        None => None,
+    };
 
     run_scaffold(&args).await?;
     println!("Watching {} for changes...", schema);
 
+    // Last successfully loaded spec, used to detect per-operation changes
+    let mut previous = if args.only_changed {
+        OpenApiContext::from_file(&schema).await.ok()
+    } else {
+        None
+    };
+
     while let Some(res) = rx.recv().await {
         match res {
-            Ok(_event) => {
+            Ok(event) => {
                 println!("Change detected. Regenerating...");
-                if let Err(e) = run_scaffold(&args).await {
+                let template_changed = template_dir
+                    .as_ref()
+                    .is_some_and(|dir| event.paths.iter().any(|p| p.starts_with(dir)));
+                let result = if args.only_changed && !template_changed {
+                    regenerate_changed(&args, &mut previous).await
+                } else {
+                    run_scaffold(&args).await
+                };
+                if let Err(e) = result {
                     eprintln!("Generation failed: {e:#}");
                 }
-                let output_dir = args
-                    .output_dir
-                    .clone()
-                    .unwrap_or_else(|| PathBuf::from(&args.project_name));
+                let output_dir = args.output_path();
                 let build = tokio::process::Command::new("cargo")
                     .arg("check")
                     .current_dir(&output_dir)
@@ -264,11 +754,75 @@ async fn watch_and_scaffold(args: ScaffoldArgs) -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Treat a template kind agenterra doesn't know as a custom template when a
+/// template directory is given, so arbitrary template names work with an
+/// explicit directory. The template is `<dir>/<kind>` if that exists, else
+/// `<dir>` itself.
+fn unknown_kind_as_custom(
+    template_kind: &str,
+    template_dir: Option<&PathBuf>,
+) -> (String, Option<PathBuf>) {
+    match template_dir {
+        Some(dir) if template_kind.parse::<TemplateKind>().is_err() => {
+            let named = dir.join(template_kind);
+            let dir = if named.is_dir() { named } else { dir.clone() };
+            eprintln!(
+                "Unknown template kind '{}', using {} as a custom template",
+                template_kind,
+                dir.display()
+            );
+            (TemplateKind::Custom.as_str().to_string(), Some(dir))
+        }
+        _ => (template_kind.to_string(), template_dir.cloned()),
+    }
+}
+
+/// Colors and emoji only go to a terminal, and never when `--no-color` or a
+/// non-empty `NO_COLOR` (<https://no-color.org>) asks for plain output
+fn color_enabled(no_color: bool) -> bool {
+    !no_color
+        && std::env::var_os("NO_COLOR").is_none_or(|value| value.is_empty())
+        && std::io::stdout().is_terminal()
+}
+
+/// `fancy` when output is colored, `plain` otherwise
+fn decoration<'a>(fancy: &'a str, plain: &'a str) -> &'a str {
+    if COLOR.get().copied().unwrap_or(false) {
+        fancy
+    } else {
+        plain
+    }
+}
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-    // Initialize logging
-    tracing_subscriber::fmt::init();
     let cli = Cli::parse();
+    let color = *COLOR.get_or_init(|| color_enabled(cli.no_color));
+
+    // Initialize logging
+    let log_file = match &cli.log_to {
+        Some(path) => {
+            let file = std::fs::File::create(path)
+                .with_context(|| format!("Failed to create log file {}", path.display()))?;
+            let filter = EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_| EnvFilter::new(DEFAULT_LOG_FILE_FILTER));
+            Some(
+                fmt::layer()
+                    .with_ansi(false)
+                    .with_writer(Arc::new(file))
+                    .with_filter(filter),
+            )
+        }
+        None => None,
+    };
+    tracing_subscriber::registry()
+        .with(
+            fmt::layer()
+                .with_ansi(color)
+                .with_filter(EnvFilter::from_default_env()),
+        )
+        .with(log_file)
+        .init();
     match &cli.command {
         Commands::Scaffold {
             project_name,
@@ -279,38 +833,105 @@ async fn main() -> anyhow::Result<()> {
             log_file,
             port,
             base_url,
+            server_index,
             watch,
+            only_changed,
+            append_only,
+            header_file,
+            agent_instructions,
+            agent_instructions_file,
+            with_dockerfile,
+            with_env_example,
+            emit_mcp_descriptor,
+            emit_operation_fragments,
+            workspace,
+            from_postman,
+            from_graphql,
+            tools_file,
+            include_paths,
+            exclude_paths,
+            only_file,
+            dump_context,
+            fail_on_unmapped_type,
+            strip_vendor_extensions,
+            minify_schemas,
+            max_concurrency,
+            strict,
+            force,
+            lint_output,
+            emit_bundled_spec,
+            spec_patch,
+            max_spec_bytes,
         } => {
+            let (template_kind, template_dir) =
+                unknown_kind_as_custom(template_kind, template_dir.as_ref());
             let args = ScaffoldArgs {
                 project_name: project_name.clone(),
-                schema_path: schema_path.clone(),
-                template_kind: template_kind.clone(),
-                template_dir: template_dir.clone(),
+                schema_path: match tools_file {
+                    Some(path) => path.display().to_string(),
+                    None => schema_path.clone().unwrap_or_default(),
+                },
+                template_kind,
+                template_dir,
                 output_dir: output_dir.clone(),
                 log_file: log_file.clone(),
                 port: *port,
                 base_url: base_url.clone(),
+                server_index: *server_index,
                 watch: *watch,
+                only_changed: *only_changed,
+                append_only: *append_only,
+                header_file: header_file.clone(),
+                agent_instructions: agent_instructions.clone(),
+                agent_instructions_file: agent_instructions_file.clone(),
+                with_dockerfile: *with_dockerfile,
+                with_env_example: *with_env_example,
+                emit_mcp_descriptor: *emit_mcp_descriptor,
+                emit_operation_fragments: *emit_operation_fragments,
+                workspace: *workspace,
+                from_postman: *from_postman,
+                from_graphql: *from_graphql,
+                from_tools_file: tools_file.is_some(),
+                include_paths: include_paths.clone(),
+                exclude_paths: exclude_paths.clone(),
+                only_file: only_file.clone(),
+                dump_context: dump_context.clone(),
+                fail_on_unmapped_type: *fail_on_unmapped_type,
+                strip_vendor_extensions: *strip_vendor_extensions,
+                minify_schemas: *minify_schemas,
+                max_concurrency: *max_concurrency,
+                strict: *strict,
+                force: *force,
+                lint_output: *lint_output,
+                emit_bundled_spec: emit_bundled_spec.clone(),
+                spec_patch: spec_patch.clone(),
+                max_spec_bytes: *max_spec_bytes,
             };
-            if args.watch {
+            if let Some(operation_id) = &args.dump_context {
+                dump_operation_context(&args, operation_id).await?;
+            } else if args.watch {
                 watch_and_scaffold(args).await?;
             } else {
                 run_scaffold(&args).await?;
             }
         }
         Commands::Init => {
-            let theme = ColorfulTheme::default();
-            let project_name: String = Input::with_theme(&theme)
+            let theme: Box<dyn Theme> = if color {
+                Box::new(ColorfulTheme::default())
+            } else {
+                Box::new(SimpleTheme)
+            };
+            let theme = theme.as_ref();
+            let project_name: String = Input::with_theme(theme)
                 .with_prompt("Project name")
-                .default("agenterra_mcp_server".into())
+                .default(DEFAULT_PROJECT_NAME.into())
                 .interact_text()?;
 
-            let schema_path: String = Input::with_theme(&theme)
+            let schema_path: String = Input::with_theme(theme)
                 .with_prompt("Path or URL to OpenAPI schema")
                 .default("tests/fixtures/openapi/petstore.openapi.v3.json".into())
                 .interact_text()?;
-            if !schema_path.starts_with("http://")
-                && !schema_path.starts_with("https://")
+            if matches!(schema_path.parse()?, SpecSource::File(_))
                 && tokio::fs::metadata(&schema_path).await.is_err()
             {
                 return Err(anyhow::anyhow!(
@@ -322,7 +943,7 @@ async fn main() -> anyhow::Result<()> {
             let templates: Vec<String> = TemplateKind::all()
                 .map(|k| k.as_str().to_string())
                 .collect();
-            let selection = Select::with_theme(&theme)
+            let selection = Select::with_theme(theme)
                 .with_prompt("Template kind")
                 .items(&templates)
                 .default(0)
@@ -330,21 +951,17 @@ async fn main() -> anyhow::Result<()> {
             let template_kind = templates[selection].clone();
 
             let default_output = project_name.clone();
-            let output_dir_str: String = Input::with_theme(&theme)
+            let output_dir_str: String = Input::with_theme(theme)
                 .with_prompt("Output directory")
                 .default(default_output)
                 .interact_text()?;
 
             let args = ScaffoldArgs {
-                project_name,
+                project_name: Some(project_name),
                 schema_path,
                 template_kind,
-                template_dir: None,
                 output_dir: Some(PathBuf::from(output_dir_str)),
-                log_file: None,
-                port: None,
-                base_url: None,
-                watch: false,
+                ..Default::default()
             };
             if args.watch {
                 watch_and_scaffold(args).await?;
@@ -358,6 +975,48 @@ async fn main() -> anyhow::Result<()> {
                 println!("- {}", kind.as_str());
             }
         }
+        Commands::Info {
+            schema_path,
+            format,
+        } => {
+            spec_info(schema_path, *format).await?;
+        }
+        Commands::ListOperations {
+            schema_path,
+            format,
+        } => {
+            list_operations(schema_path, *format).await?;
+        }
+        Commands::Upgrade {
+            project_dir,
+            template_dir,
+            output,
+        } => {
+            let report = agenterra_core::upgrade(project_dir, template_dir.as_deref())
+                .await
+                .with_context(|| format!("Failed to upgrade {}", project_dir.display()))?;
+            match output {
+                Some(path) => fs::write(path, &report.patch)
+                    .await
+                    .with_context(|| format!("Failed to write patch to {}", path.display()))?,
+                None => print!("{}", report.patch),
+            }
+            for path in &report.edited_files {
+                eprintln!(
+                    "{} was edited since it was generated; check its hunks apply",
+                    path.display()
+                );
+            }
+            for path in &report.removed_files {
+                eprintln!(
+                    "{} is no longer generated by the template; remove it if unused",
+                    path.display()
+                );
+            }
+            if report.is_up_to_date() {
+                eprintln!("{} is up to date", project_dir.display());
+            }
+        }
     }
     Ok(())
 }
@@ -170,6 +170,612 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_project_name_overrides_spec_title() -> Result<()> {
+        cleanup_env_vars();
+        let ctx = TestContext::new()?;
+
+        let build_status = Command::new("cargo")
+            .args(["build"])
+            .status()
+            .context("Failed to build agenterra CLI")?;
+        if !build_status.success() {
+            bail!("Failed to build agenterra CLI (status: {})", build_status);
+        }
+
+        let schema_path =
+            get_test_openapi_schema_path("tests/fixtures/openapi/petstore.openapi.v3.json");
+        let output_dir = ctx.output_dir.join("rust_axum_custom_project_name");
+        if output_dir.exists() {
+            std::fs::remove_dir_all(&output_dir)?;
+        }
+
+        let mut cmd = ctx.build_command()?;
+        cmd.args(["scaffold", "--project-name", "custom_name", "--schema-path"])
+            .arg(&schema_path)
+            .arg("--output-dir")
+            .arg(&output_dir)
+            .arg("--template-dir")
+            .arg(ctx.workspace_root.join("templates"))
+            .args(["--base-url", "https://petstore3.swagger.io"]);
+        let output = cmd.output()?;
+
+        if !output.status.success() {
+            bail!(
+                "scaffold command failed: {}",
+                String::from_utf8_lossy(&output.stderr)
+            );
+        }
+
+        let cargo_toml = std::fs::read_to_string(output_dir.join("Cargo.toml"))?;
+        assert!(
+            cargo_toml.contains("name = \"custom_name\""),
+            "Cargo.toml should use the --project-name value:\n{}",
+            cargo_toml
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_info() -> Result<()> {
+        cleanup_env_vars();
+        let ctx = TestContext::new()?;
+
+        let build_status = Command::new("cargo")
+            .args(["build"])
+            .status()
+            .context("Failed to build agenterra CLI")?;
+        if !build_status.success() {
+            bail!("Failed to build agenterra CLI (status: {})", build_status);
+        }
+
+        let schema_path =
+            get_test_openapi_schema_path("tests/fixtures/openapi/petstore.openapi.v3.json");
+        let output = ctx
+            .build_command()?
+            .args(["info", "--schema-path", &schema_path])
+            .output()?;
+        if !output.status.success() {
+            bail!(
+                "info command failed: {}",
+                String::from_utf8_lossy(&output.stderr)
+            );
+        }
+        let text = String::from_utf8_lossy(&output.stdout);
+        assert!(text.contains("Swagger Petstore - OpenAPI 3.0"));
+        assert!(text.contains("Operations:  19"));
+
+        let output = ctx
+            .build_command()?
+            .args(["info", "--schema-path", &schema_path, "--format", "json"])
+            .output()?;
+        let summary: serde_json::Value = serde_json::from_slice(&output.stdout)
+            .context("info --format json did not print JSON")?;
+        assert_eq!(summary["title"], "Swagger Petstore - OpenAPI 3.0");
+        assert_eq!(summary["operations"], 19);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_list_operations() -> Result<()> {
+        cleanup_env_vars();
+        let ctx = TestContext::new()?;
+
+        let build_status = Command::new("cargo")
+            .args(["build"])
+            .status()
+            .context("Failed to build agenterra CLI")?;
+        if !build_status.success() {
+            bail!("Failed to build agenterra CLI (status: {})", build_status);
+        }
+
+        let schema_path =
+            get_test_openapi_schema_path("tests/fixtures/openapi/petstore.openapi.v3.json");
+        let mut cmd = ctx.build_command()?;
+        cmd.args([
+            "list-operations",
+            "--schema-path",
+            &schema_path,
+            "--format",
+            "json",
+        ]);
+        let output = cmd.output()?;
+
+        if !output.status.success() {
+            bail!(
+                "list-operations command failed: {}",
+                String::from_utf8_lossy(&output.stderr)
+            );
+        }
+
+        let listing: serde_json::Value = serde_json::from_slice(&output.stdout)
+            .context("list-operations --format json did not print JSON")?;
+        let operations = listing.as_array().context("Expected a JSON array")?;
+        let find = |id: &str| {
+            operations
+                .iter()
+                .find(|op| op["operation_id"] == id)
+                .cloned()
+        };
+
+        let find_pets = find("findPetsByStatus").context("findPetsByStatus not listed")?;
+        assert_eq!(find_pets["method"], "GET");
+        assert_eq!(find_pets["path"], "/pet/findByStatus");
+        assert_eq!(find_pets["synthesized"], false);
+        assert!(find("addPet").is_some());
+        assert!(find("getInventory").is_some());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_dump_context() -> Result<()> {
+        cleanup_env_vars();
+        let ctx = TestContext::new()?;
+
+        let build_status = Command::new("cargo")
+            .args(["build"])
+            .status()
+            .context("Failed to build agenterra CLI")?;
+        if !build_status.success() {
+            bail!("Failed to build agenterra CLI (status: {})", build_status);
+        }
+
+        let schema_path =
+            get_test_openapi_schema_path("tests/fixtures/openapi/petstore.openapi.v3.json");
+        let mut cmd = ctx.build_command()?;
+        cmd.args(["scaffold", "--schema-path", &schema_path])
+            .arg("--template-dir")
+            .arg(ctx.workspace_root.join("templates"))
+            .args(["--base-url", "https://petstore3.swagger.io"])
+            .args(["--dump-context", "getPetById"]);
+        let output = cmd.output()?;
+
+        if !output.status.success() {
+            bail!(
+                "scaffold --dump-context failed: {}",
+                String::from_utf8_lossy(&output.stderr)
+            );
+        }
+
+        let context: serde_json::Value =
+            serde_json::from_slice(&output.stdout).context("--dump-context did not print JSON")?;
+        assert_eq!(context["fn_name"], "get_pet_by_id");
+        assert_eq!(context["path_params"], serde_json::json!(["petId"]));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_agent_instructions_file() -> Result<()> {
+        cleanup_env_vars();
+        let ctx = TestContext::new()?;
+
+        let build_status = Command::new("cargo")
+            .args(["build"])
+            .status()
+            .context("Failed to build agenterra CLI")?;
+        if !build_status.success() {
+            bail!("Failed to build agenterra CLI (status: {})", build_status);
+        }
+
+        let instructions = "Always look pets up by ID before updating them.\n";
+        let temp_dir = tempfile::tempdir()?;
+        let instructions_path = temp_dir.path().join("instructions.md");
+        std::fs::write(&instructions_path, instructions)?;
+
+        let schema_path =
+            get_test_openapi_schema_path("tests/fixtures/openapi/petstore.openapi.v3.json");
+        let mut cmd = ctx.build_command()?;
+        cmd.args(["scaffold", "--schema-path", &schema_path])
+            .arg("--template-dir")
+            .arg(ctx.workspace_root.join("templates"))
+            .args(["--base-url", "https://petstore3.swagger.io"])
+            .arg("--agent-instructions-file")
+            .arg(&instructions_path)
+            .args(["--dump-context", "getPetById"]);
+        let output = cmd.output()?;
+
+        if !output.status.success() {
+            bail!(
+                "scaffold --agent-instructions-file failed: {}",
+                String::from_utf8_lossy(&output.stderr)
+            );
+        }
+
+        let context: serde_json::Value =
+            serde_json::from_slice(&output.stdout).context("--dump-context did not print JSON")?;
+        assert_eq!(context["agent_instructions"], instructions);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_unknown_template_kind_with_dir_is_custom() -> Result<()> {
+        cleanup_env_vars();
+        let ctx = TestContext::new()?;
+
+        let build_status = Command::new("cargo")
+            .args(["build"])
+            .status()
+            .context("Failed to build agenterra CLI")?;
+        if !build_status.success() {
+            bail!("Failed to build agenterra CLI (status: {})", build_status);
+        }
+
+        let temp_dir = tempfile::tempdir()?;
+        let template_dir = temp_dir.path().join("templates/graphql");
+        std::fs::create_dir_all(&template_dir)?;
+        std::fs::write(
+            template_dir.join("manifest.yaml"),
+            "name: graphql\ndescription: GraphQL server\nversion: \"0.1.0\"\nlanguage: graphql\nfiles:\n  - source: schema.tera\n    destination: schema.graphql\n  - source: resolver.tera\n    destination: \"resolvers/{endpoint}.graphql\"\n    for_each: operation\n",
+        )?;
+        std::fs::write(template_dir.join("schema.tera"), "# {{ project_name }}\n")?;
+        std::fs::write(
+            template_dir.join("resolver.tera"),
+            "# {{ type_name }}: {{ method | upper }} {{ path }}\n",
+        )?;
+
+        let schema_path =
+            get_test_openapi_schema_path("tests/fixtures/openapi/petstore.openapi.v3.json");
+        let output_dir = temp_dir.path().join("out");
+        let mut cmd = ctx.build_command()?;
+        cmd.args(["scaffold", "--project-name", "pets", "--schema-path"])
+            .arg(&schema_path)
+            .args(["--template-kind", "graphql"])
+            .arg("--template-dir")
+            .arg(temp_dir.path().join("templates"))
+            .arg("--output-dir")
+            .arg(&output_dir)
+            .args(["--base-url", "https://petstore3.swagger.io"]);
+        let output = cmd.output()?;
+
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(
+            stderr.contains(&format!(
+                "using {} as a custom template",
+                template_dir.display()
+            )),
+            "unknown kind was not treated as custom:\n{}",
+            stderr
+        );
+        assert!(!stderr.contains("Invalid template"), "{}", stderr);
+        assert!(output.status.success(), "{}", stderr);
+        assert_eq!(
+            std::fs::read_to_string(output_dir.join("schema.graphql"))?,
+            "# pets\n"
+        );
+        assert_eq!(
+            std::fs::read_to_string(output_dir.join("resolvers/get_pet_by_id.graphql"))?,
+            "# GetPetById: GET /pet/{petId}\n"
+        );
+
+        // Without a directory an unknown kind is still an error
+        let mut cmd = ctx.build_command()?;
+        cmd.args(["scaffold", "--schema-path", &schema_path])
+            .args(["--template-kind", "graphql"])
+            .arg("--output-dir")
+            .arg(&output_dir);
+        let output = cmd.output()?;
+        assert!(!output.status.success());
+        assert!(String::from_utf8_lossy(&output.stderr).contains("Invalid template 'graphql'"));
+
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_read_only_output_dir_fails_early() -> Result<()> {
+        use std::os::unix::fs::PermissionsExt;
+
+        cleanup_env_vars();
+        let ctx = TestContext::new()?;
+
+        let build_status = Command::new("cargo")
+            .args(["build"])
+            .status()
+            .context("Failed to build agenterra CLI")?;
+        if !build_status.success() {
+            bail!("Failed to build agenterra CLI (status: {})", build_status);
+        }
+
+        let temp_dir = tempfile::tempdir()?;
+        let output_dir = temp_dir.path().join("read-only");
+        std::fs::create_dir(&output_dir)?;
+        std::fs::set_permissions(&output_dir, std::fs::Permissions::from_mode(0o555))?;
+        // Privileged users can write regardless of permissions
+        if std::fs::write(output_dir.join("probe"), "").is_ok() {
+            return Ok(());
+        }
+
+        let schema_path =
+            get_test_openapi_schema_path("tests/fixtures/openapi/petstore.openapi.v3.json");
+        let mut cmd = ctx.build_command()?;
+        cmd.args(["scaffold", "--schema-path", &schema_path])
+            .arg("--template-dir")
+            .arg(ctx.workspace_root.join("templates"))
+            .arg("--output-dir")
+            .arg(&output_dir)
+            .args(["--base-url", "https://petstore3.swagger.io"]);
+        let output = cmd.output()?;
+        std::fs::set_permissions(&output_dir, std::fs::Permissions::from_mode(0o755))?;
+
+        assert!(!output.status.success());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(
+            stderr.contains(&format!(
+                "Output directory {} is not writable",
+                output_dir.display()
+            )),
+            "{}",
+            stderr
+        );
+        // The spec is never loaded
+        assert!(!String::from_utf8_lossy(&output.stdout).contains("Loading OpenAPI schema"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_redirected_output_has_no_ansi_escapes() -> Result<()> {
+        cleanup_env_vars();
+        let ctx = TestContext::new()?;
+
+        let build_status = Command::new("cargo")
+            .args(["build"])
+            .status()
+            .context("Failed to build agenterra CLI")?;
+        if !build_status.success() {
+            bail!("Failed to build agenterra CLI (status: {})", build_status);
+        }
+
+        let schema_path =
+            get_test_openapi_schema_path("tests/fixtures/openapi/petstore.openapi.v3.json");
+        // Piped output is never a terminal; NO_COLOR must also win on its own
+        for no_color in [None, Some("1")] {
+            let temp_dir = tempfile::tempdir()?;
+            let mut cmd = ctx.build_command()?;
+            cmd.args(["scaffold", "--schema-path", &schema_path])
+                .arg("--template-dir")
+                .arg(ctx.workspace_root.join("templates"))
+                .arg("--output-dir")
+                .arg(temp_dir.path().join("out"))
+                .args(["--base-url", "https://petstore3.swagger.io"])
+                .env("RUST_LOG", "debug");
+            match no_color {
+                Some(value) => cmd.env("NO_COLOR", value),
+                None => cmd.env_remove("NO_COLOR"),
+            };
+            let output = cmd.output()?;
+
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            assert!(output.status.success(), "{}", stderr);
+            assert!(stdout.contains("Successfully generated server in"));
+            for text in [&stdout, &stderr] {
+                assert!(!text.contains('\x1b'), "ANSI escape in output:\n{}", text);
+                assert!(!text.contains('✅'), "{}", text);
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_log_to_writes_log_file() -> Result<()> {
+        cleanup_env_vars();
+        let ctx = TestContext::new()?;
+
+        let build_status = Command::new("cargo")
+            .args(["build"])
+            .status()
+            .context("Failed to build agenterra CLI")?;
+        if !build_status.success() {
+            bail!("Failed to build agenterra CLI (status: {})", build_status);
+        }
+
+        let temp_dir = tempfile::tempdir()?;
+        let log_path = temp_dir.path().join("agenterra.log");
+        let schema_path =
+            get_test_openapi_schema_path("tests/fixtures/openapi/petstore.openapi.v3.json");
+        let output = ctx
+            .build_command()?
+            .arg("--log-to")
+            .arg(&log_path)
+            .args(["scaffold", "--schema-path", &schema_path])
+            .arg("--template-dir")
+            .arg(ctx.workspace_root.join("templates"))
+            .arg("--output-dir")
+            .arg(temp_dir.path().join("out"))
+            .args(["--base-url", "https://petstore3.swagger.io"])
+            .env_remove("RUST_LOG")
+            .output()?;
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+
+        let log = std::fs::read_to_string(&log_path)?;
+        assert!(log.contains("INFO"), "{}", log);
+        assert!(log.contains("Starting generation"), "{}", log);
+        assert!(log.contains("template=\"rust_axum\""), "{}", log);
+        assert!(!log.contains('\x1b'), "{}", log);
+        // The console still only shows what RUST_LOG asks for
+        assert!(!String::from_utf8_lossy(&output.stdout).contains("Starting generation"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_spec_patch_injects_server() -> Result<()> {
+        cleanup_env_vars();
+        let ctx = TestContext::new()?;
+
+        let build_status = Command::new("cargo")
+            .args(["build"])
+            .status()
+            .context("Failed to build agenterra CLI")?;
+        if !build_status.success() {
+            bail!("Failed to build agenterra CLI (status: {})", build_status);
+        }
+
+        // The spec declares no servers, which would otherwise need --base-url
+        let temp_dir = tempfile::tempdir()?;
+        let schema_path =
+            get_test_openapi_schema_path("tests/fixtures/openapi/petstore.openapi.v3.json");
+        let mut spec: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&schema_path)?)?;
+        spec.as_object_mut().unwrap().remove("servers");
+        let spec_path = temp_dir.path().join("openapi.json");
+        std::fs::write(&spec_path, serde_json::to_string(&spec)?)?;
+        let patch_path = temp_dir.path().join("patch.json");
+        std::fs::write(
+            &patch_path,
+            r#"{"servers": [{"url": "https://patched.example.com/api"}]}"#,
+        )?;
+
+        let output_dir = temp_dir.path().join("out");
+        let mut cmd = ctx.build_command()?;
+        cmd.arg("scaffold")
+            .arg("--schema-path")
+            .arg(&spec_path)
+            .arg("--template-dir")
+            .arg(ctx.workspace_root.join("templates"))
+            .arg("--output-dir")
+            .arg(&output_dir)
+            .arg("--spec-patch")
+            .arg(&patch_path);
+        let output = cmd.output()?;
+
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        let config = std::fs::read_to_string(output_dir.join("src/config.rs"))?;
+        assert!(
+            config.contains("https://patched.example.com/api"),
+            "{}",
+            config
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_schema_path_dash_reads_stdin() -> Result<()> {
+        cleanup_env_vars();
+        let ctx = TestContext::new()?;
+
+        let build_status = Command::new("cargo")
+            .args(["build"])
+            .status()
+            .context("Failed to build agenterra CLI")?;
+        if !build_status.success() {
+            bail!("Failed to build agenterra CLI (status: {})", build_status);
+        }
+
+        let temp_dir = tempfile::tempdir()?;
+        let schema_path =
+            get_test_openapi_schema_path("tests/fixtures/openapi/petstore.openapi.v3.json");
+        let output_dir = temp_dir.path().join("out");
+        let mut child = ctx
+            .build_command()?
+            .args(["scaffold", "--schema-path", "-"])
+            .arg("--template-dir")
+            .arg(ctx.workspace_root.join("templates"))
+            .arg("--output-dir")
+            .arg(&output_dir)
+            .args(["--base-url", "https://petstore3.swagger.io"])
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped())
+            .spawn()?;
+        {
+            use std::io::Write;
+            let mut stdin = child.stdin.take().context("stdin not piped")?;
+            stdin.write_all(std::fs::read_to_string(&schema_path)?.as_bytes())?;
+        }
+        let output = child.wait_with_output()?;
+
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        assert!(output_dir
+            .join("src/handlers/find_pets_by_status.rs")
+            .is_file());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_json_patch_adds_operation() -> Result<()> {
+        cleanup_env_vars();
+        let ctx = TestContext::new()?;
+
+        let build_status = Command::new("cargo")
+            .args(["build"])
+            .status()
+            .context("Failed to build agenterra CLI")?;
+        if !build_status.success() {
+            bail!("Failed to build agenterra CLI (status: {})", build_status);
+        }
+
+        let temp_dir = tempfile::tempdir()?;
+        let schema_path =
+            get_test_openapi_schema_path("tests/fixtures/openapi/petstore.openapi.v3.json");
+        let scaffold = |patch: &str| -> Result<std::process::Output> {
+            let patch_path = temp_dir.path().join("patch.json");
+            std::fs::write(&patch_path, patch)?;
+            let mut cmd = ctx.build_command()?;
+            cmd.args(["scaffold", "--schema-path", &schema_path])
+                .arg("--template-dir")
+                .arg(ctx.workspace_root.join("templates"))
+                .arg("--output-dir")
+                .arg(temp_dir.path().join("out"))
+                .args(["--base-url", "https://petstore3.swagger.io"])
+                .arg("--spec-patch")
+                .arg(&patch_path);
+            Ok(cmd.output()?)
+        };
+
+        let output = scaffold(
+            r##"[{"op": "add", "path": "/paths/~1owners", "value": {"get": {
+                "operationId": "listOwners",
+                "summary": "List owners",
+                "tags": ["owner"],
+                "responses": {"200": {"description": "All owners", "content": {
+                    "application/json": {"schema": {"$ref": "#/components/schemas/Pet"}}
+                }}}
+            }}}]"##,
+        )?;
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        let handlers = temp_dir.path().join("out/src/handlers");
+        assert!(handlers.join("list_owners.rs").exists());
+        let handlers_mod = std::fs::read_to_string(handlers.join("mod.rs"))?;
+        assert!(handlers_mod.contains("list_owners"), "{}", handlers_mod);
+
+        let output = scaffold(r#"[{"op": "remove", "path": "/paths/~1missing"}]"#)?;
+        assert!(!output.status.success());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(
+            stderr.contains("JSON Patch operation 0 (remove /paths/~1missing) failed"),
+            "{}",
+            stderr
+        );
+
+        Ok(())
+    }
+
     // Helper function to clean up environment variables after test
     fn cleanup_env_vars() {
         let env_vars = [
@@ -0,0 +1,63 @@
+//! Language-agnostic endpoint context builder for custom templates.
+
+use super::EndpointContextBuilder;
+use crate::openapi::OpenApiOperation;
+use crate::utils::{to_snake_case, to_upper_camel_case};
+use serde_json::{json, Value as JsonValue};
+
+/// Passes the operation through as-is (`operationId`, `method`, `path`,
+/// `parameters`, `requestBody`, `responses`, ...) with a few normalized names
+/// added, leaving type mapping to the template:
+///
+/// - `endpoint`, `endpoint_fs` and `fn_name`: the operationId in snake_case
+/// - `type_name`: the operationId in PascalCase
+#[derive(Debug, Clone)]
+pub struct GenericEndpointContextBuilder;
+
+impl EndpointContextBuilder for GenericEndpointContextBuilder {
+    fn build(&self, op: &OpenApiOperation) -> crate::Result<JsonValue> {
+        let mut context = serde_json::to_value(op)?;
+        let snake = to_snake_case(&op.id);
+        if let Some(map) = context.as_object_mut() {
+            map.insert("endpoint".to_string(), json!(snake));
+            map.insert("endpoint_fs".to_string(), json!(snake));
+            map.insert("fn_name".to_string(), json!(snake));
+            map.insert("type_name".to_string(), json!(to_upper_camel_case(&op.id)));
+        }
+        Ok(context)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_operation_passes_through_with_normalized_names() -> crate::Result<()> {
+        let op: OpenApiOperation = serde_json::from_value(json!({
+            "operationId": "getPetById",
+            "method": "get",
+            "path": "/pets/{petId}",
+            "parameters": [
+                {"name": "petId", "in": "path", "required": true, "schema": {"type": "integer"}}
+            ],
+            "responses": {"200": {"description": "ok"}}
+        }))?;
+        let context = GenericEndpointContextBuilder.build(&op)?;
+
+        assert_eq!(context["operationId"], "getPetById");
+        assert_eq!(context["method"], "get");
+        assert_eq!(context["path"], "/pets/{petId}");
+        assert_eq!(
+            context["parameters"][0]["schema"],
+            json!({"type": "integer"})
+        );
+        assert_eq!(context["endpoint"], "get_pet_by_id");
+        assert_eq!(context["fn_name"], "get_pet_by_id");
+        assert_eq!(context["type_name"], "GetPetById");
+        // No language-specific type mapping
+        assert!(context.get("target_type").is_none());
+        assert!(context["parameters"][0].get("target_type").is_none());
+        Ok(())
+    }
+}
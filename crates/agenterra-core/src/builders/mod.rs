@@ -1,13 +1,55 @@
 //! Context builder traits and adapters for language-specific codegen.
+pub mod generic;
 pub mod rust;
 
-use crate::openapi::OpenApiOperation;
-use crate::templates::TemplateKind;
+use std::fmt;
+
+use crate::openapi::{OpenApiContext, OpenApiOperation};
+use crate::templates::{TemplateKind, TemplateOptions};
+use serde::Serialize;
 use serde_json::Value as JsonValue;
 
 /// Trait for converting an OpenApiOperation into a language-specific context.
 pub trait EndpointContextBuilder {
     fn build(&self, op: &OpenApiOperation) -> crate::Result<JsonValue>;
+
+    /// [`build`](Self::build) with the whole spec at hand, for builders that
+    /// resolve `$ref`s in the operation against it
+    fn build_with_spec(
+        &self,
+        op: &OpenApiOperation,
+        _spec: &OpenApiContext,
+    ) -> crate::Result<JsonValue> {
+        self.build(op)
+    }
+
+    /// Schemas in `op` that [`build`](Self::build) could only map to a catch-all type
+    fn unmapped_types(&self, _op: &OpenApiOperation) -> Vec<UnmappedType> {
+        Vec::new()
+    }
+}
+
+/// A parameter or response field whose schema fell back to a catch-all type
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct UnmappedType {
+    /// ID of the operation the field belongs to
+    pub operation: String,
+    /// Parameter or response property name
+    pub field: String,
+    /// What about the schema couldn't be mapped, e.g. "oneOf schema"
+    pub reason: String,
+    /// Type generated instead
+    pub fallback: String,
+}
+
+impl fmt::Display for UnmappedType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}.{}: {} (falls back to {})",
+            self.operation, self.field, self.reason, self.fallback
+        )
+    }
 }
 
 pub struct EndpointContext;
@@ -18,11 +60,23 @@ impl EndpointContext {
     pub fn transform_endpoints(
         template: TemplateKind,
         operations: Vec<OpenApiOperation>,
+        spec: &OpenApiContext,
+    ) -> crate::Result<Vec<JsonValue>> {
+        Self::transform_endpoints_with(template, operations, spec, None)
+    }
+
+    /// Like [`transform_endpoints`](Self::transform_endpoints), with the
+    /// builder configured from `options`
+    pub fn transform_endpoints_with(
+        template: TemplateKind,
+        operations: Vec<OpenApiOperation>,
+        spec: &OpenApiContext,
+        options: Option<&TemplateOptions>,
     ) -> crate::Result<Vec<JsonValue>> {
-        let builder = Self::get_builder(template)?;
+        let builder = Self::get_builder_with(template, options)?;
         let mut contexts = Vec::new();
         for op in operations {
-            contexts.push(builder.build(&op)?);
+            contexts.push(builder.build_with_spec(&op, spec)?);
         }
 
         // Sort endpoints alphabetically by endpoint name for consistent output
@@ -36,8 +90,22 @@ impl EndpointContext {
     }
 
     pub fn get_builder(template: TemplateKind) -> crate::Result<Box<dyn EndpointContextBuilder>> {
+        Self::get_builder_with(template, None)
+    }
+
+    /// Like [`get_builder`](Self::get_builder), configuring the builder from
+    /// `options` (e.g. [`TemplateOptions::request_body_naming`])
+    pub fn get_builder_with(
+        template: TemplateKind,
+        options: Option<&TemplateOptions>,
+    ) -> crate::Result<Box<dyn EndpointContextBuilder>> {
         match template {
-            TemplateKind::RustAxum => Ok(Box::new(rust::RustEndpointContextBuilder)),
+            TemplateKind::RustAxum => Ok(Box::new(rust::RustEndpointContextBuilder {
+                request_body_naming: options
+                    .map(|opts| opts.request_body_naming)
+                    .unwrap_or_default(),
+            })),
+            TemplateKind::Custom => Ok(Box::new(generic::GenericEndpointContextBuilder)),
             _ => Err(crate::error::Error::template(format!(
                 "Builder not implemented for template: {:?}",
                 template
@@ -45,3 +113,19 @@ impl EndpointContext {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_builder_errors_for_kinds_without_one() {
+        assert!(EndpointContext::get_builder(TemplateKind::Custom).is_ok());
+        let err = EndpointContext::get_builder(TemplateKind::PythonFastAPI)
+            .err()
+            .unwrap();
+        assert!(err
+            .to_string()
+            .contains("Builder not implemented for template: PythonFastAPI"));
+    }
+}
@@ -1,9 +1,12 @@
 //! Rust-specific endpoint context builder for Agenterra codegen.
 
-use super::EndpointContextBuilder;
-use crate::openapi::OpenApiOperation;
-use crate::templates::{ParameterKind, TemplateParameterInfo};
-use crate::utils::{to_snake_case, to_upper_camel_case};
+use super::{EndpointContextBuilder, UnmappedType};
+use crate::openapi::{merge_parameter, OpenApiContext, OpenApiOperation, OpenApiParameter};
+use crate::templates::{
+    ParameterKind, ParameterSerialization, RequestBodyNaming, TemplateEnumInfo,
+    TemplateEnumVariant, TemplateParameterInfo, TemplateResponseInfo,
+};
+use crate::utils::{escape_rust_keyword, to_snake_case, to_upper_camel_case};
 use serde::{Deserialize, Serialize};
 use serde_json::{Map as JsonMap, Value as JsonValue};
 
@@ -14,10 +17,16 @@ pub type RustParameterInfo = TemplateParameterInfo;
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct RustPropertyInfo {
     pub name: String,
+    /// `name` as a Rust field name (`r#type` for `type`)
+    pub ident: String,
     pub rust_type: String,
     pub title: Option<String>,
     pub description: Option<String>,
     pub example: Option<JsonValue>,
+    /// Allowed values of an integer/number `enum` (`None` when unconstrained)
+    pub enum_values: Option<Vec<JsonValue>>,
+    /// Whether `rust_type` names one of the endpoint's generated `enums`
+    pub is_enum: bool,
 }
 
 // Rust-specific context for codegen
@@ -29,13 +38,18 @@ pub struct RustEndpointContext {
     pub endpoint_cap: String,
     /// Sanitized endpoint name for file system use
     pub endpoint_fs: String,
+    /// Name of the endpoint's handler module, escaped when it's a keyword
+    pub module: String,
+    /// HTTP method as defined in the OpenAPI spec (e.g., "get")
+    pub method: String,
     /// Raw path as defined in the OpenAPI spec (e.g., "/pet/{petId}")
     pub path: String,
-    /// Name of the generated function for the endpoint
+    /// Name of the generated function for the endpoint, escaped when it's a keyword
     pub fn_name: String,
     /// Name of the generated parameters struct (e.g., 'users_params')
     pub parameters_type: String,
-    /// Name of the generated properties struct
+    /// Name of the generated properties struct: the response schema's `title` when it
+    /// has one, else `{Op}Properties`
     pub properties_type: String,
     /// Name of the generated response struct
     pub response_type: String,
@@ -55,50 +69,79 @@ pub struct RustEndpointContext {
     pub tags: Vec<String>,
     /// Schema reference for the properties
     pub properties_schema: JsonMap<String, JsonValue>,
-    /// Schema reference for the response
+    /// Schema of the success response: `200`, else the first other 2xx, else `default`
     pub response_schema: JsonValue,
     /// Name of the spec file (if loaded from a file)
     pub spec_file_name: Option<String>,
     /// Valid fields for the endpoint
     pub valid_fields: Vec<String>,
+    /// Whether the request body is a raw byte stream to be taken as `axum::body::Bytes`
+    pub request_body_is_binary: bool,
+    /// Name of the request body struct: the referenced component's name so operations
+    /// sharing a body share the struct, else the inline schema's `title`, else
+    /// `{Op}RequestBody` (`None` without a structured body)
+    pub request_body_type: Option<String>,
+    /// Media types the request body accepts (empty without a request body)
+    pub request_media_types: Vec<String>,
+    /// Every declared response (2xx, 4xx, 5xx and `default`) in status order
+    pub responses_by_status: Vec<TemplateResponseInfo>,
+    /// Enums generated for `oneOf`/`anyOf` properties
+    pub enums: Vec<TemplateEnumInfo>,
+    /// Header parameter carrying the API version, e.g. `X-API-Version` or an
+    /// `Accept` header listing `application/vnd.api.v2+json` (also in `parameters`)
+    pub version_header: Option<TemplateParameterInfo>,
 }
 
-#[derive(Debug, Clone)]
-pub struct RustEndpointContextBuilder;
+#[derive(Debug, Clone, Default)]
+pub struct RustEndpointContextBuilder {
+    /// How `request_body_type` is chosen
+    pub request_body_naming: RequestBodyNaming,
+}
 
 impl EndpointContextBuilder for RustEndpointContextBuilder {
     fn build(&self, op: &OpenApiOperation) -> crate::Result<JsonValue> {
+        let parameters: Vec<OpenApiParameter> = op
+            .parameters
+            .clone()
+            .unwrap_or_default()
+            .into_iter()
+            // Collapse duplicates, e.g. `X-Request-Id` and `x-request-id` headers
+            .fold(Vec::new(), |mut params, p| {
+                merge_parameter(&mut params, p);
+                params
+            });
+        let version_header = parameters
+            .iter()
+            .find(|p| is_version_header(p))
+            .cloned()
+            .map(build_parameter_info);
+        let parameters_type = to_upper_camel_case(&format!("{}_params", op.id));
+        let response_type = to_upper_camel_case(&format!("{}_response", op.id));
+        let properties_type = title_type_name(
+            &extract_response_schema(op),
+            &[&parameters_type, &response_type],
+        )
+        .unwrap_or_else(|| to_upper_camel_case(&format!("{}_properties", op.id)));
+        let request_body_type = request_body_type(
+            op,
+            self.request_body_naming,
+            &[&parameters_type, &response_type, &properties_type],
+        );
         let context = RustEndpointContext {
-            fn_name: to_snake_case(&op.id),
-            parameters_type: to_upper_camel_case(&format!("{}_params", op.id)),
+            fn_name: escape_rust_keyword(&to_snake_case(&op.id)),
+            parameters_type,
             endpoint: to_snake_case(&op.id),
             endpoint_cap: to_upper_camel_case(&op.id),
             endpoint_fs: to_snake_case(&op.id),
+            module: escape_rust_keyword(&to_snake_case(&op.id)),
+            method: op.method.clone(),
             path: op.path.clone(),
-            properties_type: to_upper_camel_case(&format!("{}_properties", op.id)),
-            response_type: to_upper_camel_case(&format!("{}_response", op.id)),
+            properties_type,
+            response_type,
             envelope_properties: extract_response_properties(op),
             properties: build_property_info(op),
             properties_for_handler: collect_property_names(op),
-            parameters: op
-                .parameters
-                .clone()
-                .unwrap_or_default()
-                .into_iter()
-                .map(|p| TemplateParameterInfo {
-                    name: p.name,
-                    target_type: map_openapi_schema_to_rust_type(p.schema.as_ref()),
-                    description: p.description,
-                    example: p.example,
-                    kind: match p.in_.as_str() {
-                        "path" => ParameterKind::Path,
-                        "query" => ParameterKind::Query,
-                        "header" => ParameterKind::Header,
-                        "cookie" => ParameterKind::Cookie,
-                        _ => ParameterKind::Query, // Safe default
-                    },
-                })
-                .collect(),
+            parameters: parameters.into_iter().map(build_parameter_info).collect(),
             summary: op.summary.clone().unwrap_or_default(),
             description: op.description.clone().unwrap_or_default(),
             tags: op.tags.clone().unwrap_or_default(),
@@ -106,11 +149,233 @@ impl EndpointContextBuilder for RustEndpointContextBuilder {
             response_schema: extract_response_schema(op),
             spec_file_name: None,
             valid_fields: collect_property_names(op),
+            request_body_is_binary: op.request_body_is_binary(),
+            request_body_type,
+            request_media_types: op.request_media_types(),
+            responses_by_status: build_response_info(op),
+            enums: build_enum_info(op),
+            version_header,
         };
 
         // Convert to JSON
         Ok(serde_json::to_value(&context)?)
     }
+
+    fn build_with_spec(
+        &self,
+        op: &OpenApiOperation,
+        spec: &OpenApiContext,
+    ) -> crate::Result<JsonValue> {
+        self.build(&resolve_response_refs(op, spec))
+    }
+
+    fn unmapped_types(&self, op: &OpenApiOperation) -> Vec<UnmappedType> {
+        let parameters = op
+            .parameters
+            .iter()
+            .flatten()
+            .map(|p| (p.name.clone(), parameter_schema(p).cloned()));
+        let properties = extract_properties_schema(op)
+            .into_iter()
+            .filter(|(name, schema)| union_enum(op, name, schema).is_none())
+            .map(|(name, schema)| (name, Some(schema)));
+        parameters
+            .chain(properties)
+            .filter_map(|(field, schema)| {
+                let reason = unmapped_reason(schema.as_ref())?;
+                Some(UnmappedType {
+                    operation: op.id.clone(),
+                    field,
+                    reason,
+                    fallback: map_openapi_schema_to_rust_type(schema.as_ref()),
+                })
+            })
+            .collect()
+    }
+}
+
+// `op` with `$ref` response schemas, and `$ref` items of array response
+// schemas, replaced by the component they point to so their properties reach
+// the context. Refs nested deeper are left alone.
+fn resolve_response_refs(op: &OpenApiOperation, spec: &OpenApiContext) -> OpenApiOperation {
+    let mut op = op.clone();
+    let schemas = op
+        .responses
+        .values_mut()
+        .filter_map(|response| response.content.as_mut())
+        .flat_map(|content| content.values_mut())
+        .filter_map(|media| media.get_mut("schema"));
+    for schema in schemas {
+        resolve_ref(schema, spec);
+        if let Some(items) = schema.get_mut("items") {
+            resolve_ref(items, spec);
+        }
+    }
+    op
+}
+
+// Replace `schema` by what its `$ref` (chain) points to, stopping at a ref
+// that doesn't resolve or at a cycle of refs
+fn resolve_ref(schema: &mut JsonValue, spec: &OpenApiContext) {
+    let mut seen = Vec::new();
+    while let Some(ref_str) = schema.get("$ref").and_then(JsonValue::as_str) {
+        if seen.iter().any(|r| r == ref_str) {
+            log::warn!("Cyclic schema reference '{}' left unresolved", ref_str);
+            return;
+        }
+        let Some(def) = spec.resolve_schema_ref(ref_str) else {
+            return;
+        };
+        seen.push(ref_str.to_string());
+        *schema = def.clone();
+    }
+}
+
+fn build_parameter_info(p: OpenApiParameter) -> TemplateParameterInfo {
+    let serialization = parameter_serialization(&p);
+    let schema = parameter_schema(&p);
+    let target_type = map_openapi_schema_to_rust_type(schema);
+    // Path parameters are always required, whatever the spec says
+    let required = p.in_ == "path" || p.required == Some(true);
+    TemplateParameterInfo {
+        field_type: if required {
+            target_type.clone()
+        } else {
+            format!("Option<{}>", target_type)
+        },
+        target_type,
+        required,
+        enum_values: numeric_enum_values(schema),
+        kind: match p.in_.as_str() {
+            "path" => ParameterKind::Path,
+            "query" => ParameterKind::Query,
+            "header" => ParameterKind::Header,
+            "cookie" => ParameterKind::Cookie,
+            _ => ParameterKind::Query, // Safe default
+        },
+        canonical_name: p.canonical_name(),
+        ident: escape_rust_keyword(&p.name),
+        name: p.name,
+        description: p.description,
+        example: p.example,
+        serialization,
+    }
+}
+
+// Whether a header parameter selects the API version: its name mentions a
+// version (`X-API-Version`, `Accept-Version`), or it's an `Accept` header whose
+// values are versioned vendor media types like `application/vnd.api.v2+json`
+fn is_version_header(p: &OpenApiParameter) -> bool {
+    if p.in_ != "header" {
+        return false;
+    }
+    let name = p.name.to_ascii_lowercase();
+    if name.contains("version") {
+        return true;
+    }
+    if name != "accept" {
+        return false;
+    }
+    let schema = parameter_schema(p);
+    let enum_values = schema
+        .and_then(|s| s.get("enum"))
+        .and_then(JsonValue::as_array)
+        .into_iter()
+        .flatten();
+    let defaults = [
+        p.example.as_ref(),
+        schema.and_then(|s| s.get("default")),
+        schema.and_then(|s| s.get("example")),
+    ];
+    enum_values
+        .chain(defaults.into_iter().flatten())
+        .filter_map(JsonValue::as_str)
+        .any(is_versioned_media_type)
+}
+
+// `application/vnd.api.v2+json` or `application/json; version=2`
+fn is_versioned_media_type(media_type: &str) -> bool {
+    let media_type = media_type.to_ascii_lowercase();
+    let vendor_version = media_type.contains("vnd.")
+        && media_type.split(['.', '+', ';']).any(|part| {
+            part.strip_prefix('v')
+                .is_some_and(|n| n.starts_with(|c: char| c.is_ascii_digit()))
+        });
+    vendor_version || media_type.contains("version=")
+}
+
+// Schema of a parameter, falling back to `content[mime].schema` for complex
+// parameters (preferring JSON when several media types are listed)
+fn parameter_schema(p: &OpenApiParameter) -> Option<&JsonValue> {
+    p.schema.as_ref().or_else(|| {
+        let content = p.content.as_ref()?;
+        content
+            .get("application/json")
+            .or_else(|| content.values().next())
+            .and_then(|media| media.get("schema"))
+    })
+}
+
+// Values of a numeric `enum`, so templates can validate against them
+fn numeric_enum_values(schema: Option<&JsonValue>) -> Option<Vec<JsonValue>> {
+    let schema = schema?;
+    let is_numeric = matches!(
+        schema.get("type").and_then(JsonValue::as_str),
+        Some("integer" | "number")
+    );
+    let values = schema.get("enum")?.as_array()?;
+    (is_numeric && !values.is_empty() && values.iter().all(JsonValue::is_number))
+        .then(|| values.clone())
+}
+
+// Effective style/explode for array parameters and matrix/label path parameters,
+// applying the OpenAPI defaults (form + explode for query/cookie, simple otherwise)
+fn parameter_serialization(p: &OpenApiParameter) -> Option<ParameterSerialization> {
+    let is_array = p
+        .schema
+        .as_ref()
+        .and_then(|s| s.get("type"))
+        .and_then(JsonValue::as_str)
+        == Some("array");
+    let is_path_prefixed =
+        p.in_ == "path" && matches!(p.style.as_deref(), Some("matrix") | Some("label"));
+    if is_path_prefixed {
+        log::warn!(
+            "Path parameter '{}' uses style '{}', which the generated routes don't decode",
+            p.name,
+            p.style.as_deref().unwrap_or_default()
+        );
+    } else if !is_array {
+        return None;
+    }
+    let style = p.style.clone().unwrap_or_else(|| {
+        match p.in_.as_str() {
+            "query" | "cookie" => "form",
+            _ => "simple",
+        }
+        .to_string()
+    });
+    let explode = p.explode.unwrap_or(style == "form");
+    Some(ParameterSerialization { style, explode })
+}
+
+// Whether an `enum` lists values of more than one JSON type, e.g. `["a", 1, true]`
+// (`null` doesn't count, it only makes the field nullable)
+fn is_mixed_type_enum(schema: &JsonValue) -> bool {
+    let Some(values) = schema.get("enum").and_then(JsonValue::as_array) else {
+        return false;
+    };
+    let mut kinds = values.iter().filter_map(|value| match value {
+        JsonValue::Null => None,
+        JsonValue::Bool(_) => Some("boolean"),
+        JsonValue::Number(_) => Some("number"),
+        JsonValue::String(_) => Some("string"),
+        JsonValue::Array(_) => Some("array"),
+        JsonValue::Object(_) => Some("object"),
+    });
+    kinds
+        .next()
+        .is_some_and(|first| kinds.any(|kind| kind != first))
 }
 
 // Helper to map OpenAPI schema to Rust type
@@ -122,8 +387,14 @@ fn map_openapi_schema_to_rust_type(schema: Option<&JsonValue>) -> String {
                 "integer" => "i32".to_string(),
                 "boolean" => "bool".to_string(),
                 "number" => "f64".to_string(),
-                other => other.to_string(),
+                "object" => "serde_json::Value".to_string(),
+                "array" => format!("Vec<{}>", map_openapi_schema_to_rust_type(sch.get("items"))),
+                // Unknown types (e.g. Swagger 2 `file`) aren't valid Rust types
+                _ => "serde_json::Value".to_string(),
             }
+        } else if is_mixed_type_enum(sch) {
+            // No single scalar type holds every value
+            "serde_json::Value".to_string()
         } else {
             "String".to_string()
         }
@@ -132,9 +403,137 @@ fn map_openapi_schema_to_rust_type(schema: Option<&JsonValue>) -> String {
     }
 }
 
+// Why `map_openapi_schema_to_rust_type` can't give a precise type for a schema,
+// or `None` when it can
+fn unmapped_reason(schema: Option<&JsonValue>) -> Option<String> {
+    let Some(schema) = schema else {
+        return Some("no schema".to_string());
+    };
+    // The values can't become one Rust enum, so the field is left unconstrained
+    if is_mixed_type_enum(schema) {
+        return Some("mixed-type enum".to_string());
+    }
+    match schema.get("type").and_then(JsonValue::as_str) {
+        Some("string" | "integer" | "boolean" | "number") => None,
+        Some("object") => Some("free-form object".to_string()),
+        Some("array") => {
+            unmapped_reason(schema.get("items")).map(|reason| format!("array of {}", reason))
+        }
+        Some(other) => Some(format!("unknown type `{}`", other)),
+        None => Some(
+            ["$ref", "oneOf", "anyOf", "allOf"]
+                .into_iter()
+                .find(|key| schema.get(*key).is_some())
+                .map_or_else(
+                    || "untyped schema".to_string(),
+                    |key| format!("{} schema", key),
+                ),
+        ),
+    }
+}
+
+// `taken` lists the other type names of the operation, which the body type
+// can't reuse
+fn request_body_type(
+    op: &OpenApiOperation,
+    naming: RequestBodyNaming,
+    taken: &[&str],
+) -> Option<String> {
+    if op.request_body.is_none() || op.request_body_is_binary() {
+        return None;
+    }
+    let per_operation = to_upper_camel_case(&format!("{}_request_body", op.id));
+    if naming == RequestBodyNaming::Operation {
+        return Some(per_operation);
+    }
+    let inline_title = || {
+        let content = op.request_body.as_ref()?.get("content")?.as_object()?;
+        let media = content
+            .get("application/json")
+            .or_else(|| content.values().next())?;
+        title_type_name(media.get("schema")?, taken)
+    };
+    Some(
+        op.request_body_component()
+            .map(to_upper_camel_case)
+            .filter(|name| is_free_type_name(name, taken))
+            .or_else(inline_title)
+            .unwrap_or(per_operation),
+    )
+}
+
+// Names the generated handler module already uses: its imports, prelude types
+// and the items of `rmcp::model` it refers to
+const RESERVED_TYPE_NAMES: &[&str] = &[
+    "Box",
+    "Config",
+    "Content",
+    "Deserialize",
+    "Endpoint",
+    "Error",
+    "HashMap",
+    "IntoCallToolResult",
+    "IntoContents",
+    "JsonSchema",
+    "Option",
+    "Result",
+    "Serialize",
+    "String",
+    "ToSchema",
+    "Tool",
+    "Value",
+    "Vec",
+];
+
+// Struct name from an inline schema's `title`, PascalCased; `None` without a
+// title that makes a valid type name, or when the name is reserved or `taken`
+fn title_type_name(schema: &JsonValue, taken: &[&str]) -> Option<String> {
+    let name = to_upper_camel_case(schema.get("title")?.as_str()?);
+    let usable =
+        name.starts_with(|c: char| c.is_ascii_alphabetic()) && is_free_type_name(&name, taken);
+    usable.then_some(name)
+}
+
+// Whether `name` is neither reserved nor `taken`
+fn is_free_type_name(name: &str, taken: &[&str]) -> bool {
+    !RESERVED_TYPE_NAMES.contains(&name) && !taken.contains(&name)
+}
+
+fn build_response_info(op: &OpenApiOperation) -> Vec<TemplateResponseInfo> {
+    op.responses
+        .iter()
+        .map(|(status, response)| {
+            let schema = response.content.as_ref().and_then(|content| {
+                content
+                    .get("application/json")
+                    .or_else(|| content.values().next())
+                    .and_then(|media| media.get("schema"))
+                    .cloned()
+            });
+            TemplateResponseInfo {
+                status: status.clone(),
+                description: response.description.clone(),
+                target_type: schema
+                    .as_ref()
+                    .map(|schema| map_openapi_schema_to_rust_type(Some(schema))),
+                schema,
+                is_default: status == "default",
+            }
+        })
+        .collect()
+}
+
+// Schema of the success response: `200`, else the first other 2xx, else `default`
 fn extract_response_schema(op: &OpenApiOperation) -> JsonValue {
     op.responses
         .get("200")
+        .or_else(|| {
+            op.responses
+                .iter()
+                .find(|(status, _)| status.starts_with('2'))
+                .map(|(_, resp)| resp)
+        })
+        .or_else(|| op.responses.get("default"))
         .and_then(|resp| resp.content.as_ref())
         .and_then(|content| content.get("application/json"))
         .and_then(|c| c.get("schema"))
@@ -143,16 +542,18 @@ fn extract_response_schema(op: &OpenApiOperation) -> JsonValue {
 }
 
 fn extract_properties_schema(op: &OpenApiOperation) -> JsonMap<String, JsonValue> {
-    extract_response_schema(op)
-        .get("properties")
-        .and_then(JsonValue::as_object)
+    extract_response_properties(op)
+        .as_object()
         .cloned()
         .unwrap_or_default()
 }
 
+// Properties of the success response schema, or of its items when it's an array
 fn extract_response_properties(op: &OpenApiOperation) -> JsonValue {
-    extract_response_schema(op)
+    let schema = extract_response_schema(op);
+    schema
         .get("properties")
+        .or_else(|| schema.get("items")?.get("properties"))
         .cloned()
         .unwrap_or(JsonValue::Null)
 }
@@ -161,22 +562,843 @@ fn build_property_info(op: &OpenApiOperation) -> Vec<RustPropertyInfo> {
     let props = extract_properties_schema(op);
     props
         .iter()
-        .map(|(name, schema)| RustPropertyInfo {
-            name: name.clone(),
-            rust_type: map_openapi_schema_to_rust_type(Some(schema)),
-            title: schema
-                .get("title")
-                .and_then(|v| v.as_str())
-                .map(String::from),
-            description: schema
-                .get("description")
-                .and_then(|v| v.as_str())
-                .map(String::from),
-            example: schema.get("example").cloned(),
+        .map(|(name, schema)| {
+            let union = union_enum(op, name, schema);
+            RustPropertyInfo {
+                name: name.clone(),
+                ident: escape_rust_keyword(name),
+                is_enum: union.is_some(),
+                rust_type: union
+                    .map_or_else(|| map_openapi_schema_to_rust_type(Some(schema)), |e| e.name),
+                title: schema
+                    .get("title")
+                    .and_then(|v| v.as_str())
+                    .map(String::from),
+                description: schema
+                    .get("description")
+                    .and_then(|v| v.as_str())
+                    .map(String::from),
+                example: schema.get("example").cloned(),
+                enum_values: numeric_enum_values(Some(schema)),
+            }
         })
         .collect()
 }
 
+fn build_enum_info(op: &OpenApiOperation) -> Vec<TemplateEnumInfo> {
+    extract_properties_schema(op)
+        .iter()
+        .filter_map(|(name, schema)| union_enum(op, name, schema))
+        .collect()
+}
+
+// Variant identifier that is a valid, unused Rust name: leading digits get a
+// `Variant` prefix, keywords are escaped and repeats get a numeric suffix
+fn unique_variant_name(name: &str, taken: &mut Vec<String>) -> String {
+    let base = if name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("Variant{}", name)
+    } else {
+        escape_rust_keyword(name)
+    };
+    let mut candidate = base.clone();
+    let mut suffix = 2;
+    while taken.contains(&candidate) {
+        candidate = format!("{}{}", base, suffix);
+        suffix += 1;
+    }
+    taken.push(candidate.clone());
+    candidate
+}
+
+// Enum for a `oneOf`/`anyOf` property: internally tagged by the discriminator
+// property when the schema declares one, untagged otherwise
+fn union_enum(
+    op: &OpenApiOperation,
+    property: &str,
+    schema: &JsonValue,
+) -> Option<TemplateEnumInfo> {
+    let subschemas = schema
+        .get("oneOf")
+        .or_else(|| schema.get("anyOf"))?
+        .as_array()
+        .filter(|subschemas| !subschemas.is_empty())?;
+    let discriminator = schema.get("discriminator");
+    let tag = discriminator
+        .and_then(|d| d.get("propertyName"))
+        .and_then(JsonValue::as_str);
+    let mapping = discriminator
+        .and_then(|d| d.get("mapping"))
+        .and_then(JsonValue::as_object);
+
+    let mut taken: Vec<String> = Vec::new();
+    let variants = subschemas
+        .iter()
+        .enumerate()
+        .map(|(i, subschema)| {
+            let ref_name = subschema
+                .get("$ref")
+                .and_then(JsonValue::as_str)
+                .and_then(|r| r.rsplit('/').next());
+            let name = ref_name
+                .or_else(|| subschema.get("title").and_then(JsonValue::as_str))
+                .map(to_upper_camel_case)
+                .filter(|name| !name.is_empty())
+                .unwrap_or_else(|| format!("Variant{}", i + 1));
+            let variant_name = unique_variant_name(&name, &mut taken);
+            // Explicit mapping first, then the value the tag property is pinned
+            // to, then the schema name as the OpenAPI default
+            let tag_value = tag.map(|tag| {
+                let mapped = subschema
+                    .get("$ref")
+                    .and_then(JsonValue::as_str)
+                    .and_then(|r| {
+                        mapping?
+                            .iter()
+                            .find(|(_, target)| target.as_str() == Some(r))
+                            .map(|(value, _)| value.clone())
+                    });
+                let pinned = || {
+                    subschema
+                        .pointer(&format!("/properties/{}/enum/0", tag))
+                        .and_then(JsonValue::as_str)
+                        .map(String::from)
+                };
+                mapped
+                    .or_else(pinned)
+                    .or_else(|| ref_name.map(String::from))
+                    .unwrap_or_else(|| name.clone())
+            });
+            // Tagged variants must be objects; referenced schemas aren't generated as types
+            let target_type = if tag.is_some() || subschema.get("$ref").is_some() {
+                "serde_json::Value".to_string()
+            } else {
+                map_openapi_schema_to_rust_type(Some(subschema))
+            };
+            TemplateEnumVariant {
+                name: variant_name,
+                tag_value,
+                target_type,
+            }
+        })
+        .collect();
+
+    Some(TemplateEnumInfo {
+        name: to_upper_camel_case(&format!("{}_{}", op.id, property)),
+        tag: tag.map(String::from),
+        variants,
+    })
+}
+
 fn collect_property_names(op: &OpenApiOperation) -> Vec<String> {
     extract_properties_schema(op).keys().cloned().collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_array_query_parameter_serialization_hint() -> crate::Result<()> {
+        let op: OpenApiOperation = serde_json::from_value(json!({
+            "operationId": "findPets",
+            "method": "get",
+            "path": "/pets",
+            "parameters": [
+                {"name": "status", "in": "query", "explode": true,
+                 "schema": {"type": "array", "items": {"type": "string"}}},
+                {"name": "ids", "in": "query", "style": "form", "explode": false,
+                 "schema": {"type": "array", "items": {"type": "integer"}}},
+                {"name": "limit", "in": "query", "schema": {"type": "integer"}}
+            ],
+            "responses": {}
+        }))?;
+        let context = RustEndpointContextBuilder::default().build(&op)?;
+        let params = &context["parameters"];
+
+        assert_eq!(
+            params[0]["serialization"],
+            json!({"style": "form", "explode": true})
+        );
+        assert_eq!(
+            params[1]["serialization"],
+            json!({"style": "form", "explode": false})
+        );
+        assert!(params[2]["serialization"].is_null());
+        Ok(())
+    }
+
+    #[test]
+    fn test_octet_stream_binary_body_is_raw_bytes() -> crate::Result<()> {
+        let op: OpenApiOperation = serde_json::from_value(json!({
+            "operationId": "uploadImage",
+            "method": "post",
+            "path": "/pets/{petId}/image",
+            "requestBody": {"content": {"application/octet-stream": {
+                "schema": {"type": "string", "format": "binary"}
+            }}},
+            "responses": {}
+        }))?;
+        let context = RustEndpointContextBuilder::default().build(&op)?;
+
+        assert_eq!(context["request_body_is_binary"], json!(true));
+
+        let mut json_op = op.clone();
+        json_op.request_body = Some(json!({"content": {"application/json": {
+            "schema": {"type": "object", "properties": {"name": {"type": "string"}}}
+        }}}));
+        let context = RustEndpointContextBuilder::default().build(&json_op)?;
+
+        assert_eq!(context["request_body_is_binary"], json!(false));
+        Ok(())
+    }
+
+    #[test]
+    fn test_request_media_types_lists_every_content_type() -> crate::Result<()> {
+        let schema = json!({"type": "object", "properties": {"name": {"type": "string"}}});
+        let op: OpenApiOperation = serde_json::from_value(json!({
+            "operationId": "addPet",
+            "method": "post",
+            "path": "/pets",
+            "requestBody": {"content": {
+                "application/json": {"schema": schema},
+                "application/xml": {"schema": schema}
+            }},
+            "responses": {}
+        }))?;
+        let context = RustEndpointContextBuilder::default().build(&op)?;
+
+        assert_eq!(
+            context["request_media_types"],
+            json!(["application/json", "application/xml"])
+        );
+        assert_eq!(context["request_body_type"], json!("AddPetRequestBody"));
+
+        let mut no_body = op.clone();
+        no_body.request_body = None;
+        let context = RustEndpointContextBuilder::default().build(&no_body)?;
+        assert_eq!(context["request_media_types"], json!([]));
+        Ok(())
+    }
+
+    #[test]
+    fn test_unmapped_types() -> crate::Result<()> {
+        let op: OpenApiOperation = serde_json::from_value(json!({
+            "operationId": "getPet",
+            "method": "get",
+            "path": "/pets",
+            "parameters": [
+                {"name": "id", "in": "query", "schema": {"type": "integer"}},
+                {"name": "filter", "in": "query",
+                 "schema": {"oneOf": [{"type": "string"}, {"type": "integer"}]}},
+                {"name": "ids", "in": "query",
+                 "schema": {"type": "array", "items": {"type": "file"}}}
+            ],
+            "responses": {"200": {"description": "ok", "content": {"application/json": {
+                "schema": {"type": "object", "properties": {
+                    "name": {"type": "string"},
+                    "extra": {"type": "object"}
+                }}
+            }}}}
+        }))?;
+
+        let unmapped: Vec<String> = RustEndpointContextBuilder::default()
+            .unmapped_types(&op)
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+
+        assert_eq!(
+            unmapped,
+            vec![
+                "getPet.filter: oneOf schema (falls back to String)",
+                "getPet.ids: array of unknown type `file` (falls back to Vec<serde_json::Value>)",
+                "getPet.extra: free-form object (falls back to serde_json::Value)",
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_responses_by_status() -> crate::Result<()> {
+        let op: OpenApiOperation = serde_json::from_value(json!({
+            "operationId": "getPet",
+            "method": "get",
+            "path": "/pets/{id}",
+            "responses": {
+                "200": {"description": "The pet", "content": {"application/json": {
+                    "schema": {"type": "object", "properties": {"name": {"type": "string"}}}
+                }}},
+                "404": {"description": "Not found", "content": {"text/plain": {
+                    "schema": {"type": "string"}
+                }}},
+                "default": {"description": "Error codes", "content": {"application/json": {
+                    "schema": {"type": "array", "items": {"type": "integer"}}
+                }}},
+                "204": {"description": "Nothing"}
+            }
+        }))?;
+        let context = RustEndpointContextBuilder::default().build(&op)?;
+        let responses: Vec<(String, JsonValue)> = context["responses_by_status"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|r| {
+                (
+                    r["status"].as_str().unwrap().to_string(),
+                    r["target_type"].clone(),
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            responses,
+            vec![
+                ("200".to_string(), json!("serde_json::Value")),
+                ("204".to_string(), JsonValue::Null),
+                ("404".to_string(), json!("String")),
+                ("default".to_string(), json!("Vec<i32>")),
+            ]
+        );
+        assert_eq!(
+            context["responses_by_status"][2]["description"],
+            json!("Not found")
+        );
+        assert_eq!(context["responses_by_status"][2]["is_default"], false);
+        assert_eq!(context["responses_by_status"][3]["is_default"], true);
+        Ok(())
+    }
+
+    #[test]
+    fn test_default_only_response_schema() -> crate::Result<()> {
+        let op: OpenApiOperation = serde_json::from_value(json!({
+            "operationId": "getStatus",
+            "method": "get",
+            "path": "/status",
+            "responses": {
+                "default": {"description": "Service status", "content": {"application/json": {
+                    "schema": {"type": "object", "properties": {"healthy": {"type": "boolean"}}}
+                }}}
+            }
+        }))?;
+        let context = RustEndpointContextBuilder::default().build(&op)?;
+        assert_eq!(
+            context["envelope_properties"],
+            json!({"healthy": {"type": "boolean"}})
+        );
+        assert_eq!(context["properties"][0]["name"], "healthy");
+        assert_eq!(context["properties"][0]["rust_type"], "bool");
+        Ok(())
+    }
+
+    #[test]
+    fn test_matrix_path_parameter_style_hint() -> crate::Result<()> {
+        let op: OpenApiOperation = serde_json::from_value(json!({
+            "operationId": "getPet",
+            "method": "get",
+            "path": "/pets/{id}",
+            "parameters": [
+                {"name": "id", "in": "path", "required": true, "style": "matrix",
+                 "schema": {"type": "integer"}},
+                {"name": "owner", "in": "path", "required": true,
+                 "schema": {"type": "string"}}
+            ],
+            "responses": {}
+        }))?;
+        let context = RustEndpointContextBuilder::default().build(&op)?;
+        let params = &context["parameters"];
+
+        assert_eq!(
+            params[0]["serialization"],
+            json!({"style": "matrix", "explode": false})
+        );
+        assert!(params[1]["serialization"].is_null());
+        Ok(())
+    }
+
+    #[test]
+    fn test_content_parameter_uses_content_schema() -> crate::Result<()> {
+        let op: OpenApiOperation = serde_json::from_value(json!({
+            "operationId": "searchPets",
+            "method": "get",
+            "path": "/pets/search",
+            "parameters": [
+                {"name": "filter", "in": "query", "content": {
+                    "application/json": {"schema": {
+                        "type": "object",
+                        "properties": {"name": {"type": "string"}}
+                    }}
+                }},
+                {"name": "ids", "in": "query", "content": {
+                    "text/plain": {"schema": {"type": "array", "items": {"type": "integer"}}}
+                }}
+            ],
+            "responses": {}
+        }))?;
+        let context = RustEndpointContextBuilder::default().build(&op)?;
+
+        assert_eq!(context["parameters"][0]["target_type"], "serde_json::Value");
+        assert_eq!(context["parameters"][1]["target_type"], "Vec<i32>");
+        Ok(())
+    }
+
+    #[test]
+    fn test_header_parameters_collapse_case_insensitively() -> crate::Result<()> {
+        let op: OpenApiOperation = serde_json::from_value(json!({
+            "operationId": "getPet",
+            "method": "get",
+            "path": "/pets",
+            "parameters": [
+                {"name": "X-Request-Id", "in": "header", "schema": {"type": "string"}},
+                {"name": "x-request-id", "in": "header", "schema": {"type": "string"}},
+                {"name": "Limit", "in": "query", "schema": {"type": "integer"}},
+                {"name": "limit", "in": "query", "schema": {"type": "integer"}}
+            ],
+            "responses": {}
+        }))?;
+        let context = RustEndpointContextBuilder::default().build(&op)?;
+
+        let params = context["parameters"].as_array().unwrap();
+        assert_eq!(params.len(), 3);
+        assert_eq!(params[0]["name"], "x-request-id");
+        assert_eq!(params[0]["canonical_name"], "x-request-id");
+        // Query parameter names stay case-sensitive
+        assert_eq!(params[1]["canonical_name"], "Limit");
+        assert_eq!(params[2]["canonical_name"], "limit");
+        Ok(())
+    }
+
+    #[test]
+    fn test_keyword_identifiers_are_escaped() -> crate::Result<()> {
+        let op: OpenApiOperation = serde_json::from_value(json!({
+            "operationId": "move",
+            "method": "get",
+            "path": "/pets",
+            "parameters": [
+                {"name": "type", "in": "query", "schema": {"type": "string"}},
+                {"name": "self", "in": "query", "schema": {"type": "string"}},
+                {"name": "status", "in": "query", "schema": {"type": "string"}}
+            ],
+            "responses": {}
+        }))?;
+        let context = RustEndpointContextBuilder::default().build(&op)?;
+
+        assert_eq!(context["fn_name"], "r#move");
+        assert_eq!(context["module"], "r#move");
+        assert_eq!(context["endpoint"], "move");
+        let params = context["parameters"].as_array().unwrap();
+        assert_eq!(params[0]["name"], "type");
+        assert_eq!(params[0]["ident"], "r#type");
+        assert_eq!(params[1]["ident"], "self_");
+        assert_eq!(params[2]["ident"], "status");
+        Ok(())
+    }
+
+    #[test]
+    fn test_optional_query_parameters_are_wrapped_in_option() -> crate::Result<()> {
+        let op: OpenApiOperation = serde_json::from_value(json!({
+            "operationId": "findPets",
+            "method": "get",
+            "path": "/pets/{kind}",
+            "parameters": [
+                {"name": "kind", "in": "path", "schema": {"type": "string"}},
+                {"name": "status", "in": "query", "required": true, "schema": {"type": "string"}},
+                {"name": "limit", "in": "query", "required": false, "schema": {"type": "integer"}},
+                {"name": "offset", "in": "query", "schema": {"type": "integer"}}
+            ],
+            "responses": {}
+        }))?;
+        let context = RustEndpointContextBuilder::default().build(&op)?;
+
+        let params = context["parameters"].as_array().unwrap();
+        let field = |name: &str| {
+            let p = params.iter().find(|p| p["name"] == name).unwrap();
+            (p["required"].clone(), p["field_type"].clone())
+        };
+        assert_eq!(field("kind"), (json!(true), json!("String")));
+        assert_eq!(field("status"), (json!(true), json!("String")));
+        assert_eq!(field("limit"), (json!(false), json!("Option<i32>")));
+        assert_eq!(field("offset"), (json!(false), json!("Option<i32>")));
+        Ok(())
+    }
+
+    #[test]
+    fn test_discriminated_one_of_becomes_tagged_enum() -> crate::Result<()> {
+        let op: OpenApiOperation = serde_json::from_value(json!({
+            "operationId": "getPet",
+            "method": "get",
+            "path": "/pets/{id}",
+            "responses": {"200": {"content": {"application/json": {"schema": {
+                "type": "object",
+                "properties": {
+                    "pet": {
+                        "oneOf": [
+                            {"$ref": "#/components/schemas/Cat"},
+                            {"$ref": "#/components/schemas/Dog"},
+                            {"title": "lizard", "type": "object",
+                             "properties": {"kind": {"type": "string", "enum": ["reptile"]}}}
+                        ],
+                        "discriminator": {
+                            "propertyName": "kind",
+                            "mapping": {"dog": "#/components/schemas/Dog"}
+                        }
+                    },
+                    "id": {"anyOf": [{"type": "integer"}, {"type": "string"}]}
+                }
+            }}}}}
+        }))?;
+        let context = RustEndpointContextBuilder::default().build(&op)?;
+
+        assert_eq!(
+            context["enums"],
+            json!([
+                {"name": "GetPetId", "tag": null, "variants": [
+                    {"name": "Variant1", "tag_value": null, "target_type": "i32"},
+                    {"name": "Variant2", "tag_value": null, "target_type": "String"}
+                ]},
+                {"name": "GetPetPet", "tag": "kind", "variants": [
+                    {"name": "Cat", "tag_value": "Cat", "target_type": "serde_json::Value"},
+                    {"name": "Dog", "tag_value": "dog", "target_type": "serde_json::Value"},
+                    {"name": "Lizard", "tag_value": "reptile", "target_type": "serde_json::Value"}
+                ]}
+            ])
+        );
+        let pet = context["properties"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|p| p["name"] == "pet")
+            .unwrap();
+        assert_eq!(pet["rust_type"], "GetPetPet");
+        assert_eq!(pet["is_enum"], true);
+        assert!(RustEndpointContextBuilder::default()
+            .unmapped_types(&op)
+            .is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_union_variant_names_are_unique_and_keyword_safe() -> crate::Result<()> {
+        let op: OpenApiOperation = serde_json::from_value(json!({
+            "operationId": "getPet",
+            "method": "get",
+            "path": "/pets",
+            "responses": {"200": {"content": {"application/json": {"schema": {
+                "type": "object",
+                "properties": {
+                    "pet": {"oneOf": [
+                        {"title": "pet", "type": "string"},
+                        {"title": "Pet", "type": "integer"},
+                        {"title": "self", "type": "boolean"},
+                        {"title": "404", "type": "number"},
+                        {"title": "", "type": "object"}
+                    ]}
+                }
+            }}}}}
+        }))?;
+        let context = RustEndpointContextBuilder::default().build(&op)?;
+
+        let names: Vec<&str> = context["enums"][0]["variants"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|v| v["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(names, ["Pet", "Pet2", "Self_", "Variant404", "Variant5"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_shared_request_body_schema_shares_struct_name() -> crate::Result<()> {
+        let body = json!({"content": {"application/json": {
+            "schema": {"$ref": "#/components/schemas/new_pet"}
+        }}});
+        let op = |id: &str, body: JsonValue| -> crate::Result<JsonValue> {
+            let op: OpenApiOperation = serde_json::from_value(json!({
+                "operationId": id,
+                "method": "post",
+                "path": "/pets",
+                "requestBody": body,
+                "responses": {}
+            }))?;
+            RustEndpointContextBuilder::default().build(&op)
+        };
+
+        let create = op("createPet", body.clone())?;
+        let replace = op("replacePet", body)?;
+        assert_eq!(create["request_body_type"], "NewPet");
+        assert_eq!(replace["request_body_type"], create["request_body_type"]);
+
+        let inline = op(
+            "patchPet",
+            json!({"content": {"application/json": {"schema": {"type": "object"}}}}),
+        )?;
+        assert_eq!(inline["request_body_type"], "PatchPetRequestBody");
+        let shared = op(
+            "updatePet",
+            json!({"$ref": "#/components/requestBodies/PetBody"}),
+        )?;
+        assert_eq!(shared["request_body_type"], "PetBody");
+
+        // Components named like an import don't name the body
+        let config = op(
+            "setConfig",
+            json!({"content": {"application/json": {
+                "schema": {"$ref": "#/components/schemas/Config"}
+            }}}),
+        )?;
+        assert_eq!(config["request_body_type"], "SetConfigRequestBody");
+
+        let per_operation = RustEndpointContextBuilder {
+            request_body_naming: RequestBodyNaming::Operation,
+        }
+        .build(&serde_json::from_value(json!({
+            "operationId": "createPet",
+            "method": "post",
+            "path": "/pets",
+            "requestBody": {"content": {"application/json": {
+                "schema": {"$ref": "#/components/schemas/new_pet"}
+            }}},
+            "responses": {}
+        }))?)?;
+        assert_eq!(per_operation["request_body_type"], "CreatePetRequestBody");
+        Ok(())
+    }
+
+    #[test]
+    fn test_inline_schema_titles_name_structs() -> crate::Result<()> {
+        let op: OpenApiOperation = serde_json::from_value(json!({
+            "operationId": "createPet",
+            "method": "post",
+            "path": "/pets",
+            "requestBody": {"content": {"application/json": {"schema": {
+                "title": "new pet input",
+                "type": "object",
+                "properties": {"name": {"type": "string"}}
+            }}}},
+            "responses": {"200": {"content": {"application/json": {"schema": {
+                "title": "Created Pet",
+                "type": "object",
+                "properties": {"id": {"type": "integer"}}
+            }}}}}
+        }))?;
+        let context = RustEndpointContextBuilder::default().build(&op)?;
+        assert_eq!(context["request_body_type"], "NewPetInput");
+        assert_eq!(context["properties_type"], "CreatedPet");
+
+        // Titles that can't start a type name fall back to the operation name
+        let mut untitled = op.clone();
+        untitled.request_body.as_mut().unwrap()["content"]["application/json"]["schema"]["title"] =
+            json!("2024 input");
+        let context = RustEndpointContextBuilder::default().build(&untitled)?;
+        assert_eq!(context["request_body_type"], "CreatePetRequestBody");
+
+        // So do titles naming an import or another type of the operation
+        let mut clashing = op.clone();
+        clashing.request_body.as_mut().unwrap()["content"]["application/json"]["schema"]["title"] =
+            json!("create pet response");
+        clashing
+            .responses
+            .get_mut("200")
+            .unwrap()
+            .content
+            .as_mut()
+            .unwrap()
+            .get_mut("application/json")
+            .unwrap()["schema"]["title"] = json!("config");
+        let context = RustEndpointContextBuilder::default().build(&clashing)?;
+        assert_eq!(context["properties_type"], "CreatePetProperties");
+        assert_eq!(context["request_body_type"], "CreatePetRequestBody");
+        Ok(())
+    }
+
+    #[test]
+    fn test_numeric_enum_values_reach_context() -> crate::Result<()> {
+        let op: OpenApiOperation = serde_json::from_value(json!({
+            "operationId": "listOrders",
+            "method": "get",
+            "path": "/orders",
+            "parameters": [
+                {"name": "priority", "in": "query",
+                 "schema": {"type": "integer", "enum": [1, 2, 3]}},
+                {"name": "status", "in": "query",
+                 "schema": {"type": "string", "enum": ["open", "closed"]}}
+            ],
+            "responses": {"200": {"description": "ok", "content": {"application/json": {
+                "schema": {"type": "object", "properties": {
+                    "ratio": {"type": "number", "enum": [0.5, 1.0]}
+                }}
+            }}}}
+        }))?;
+        let context = RustEndpointContextBuilder::default().build(&op)?;
+
+        assert_eq!(context["parameters"][0]["enum_values"], json!([1, 2, 3]));
+        assert!(context["parameters"][1]["enum_values"].is_null());
+        assert_eq!(context["properties"][0]["enum_values"], json!([0.5, 1.0]));
+        Ok(())
+    }
+
+    #[test]
+    fn test_response_refs_are_resolved() -> crate::Result<()> {
+        let spec = OpenApiContext {
+            json: json!({"components": {"schemas": {
+                "Pet": {"type": "object", "properties": {
+                    "id": {"type": "integer"},
+                    "children": {"type": "array", "items": {"$ref": "#/components/schemas/Pet"}}
+                }},
+                "PetAlias": {"$ref": "#/components/schemas/Pet"},
+                "Ping": {"$ref": "#/components/schemas/Pong"},
+                "Pong": {"$ref": "#/components/schemas/Ping"}
+            }}}),
+        };
+        let op_returning = |schema: JsonValue| -> crate::Result<OpenApiOperation> {
+            Ok(serde_json::from_value(json!({
+                "operationId": "getPet",
+                "method": "get",
+                "path": "/pets/{id}",
+                "responses": {"200": {"description": "ok", "content": {"application/json": {
+                    "schema": schema
+                }}}}
+            }))?)
+        };
+
+        let op = op_returning(json!({"$ref": "#/components/schemas/PetAlias"}))?;
+        let context = RustEndpointContextBuilder::default().build_with_spec(&op, &spec)?;
+        let names: Vec<&JsonValue> = context["properties"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|p| &p["name"])
+            .collect();
+        assert_eq!(names, [&json!("children"), &json!("id")]);
+        assert_eq!(
+            context["envelope_properties"]["id"],
+            json!({"type": "integer"})
+        );
+        // The self-reference inside the schema is left as a ref
+        assert_eq!(
+            context["envelope_properties"]["children"]["items"],
+            json!({"$ref": "#/components/schemas/Pet"})
+        );
+
+        let op =
+            op_returning(json!({"type": "array", "items": {"$ref": "#/components/schemas/Pet"}}))?;
+        let context = RustEndpointContextBuilder::default().build_with_spec(&op, &spec)?;
+        assert_eq!(context["properties"].as_array().unwrap().len(), 2);
+        assert_eq!(
+            context["response_schema"]["items"]["properties"]["id"],
+            json!({"type": "integer"})
+        );
+
+        // A cycle of refs terminates, leaving the schema unresolved
+        let op = op_returning(json!({"$ref": "#/components/schemas/Ping"}))?;
+        let context = RustEndpointContextBuilder::default().build_with_spec(&op, &spec)?;
+        assert_eq!(context["properties"], json!([]));
+        Ok(())
+    }
+
+    #[test]
+    fn test_method_and_path_reach_context() -> crate::Result<()> {
+        let op: OpenApiOperation = serde_json::from_value(json!({
+            "operationId": "updatePetWithForm",
+            "method": "post",
+            "path": "/pet/{petId}",
+            "responses": {}
+        }))?;
+        let context = RustEndpointContextBuilder::default().build(&op)?;
+        assert_eq!(context["method"], "post");
+        assert_eq!(context["path"], "/pet/{petId}");
+        Ok(())
+    }
+
+    #[test]
+    fn test_version_header_is_surfaced() -> crate::Result<()> {
+        let op: OpenApiOperation = serde_json::from_value(json!({
+            "operationId": "getPet",
+            "method": "get",
+            "path": "/pets/{id}",
+            "parameters": [
+                {"name": "id", "in": "path", "required": true, "schema": {"type": "string"}},
+                {"name": "X-Request-Id", "in": "header", "schema": {"type": "string"}},
+                {"name": "Accept", "in": "header", "schema": {
+                    "type": "string",
+                    "enum": ["application/vnd.api.v1+json", "application/vnd.api.v2+json"]
+                }}
+            ],
+            "responses": {}
+        }))?;
+        let context = RustEndpointContextBuilder::default().build(&op)?;
+        assert_eq!(context["version_header"]["name"], "Accept");
+        assert_eq!(context["version_header"]["kind"], "header");
+        assert_eq!(context["parameters"].as_array().unwrap().len(), 3);
+
+        let named: OpenApiOperation = serde_json::from_value(json!({
+            "operationId": "listPets",
+            "method": "get",
+            "path": "/pets",
+            "parameters": [
+                {"name": "X-Request-Id", "in": "header", "schema": {"type": "string"}},
+                {"name": "x-api-version", "in": "header", "schema": {"type": "string"}}
+            ],
+            "responses": {}
+        }))?;
+        let context = RustEndpointContextBuilder::default().build(&named)?;
+        assert_eq!(context["version_header"]["name"], "x-api-version");
+
+        // A plain `Accept` header is an ordinary header
+        let plain: OpenApiOperation = serde_json::from_value(json!({
+            "operationId": "listPets",
+            "method": "get",
+            "path": "/pets",
+            "parameters": [
+                {"name": "Accept", "in": "header",
+                 "schema": {"type": "string", "enum": ["application/json", "text/csv"]}},
+                {"name": "version", "in": "query", "schema": {"type": "string"}}
+            ],
+            "responses": {}
+        }))?;
+        let context = RustEndpointContextBuilder::default().build(&plain)?;
+        assert!(context["version_header"].is_null());
+        Ok(())
+    }
+
+    #[test]
+    fn test_mixed_type_enum_falls_back_to_scalar_type() -> crate::Result<()> {
+        let op: OpenApiOperation = serde_json::from_value(json!({
+            "operationId": "listOrders",
+            "method": "get",
+            "path": "/orders",
+            "parameters": [
+                {"name": "level", "in": "query",
+                 "schema": {"type": "integer", "enum": [1, "high", true]}},
+                {"name": "nullable", "in": "query",
+                 "schema": {"type": "string", "enum": ["a", "b", null]}}
+            ],
+            "responses": {"200": {"description": "ok", "content": {"application/json": {
+                "schema": {"type": "object", "properties": {
+                    "flag": {"enum": ["a", 1, true]}
+                }}
+            }}}}
+        }))?;
+        let context = RustEndpointContextBuilder::default().build(&op)?;
+
+        assert_eq!(context["parameters"][0]["target_type"], "i32");
+        assert!(context["parameters"][0]["enum_values"].is_null());
+        assert_eq!(context["properties"][0]["rust_type"], "serde_json::Value");
+        assert_eq!(context["properties"][0]["is_enum"], false);
+
+        let unmapped: Vec<String> = RustEndpointContextBuilder::default()
+            .unmapped_types(&op)
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(
+            unmapped,
+            vec![
+                "listOrders.level: mixed-type enum (falls back to i32)",
+                "listOrders.flag: mixed-type enum (falls back to serde_json::Value)",
+            ]
+        );
+        Ok(())
+    }
+}
@@ -30,12 +30,12 @@ use tokio::fs;
 use url::Url;
 
 /// Configuration for Agenterra server generation
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Config {
     /// Project name
     pub project_name: String,
 
-    /// Path to the OpenAPI schema file
+    /// Location of the OpenAPI schema, as accepted by [`SpecSource`](crate::SpecSource)
     pub openapi_schema_path: String,
 
     /// Output directory for generated code
@@ -5,14 +5,22 @@ use std::{path::PathBuf, str::FromStr};
 use crate::{
     config::Config,
     error::Result,
-    openapi::OpenApiContext,
+    report::GenerationReport,
+    source::SpecSource,
     templates::{TemplateKind, TemplateManager, TemplateOptions},
 };
 
 /// Main entry point for code generation
-pub async fn generate(config: &Config, template_opts: Option<TemplateOptions>) -> Result<()> {
+pub async fn generate(
+    config: &Config,
+    template_opts: Option<TemplateOptions>,
+) -> Result<GenerationReport> {
     // 1. Load OpenAPI schema
-    let schema = OpenApiContext::from_file(&config.openapi_schema_path).await?;
+    let schema = config
+        .openapi_schema_path
+        .parse::<SpecSource>()?
+        .load()
+        .await?;
 
     // 2. Initialize template manager with template_dir from config if available
     let template_kind = TemplateKind::from_str(&config.template_kind).unwrap_or_default();
@@ -22,7 +30,5 @@ pub async fn generate(config: &Config, template_opts: Option<TemplateOptions>) -
     // 3. Delegate to TemplateManager.generate
     template_manager
         .generate(&schema, config, template_opts)
-        .await?;
-
-    Ok(())
+        .await
 }
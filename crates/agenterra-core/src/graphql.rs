@@ -0,0 +1,811 @@
+//! GraphQL schema import.
+//!
+//! This module parses a GraphQL schema in SDL form and converts it into an
+//! OpenAPI document so that it can be fed through the regular generation
+//! pipeline. Every field of the query root becomes a `GET /query/{field}`
+//! operation taking its arguments as query parameters, and every field of the
+//! mutation root a `POST /mutation/{field}` operation taking them as a JSON
+//! request body. Return types become the response schemas, and object, input,
+//! enum, union and custom scalar types become component schemas.
+//!
+//! Each operation carries an `x-graphql` extension with the operation type and
+//! field name, so templates can issue the matching GraphQL request through
+//! `vendor_extensions`. Subscriptions have no request/response equivalent and
+//! are skipped.
+
+use serde_json::{json, Map, Value as JsonValue};
+use std::collections::BTreeMap;
+use std::path::Path;
+use tokio::fs;
+
+use crate::{openapi::OpenApiContext, Error};
+
+/// Scalars every GraphQL schema has, mapped to their JSON Schema
+const BUILT_IN_SCALARS: &[(&str, &str, Option<&str>)] = &[
+    ("Int", "integer", Some("int32")),
+    ("Float", "number", Some("double")),
+    ("String", "string", None),
+    ("ID", "string", None),
+    ("Boolean", "boolean", None),
+];
+
+/// A reference to a type, e.g. `[Pet!]!`
+#[derive(Debug, Clone, PartialEq)]
+enum TypeRef {
+    Named(String),
+    List(Box<TypeRef>),
+    NonNull(Box<TypeRef>),
+}
+
+impl TypeRef {
+    fn is_non_null(&self) -> bool {
+        matches!(self, TypeRef::NonNull(_))
+    }
+
+    /// JSON Schema of a value of this type
+    fn schema(&self) -> JsonValue {
+        match self {
+            TypeRef::NonNull(inner) => inner.schema(),
+            TypeRef::List(inner) => json!({"type": "array", "items": inner.schema()}),
+            TypeRef::Named(name) => match BUILT_IN_SCALARS.iter().find(|(n, _, _)| n == name) {
+                Some((_, ty, Some(format))) => json!({"type": ty, "format": format}),
+                Some((_, ty, None)) => json!({"type": ty}),
+                None => json!({"$ref": format!("#/components/schemas/{}", name)}),
+            },
+        }
+    }
+}
+
+/// A field of an object, interface or input type, or an argument of a field
+#[derive(Debug, Clone)]
+struct Field {
+    name: String,
+    description: Option<String>,
+    arguments: Vec<Field>,
+    ty: TypeRef,
+    deprecated: bool,
+}
+
+impl Field {
+    /// JSON Schema of the field's value, with its documentation unless it is a `$ref`
+    fn schema(&self) -> JsonValue {
+        let mut schema = self.ty.schema();
+        if schema.get("$ref").is_none() {
+            if let Some(description) = &self.description {
+                schema["description"] = json!(description);
+            }
+            if self.deprecated {
+                schema["deprecated"] = json!(true);
+            }
+        }
+        schema
+    }
+}
+
+#[derive(Debug)]
+enum TypeKind {
+    /// Object, interface or input type
+    Object(Vec<Field>),
+    Enum(Vec<String>),
+    Union(Vec<String>),
+    Scalar,
+}
+
+#[derive(Debug)]
+struct TypeDefinition {
+    description: Option<String>,
+    kind: TypeKind,
+}
+
+impl TypeDefinition {
+    /// Component schema of the type
+    fn schema(&self) -> JsonValue {
+        let mut schema = match &self.kind {
+            TypeKind::Object(fields) => {
+                let properties: Map<String, JsonValue> = fields
+                    .iter()
+                    .map(|field| (field.name.clone(), field.schema()))
+                    .collect();
+                let mut schema = json!({"type": "object", "properties": properties});
+                let required = required_names(fields);
+                if !required.is_empty() {
+                    schema["required"] = json!(required);
+                }
+                schema
+            }
+            TypeKind::Enum(values) => json!({"type": "string", "enum": values}),
+            TypeKind::Union(members) => json!({
+                "oneOf": members
+                    .iter()
+                    .map(|name| TypeRef::Named(name.clone()).schema())
+                    .collect::<Vec<_>>()
+            }),
+            TypeKind::Scalar => json!({"type": "string"}),
+        };
+        if let Some(description) = &self.description {
+            schema["description"] = json!(description);
+        }
+        schema
+    }
+}
+
+/// A parsed GraphQL schema
+#[derive(Debug)]
+struct GraphQlSchema {
+    types: BTreeMap<String, TypeDefinition>,
+    query_type: String,
+    mutation_type: String,
+    subscription_type: String,
+}
+
+/// Parsed representation of a GraphQL schema.
+pub struct GraphQlContext {
+    name: String,
+    schema: GraphQlSchema,
+}
+
+impl GraphQlContext {
+    /// Load a GraphQL schema (SDL) from disk.
+    pub async fn from_file<P: AsRef<Path>>(path: P) -> crate::Result<Self> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path).await?;
+        let schema = parse_schema(&content).map_err(|e| {
+            Error::config(format!(
+                "Failed to parse GraphQL schema {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+        let name = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "GraphQL API".to_string());
+        Ok(Self { name, schema })
+    }
+
+    /// Convert the schema into an OpenAPI 3.0 document.
+    pub fn to_openapi(&self) -> OpenApiContext {
+        let mut paths = Map::new();
+        for (operation_type, root) in [
+            ("query", &self.schema.query_type),
+            ("mutation", &self.schema.mutation_type),
+        ] {
+            let Some(TypeKind::Object(fields)) = self.schema.types.get(root).map(|def| &def.kind)
+            else {
+                continue;
+            };
+            let method = if operation_type == "query" {
+                "get"
+            } else {
+                "post"
+            };
+            for field in fields {
+                paths.insert(
+                    format!("/{}/{}", operation_type, field.name),
+                    json!({method: field_operation(field, operation_type)}),
+                );
+            }
+        }
+
+        let roots = [
+            &self.schema.query_type,
+            &self.schema.mutation_type,
+            &self.schema.subscription_type,
+        ];
+        let schemas: Map<String, JsonValue> = self
+            .schema
+            .types
+            .iter()
+            .filter(|(name, _)| !roots.contains(name))
+            .map(|(name, def)| (name.clone(), def.schema()))
+            .collect();
+
+        OpenApiContext {
+            json: json!({
+                "openapi": "3.0.0",
+                "info": {"title": self.name, "version": "1.0.0"},
+                "servers": [{"url": "/"}],
+                "paths": paths,
+                "components": {"schemas": schemas},
+            }),
+        }
+    }
+}
+
+/// OpenAPI operation for one field of a root type
+fn field_operation(field: &Field, operation_type: &str) -> JsonValue {
+    let mut operation = Map::new();
+    operation.insert("operationId".into(), json!(field.name));
+    if let Some(description) = &field.description {
+        operation.insert("summary".into(), json!(description));
+    }
+    operation.insert("tags".into(), json!([operation_type]));
+    if field.deprecated {
+        operation.insert("deprecated".into(), json!(true));
+    }
+
+    if operation_type == "query" {
+        if !field.arguments.is_empty() {
+            let parameters: Vec<JsonValue> = field
+                .arguments
+                .iter()
+                .map(|argument| {
+                    let mut parameter = json!({
+                        "name": argument.name,
+                        "in": "query",
+                        "required": argument.ty.is_non_null(),
+                        "schema": argument.ty.schema(),
+                    });
+                    if let Some(description) = &argument.description {
+                        parameter["description"] = json!(description);
+                    }
+                    parameter
+                })
+                .collect();
+            operation.insert("parameters".into(), json!(parameters));
+        }
+    } else if !field.arguments.is_empty() {
+        let properties: Map<String, JsonValue> = field
+            .arguments
+            .iter()
+            .map(|argument| (argument.name.clone(), argument.schema()))
+            .collect();
+        let mut schema = json!({"type": "object", "properties": properties});
+        let required = required_names(&field.arguments);
+        if !required.is_empty() {
+            schema["required"] = json!(required);
+        }
+        operation.insert(
+            "requestBody".into(),
+            json!({"required": true, "content": {"application/json": {"schema": schema}}}),
+        );
+    }
+
+    operation.insert(
+        "responses".into(),
+        json!({"200": {
+            "description": "Successful response",
+            "content": {"application/json": {"schema": field.ty.schema()}},
+        }}),
+    );
+    operation.insert(
+        "x-graphql".into(),
+        json!({"operation": operation_type, "field": field.name}),
+    );
+    JsonValue::Object(operation)
+}
+
+/// Names of the non-null fields
+fn required_names(fields: &[Field]) -> Vec<&str> {
+    fields
+        .iter()
+        .filter(|field| field.ty.is_non_null())
+        .map(|field| field.name.as_str())
+        .collect()
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    /// A name, keyword or number
+    Name(String),
+    /// A string or block string
+    Str(String),
+    Punct(char),
+}
+
+/// Split SDL into tokens paired with their line number, dropping whitespace,
+/// commas and comments
+fn tokenize(sdl: &str) -> Result<Vec<(Token, usize)>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = sdl.chars().peekable();
+    let mut line = 1;
+    while let Some(&c) = chars.peek() {
+        match c {
+            '\n' => {
+                line += 1;
+                chars.next();
+            }
+            c if c.is_whitespace() || c == ',' || c == '\u{feff}' => {
+                chars.next();
+            }
+            '#' => {
+                while chars.peek().is_some_and(|&c| c != '\n') {
+                    chars.next();
+                }
+            }
+            '"' => {
+                let start = line;
+                chars.next();
+                if chars.peek() == Some(&'"') {
+                    chars.next();
+                    if chars.peek() == Some(&'"') {
+                        chars.next();
+                        // Block string, up to the closing `"""`
+                        let mut raw = String::new();
+                        loop {
+                            match chars.next() {
+                                Some('"') if raw.ends_with("\"\"") && !raw.ends_with("\\\"\"") => {
+                                    raw.truncate(raw.len() - 2);
+                                    break;
+                                }
+                                Some(c) => {
+                                    if c == '\n' {
+                                        line += 1;
+                                    }
+                                    raw.push(c);
+                                }
+                                None => return Err(format!("line {}: unterminated string", start)),
+                            }
+                        }
+                        tokens.push((Token::Str(block_string_value(&raw)), start));
+                    } else {
+                        tokens.push((Token::Str(String::new()), start));
+                    }
+                    continue;
+                }
+                let mut value = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some('n') => value.push('\n'),
+                            Some('t') => value.push('\t'),
+                            Some('r') => value.push('\r'),
+                            Some('u') => {
+                                let hex: String = (0..4).filter_map(|_| chars.next()).collect();
+                                let c = u32::from_str_radix(&hex, 16)
+                                    .ok()
+                                    .and_then(char::from_u32)
+                                    .ok_or_else(|| {
+                                        format!("line {}: invalid escape \\u{}", line, hex)
+                                    })?;
+                                value.push(c);
+                            }
+                            Some(c) => value.push(c),
+                            None => return Err(format!("line {}: unterminated string", start)),
+                        },
+                        Some('\n') | None => {
+                            return Err(format!("line {}: unterminated string", start))
+                        }
+                        Some(c) => value.push(c),
+                    }
+                }
+                tokens.push((Token::Str(value), start));
+            }
+            c if c.is_ascii_alphanumeric() || c == '_' || c == '-' => {
+                let mut name = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.' | '+') {
+                        name.push(c);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                tokens.push((Token::Name(name), line));
+            }
+            '{' | '}' | '(' | ')' | '[' | ']' | ':' | '!' | '=' | '@' | '|' | '&' | '$' => {
+                tokens.push((Token::Punct(c), line));
+                chars.next();
+            }
+            c => return Err(format!("line {}: unexpected character '{}'", line, c)),
+        }
+    }
+    Ok(tokens)
+}
+
+/// Value of a block string: common indentation and blank first and last lines removed
+fn block_string_value(raw: &str) -> String {
+    let raw = raw.replace("\\\"\"\"", "\"\"\"");
+    let lines: Vec<&str> = raw.lines().collect();
+    let indent = lines
+        .iter()
+        .skip(1)
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or(0);
+    let mut lines: Vec<&str> = lines
+        .iter()
+        .enumerate()
+        .map(|(i, line)| {
+            if i == 0 {
+                line.trim_start()
+            } else {
+                line.get(indent..).unwrap_or("")
+            }
+        })
+        .collect();
+    while lines.first().is_some_and(|line| line.trim().is_empty()) {
+        lines.remove(0);
+    }
+    while lines.last().is_some_and(|line| line.trim().is_empty()) {
+        lines.pop();
+    }
+    lines.join("\n")
+}
+
+/// Parse SDL into the types it defines
+fn parse_schema(sdl: &str) -> Result<GraphQlSchema, String> {
+    let mut parser = Parser {
+        tokens: tokenize(sdl)?,
+        pos: 0,
+    };
+    let mut schema = GraphQlSchema {
+        types: BTreeMap::new(),
+        query_type: "Query".to_string(),
+        mutation_type: "Mutation".to_string(),
+        subscription_type: "Subscription".to_string(),
+    };
+    while parser.peek().is_some() {
+        parser.definition(&mut schema)?;
+    }
+    Ok(schema)
+}
+
+/// Recursive descent parser over SDL tokens
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(token, _)| token)
+    }
+
+    fn error(&self, message: impl std::fmt::Display) -> String {
+        let line = self
+            .tokens
+            .get(self.pos)
+            .or_else(|| self.tokens.last())
+            .map_or(1, |(_, line)| *line);
+        format!("line {}: {}", line, message)
+    }
+
+    fn next(&mut self) -> Result<Token, String> {
+        let token = self
+            .tokens
+            .get(self.pos)
+            .map(|(token, _)| token.clone())
+            .ok_or_else(|| self.error("unexpected end of schema"))?;
+        self.pos += 1;
+        Ok(token)
+    }
+
+    fn eat(&mut self, punct: char) -> bool {
+        let found = self.peek() == Some(&Token::Punct(punct));
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        let found = matches!(self.peek(), Some(Token::Name(name)) if name == keyword);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn expect(&mut self, punct: char) -> Result<(), String> {
+        if self.eat(punct) {
+            Ok(())
+        } else {
+            Err(self.error(format!("expected '{}'", punct)))
+        }
+    }
+
+    fn name(&mut self) -> Result<String, String> {
+        match self.peek() {
+            Some(Token::Name(name)) => {
+                let name = name.clone();
+                self.pos += 1;
+                Ok(name)
+            }
+            _ => Err(self.error("expected a name")),
+        }
+    }
+
+    fn description(&mut self) -> Option<String> {
+        match self.peek() {
+            Some(Token::Str(description)) => {
+                let description = description.clone();
+                self.pos += 1;
+                Some(description)
+            }
+            _ => None,
+        }
+    }
+
+    /// One top-level definition, added to `schema`
+    fn definition(&mut self, schema: &mut GraphQlSchema) -> Result<(), String> {
+        let description = self.description();
+        let mut keyword = self.name()?;
+        if keyword == "extend" {
+            keyword = self.name()?;
+        }
+        match keyword.as_str() {
+            "schema" => {
+                self.directives()?;
+                self.expect('{')?;
+                while !self.eat('}') {
+                    let operation = self.name()?;
+                    self.expect(':')?;
+                    let root = self.name()?;
+                    match operation.as_str() {
+                        "query" => schema.query_type = root,
+                        "mutation" => schema.mutation_type = root,
+                        "subscription" => schema.subscription_type = root,
+                        other => return Err(self.error(format!("unknown operation '{}'", other))),
+                    }
+                }
+            }
+            "type" | "interface" | "input" => {
+                let name = self.name()?;
+                if self.eat_keyword("implements") {
+                    self.eat('&');
+                    self.name()?;
+                    while self.eat('&') {
+                        self.name()?;
+                    }
+                }
+                self.directives()?;
+                let mut fields = Vec::new();
+                if self.eat('{') {
+                    while !self.eat('}') {
+                        fields.push(self.field()?);
+                    }
+                }
+                add_type(schema, name, description, TypeKind::Object(fields));
+            }
+            "enum" => {
+                let name = self.name()?;
+                self.directives()?;
+                let mut values = Vec::new();
+                if self.eat('{') {
+                    while !self.eat('}') {
+                        self.description();
+                        values.push(self.name()?);
+                        self.directives()?;
+                    }
+                }
+                add_type(schema, name, description, TypeKind::Enum(values));
+            }
+            "union" => {
+                let name = self.name()?;
+                self.directives()?;
+                let mut members = Vec::new();
+                if self.eat('=') {
+                    self.eat('|');
+                    members.push(self.name()?);
+                    while self.eat('|') {
+                        members.push(self.name()?);
+                    }
+                }
+                add_type(schema, name, description, TypeKind::Union(members));
+            }
+            "scalar" => {
+                let name = self.name()?;
+                self.directives()?;
+                if !BUILT_IN_SCALARS.iter().any(|(n, _, _)| *n == name) {
+                    add_type(schema, name, description, TypeKind::Scalar);
+                }
+            }
+            "directive" => {
+                self.expect('@')?;
+                self.name()?;
+                if self.eat('(') {
+                    while !self.eat(')') {
+                        self.field()?;
+                    }
+                }
+                self.eat_keyword("repeatable");
+                if !self.eat_keyword("on") {
+                    return Err(self.error("expected 'on'"));
+                }
+                self.eat('|');
+                self.name()?;
+                while self.eat('|') {
+                    self.name()?;
+                }
+            }
+            other => {
+                self.pos -= 1;
+                return Err(self.error(format!("unsupported definition '{}'", other)));
+            }
+        }
+        Ok(())
+    }
+
+    /// A field or argument definition: `name(args): Type = default @directives`
+    fn field(&mut self) -> Result<Field, String> {
+        let description = self.description();
+        let name = self.name()?;
+        let mut arguments = Vec::new();
+        if self.eat('(') {
+            while !self.eat(')') {
+                arguments.push(self.field()?);
+            }
+        }
+        self.expect(':')?;
+        let ty = self.type_ref()?;
+        if self.eat('=') {
+            self.skip_value()?;
+        }
+        let deprecated = self.directives()?;
+        Ok(Field {
+            name,
+            description,
+            arguments,
+            ty,
+            deprecated,
+        })
+    }
+
+    fn type_ref(&mut self) -> Result<TypeRef, String> {
+        let ty = if self.eat('[') {
+            let inner = self.type_ref()?;
+            self.expect(']')?;
+            TypeRef::List(Box::new(inner))
+        } else {
+            TypeRef::Named(self.name()?)
+        };
+        Ok(if self.eat('!') {
+            TypeRef::NonNull(Box::new(ty))
+        } else {
+            ty
+        })
+    }
+
+    /// Skip any directives, returning whether one of them is `@deprecated`
+    fn directives(&mut self) -> Result<bool, String> {
+        let mut deprecated = false;
+        while self.eat('@') {
+            deprecated |= self.name()? == "deprecated";
+            if self.peek() == Some(&Token::Punct('(')) {
+                self.skip_value()?;
+            }
+        }
+        Ok(deprecated)
+    }
+
+    /// Skip a value, or a bracketed group of them
+    fn skip_value(&mut self) -> Result<(), String> {
+        let close = match self.next()? {
+            Token::Punct('[') => ']',
+            Token::Punct('{') => '}',
+            Token::Punct('(') => ')',
+            Token::Punct('$') => return self.name().map(drop),
+            Token::Name(_) | Token::Str(_) => return Ok(()),
+            Token::Punct(c) => {
+                self.pos -= 1;
+                return Err(self.error(format!("unexpected '{}'", c)));
+            }
+        };
+        while !self.eat(close) {
+            self.skip_value()?;
+            self.eat(':');
+        }
+        Ok(())
+    }
+}
+
+/// Add a type definition, merging it into an existing one of the same name
+/// (as `extend` does)
+fn add_type(schema: &mut GraphQlSchema, name: String, description: Option<String>, kind: TypeKind) {
+    match schema.types.get_mut(&name) {
+        Some(existing) => {
+            match (&mut existing.kind, kind) {
+                (TypeKind::Object(fields), TypeKind::Object(more)) => fields.extend(more),
+                (TypeKind::Enum(values), TypeKind::Enum(more)) => values.extend(more),
+                (TypeKind::Union(members), TypeKind::Union(more)) => members.extend(more),
+                (kind, more) => *kind = more,
+            }
+            if existing.description.is_none() {
+                existing.description = description;
+            }
+        }
+        None => {
+            schema
+                .types
+                .insert(name, TypeDefinition { description, kind });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{templates::TemplateKind, Config, TemplateManager};
+    use std::path::PathBuf;
+
+    #[tokio::test]
+    async fn test_queries_and_mutations_become_operations() -> crate::Result<()> {
+        let manifest = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        let base = manifest.parent().unwrap().parent().unwrap();
+        let graphql =
+            GraphQlContext::from_file(base.join("tests/fixtures/graphql/petstore.graphql")).await?;
+        let spec = graphql.to_openapi();
+
+        let operations = spec.parse_operations().await?;
+        let mut ids: Vec<(&str, &str)> = operations
+            .iter()
+            .map(|op| (op.method.as_str(), op.id.as_str()))
+            .collect();
+        ids.sort();
+        assert_eq!(
+            ids,
+            vec![
+                ("get", "pet"),
+                ("get", "petCount"),
+                ("get", "pets"),
+                ("get", "search"),
+                ("post", "addPet"),
+                ("post", "deletePet"),
+                ("post", "renamePet"),
+            ]
+        );
+
+        let pets = &spec.json["paths"]["/query/pets"]["get"];
+        assert_eq!(pets["summary"], "List pets, optionally filtered by status");
+        assert_eq!(pets["parameters"][0]["name"], "status");
+        assert_eq!(pets["parameters"][0]["required"], false);
+        assert_eq!(
+            pets["parameters"][0]["schema"],
+            json!({"$ref": "#/components/schemas/PetStatus"})
+        );
+        assert_eq!(
+            pets["responses"]["200"]["content"]["application/json"]["schema"],
+            json!({"type": "array", "items": {"$ref": "#/components/schemas/Pet"}})
+        );
+        assert_eq!(
+            pets["x-graphql"],
+            json!({"operation": "query", "field": "pets"})
+        );
+        assert_eq!(
+            spec.json["paths"]["/query/petCount"]["get"]["deprecated"],
+            true
+        );
+
+        let rename = &spec.json["paths"]["/mutation/renamePet"]["post"];
+        let body = &rename["requestBody"]["content"]["application/json"]["schema"];
+        assert_eq!(body["required"], json!(["id", "name"]));
+        assert_eq!(body["properties"]["name"], json!({"type": "string"}));
+
+        let schemas = &spec.json["components"]["schemas"];
+        assert!(schemas.get("Query").is_none());
+        assert!(schemas.get("Mutation").is_none());
+        assert_eq!(schemas["Pet"]["description"], "A pet in the store");
+        assert_eq!(schemas["Pet"]["required"], json!(["id", "name", "owners"]));
+        assert_eq!(
+            schemas["PetStatus"]["enum"],
+            json!(["AVAILABLE", "PENDING", "SOLD"])
+        );
+        assert_eq!(
+            schemas["SearchResult"]["oneOf"][1]["$ref"],
+            "#/components/schemas/Owner"
+        );
+
+        let temp_dir = tempfile::tempdir()?;
+        let out = temp_dir.path();
+        let manager =
+            TemplateManager::new(TemplateKind::RustAxum, Some(base.join("templates"))).await?;
+        let mut config = Config::new("petstore", "petstore.graphql", out.to_string_lossy());
+        config.base_url = url::Url::parse("https://petstore.example.com").ok();
+        manager.generate(&spec, &config, None).await?;
+        for handler in ["pets", "add_pet", "rename_pet"] {
+            assert!(out.join(format!("src/handlers/{}.rs", handler)).is_file());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_errors_report_the_line() {
+        let err = parse_schema("type Query {\n  pet(id: ID!) Pet\n}").unwrap_err();
+        assert_eq!(err, "line 2: expected ':'");
+        let err = parse_schema("query { pets }").unwrap_err();
+        assert_eq!(err, "line 1: unsupported definition 'query'");
+    }
+}
@@ -7,19 +7,35 @@ pub mod builders;
 pub mod config;
 pub mod error;
 pub mod generate;
+pub mod graphql;
 pub mod har;
 pub mod manifest;
 pub mod openapi;
+pub mod patch;
+pub mod postman;
+pub mod report;
+pub mod source;
 pub mod templates;
+pub mod tools;
+pub mod upgrade;
 pub mod utils;
 
 pub use crate::{
     config::Config,
     error::{Error, Result},
     generate::generate,
+    graphql::GraphQlContext,
     har::{HarContext, HarOperation},
-    openapi::OpenApiContext,
-    templates::{TemplateDir, TemplateKind, TemplateManager, TemplateOptions},
+    openapi::{OpenApiContext, SpecDiff, SpecSummary, TagSummary},
+    postman::PostmanContext,
+    report::{GenerationReport, Warning, WarningKind},
+    source::SpecSource,
+    templates::{
+        FilenameCase, Layout, LineEnding, OperationIdStrategy, RequestBodyNaming, SchemaJsonStyle,
+        SpecPreprocessor, TemplateDir, TemplateKind, TemplateManager, TemplateOptions,
+    },
+    tools::ToolsContext,
+    upgrade::{upgrade, UpgradeReport},
 };
 
 /// Result type for Agenterra generation operations
@@ -1,12 +1,20 @@
 //! Manifest file format for Agenterra templates.
 //!
 //! This module defines the structure of the `template.yaml` file that describes
-//! how to generate code from templates.
+//! how to generate code from templates, and the [`GenerationManifest`] recorded
+//! in each output directory.
+
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
 
 use serde::{Deserialize, Deserializer, Serialize};
 use serde_value::Value as SerdeValue;
 use tokio::fs;
 
+use crate::{config::Config, source::SpecSource, templates::TemplateOptions};
+
 /// The root manifest structure for a template.
 ///
 /// This describes the template's metadata and the files it contains.
@@ -41,10 +49,22 @@ pub struct TemplateFile {
     /// Destination path for the generated file, relative to the output directory
     pub destination: String,
 
-    /// Optional directive for generating multiple files (e.g., "operation")
+    /// Optional directive for generating multiple files: `operation` (or
+    /// `endpoint`), `tag`, or `all` for one file covering every operation
     #[serde(default)]
     pub for_each: Option<String>,
 
+    /// Optional context flag gating this file; the file is only generated when
+    /// the named context value is `true` (e.g. `with_dockerfile`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub when: Option<String>,
+
+    /// Workspace member the file belongs to; with the workspace layout the
+    /// destination is relative to `crates/{crate}/` instead of the output
+    /// directory. `{project_name}` is replaced with the project name.
+    #[serde(default, rename = "crate", skip_serializing_if = "Option::is_none")]
+    pub crate_name: Option<String>,
+
     /// Additional context to pass to the template
     #[serde(default)]
     pub context: serde_json::Value,
@@ -60,6 +80,11 @@ pub struct TemplateHooks {
     /// Commands to run after code generation
     #[serde(default, deserialize_with = "deserialize_commands")]
     pub post_generate: Vec<String>,
+
+    /// Commands to run after `post_generate`, only when scaffolding a fresh
+    /// project (no [`GenerationManifest`] in the output directory yet)
+    #[serde(default, deserialize_with = "deserialize_commands")]
+    pub post_generate_once: Vec<String>,
 }
 
 impl Default for TemplateManifest {
@@ -81,6 +106,8 @@ impl Default for TemplateFile {
             source: String::new(),
             destination: String::new(),
             for_each: None,
+            when: None,
+            crate_name: None,
             context: serde_json::Value::Null,
         }
     }
@@ -130,6 +157,139 @@ impl TemplateManifest {
     }
 }
 
+/// File name of the [`GenerationManifest`] inside an output directory
+pub const GENERATION_MANIFEST_FILE: &str = ".agenterra-manifest.json";
+
+/// Record of the last successful generation into an output directory.
+///
+/// Its presence tells a regeneration (e.g. a `--watch` rebuild) apart from
+/// scaffolding a fresh project.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GenerationManifest {
+    /// Name of the template that generated the project
+    pub template: String,
+
+    /// Version of that template
+    pub template_version: String,
+
+    /// Version of Agenterra that generated the project
+    pub generator_version: String,
+
+    /// Configuration the project was generated with, so it can be regenerated
+    /// (see [`upgrade`](crate::upgrade::upgrade)), with local paths made
+    /// absolute; absent in older manifests
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub config: Option<Config>,
+
+    /// Template options the project was generated with
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template_options: Option<TemplateOptions>,
+
+    /// Hash of the spec, template and options the output was generated from;
+    /// a regeneration with the same hash is skipped
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub inputs_hash: Option<String>,
+
+    /// What the template rendered for each generated file, by path relative to
+    /// the output directory, before append-only merging and hooks; the baseline
+    /// an [`upgrade`](crate::upgrade::upgrade) diffs template changes against
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub files: BTreeMap<String, String>,
+}
+
+impl GenerationManifest {
+    /// Create a record for a generation with the given template
+    pub fn new(template: &TemplateManifest) -> Self {
+        Self {
+            template: template.name.clone(),
+            template_version: template.version.clone(),
+            generator_version: env!("CARGO_PKG_VERSION").to_string(),
+            config: None,
+            template_options: None,
+            inputs_hash: None,
+            files: BTreeMap::new(),
+        }
+    }
+
+    /// Record the configuration and template options of the generation
+    pub fn with_inputs(mut self, config: &Config, template_opts: &Option<TemplateOptions>) -> Self {
+        let mut config = config.clone();
+        // Relative paths would only resolve from the directory generation ran in
+        config.openapi_schema_path = absolute_location(&config.openapi_schema_path);
+        config.template_dir = config.template_dir.map(|dir| {
+            absolute_path(Path::new(&dir))
+                .to_string_lossy()
+                .into_owned()
+        });
+        self.config = Some(config);
+        self.template_options = template_opts.clone();
+        self
+    }
+
+    /// Record the rendered content of generated files, given by their paths
+    /// under `output_dir`
+    pub fn with_files(mut self, output_dir: &Path, files: BTreeMap<PathBuf, String>) -> Self {
+        for (path, content) in files {
+            let relative = path.strip_prefix(output_dir).unwrap_or(&path);
+            let name = relative.to_string_lossy().replace('\\', "/");
+            self.files.insert(name, content);
+        }
+        self
+    }
+
+    /// Record the hash of the generation's inputs
+    pub fn with_inputs_hash(mut self, inputs_hash: String) -> Self {
+        self.inputs_hash = Some(inputs_hash);
+        self
+    }
+
+    /// Load the manifest from `output_dir`, or `None` if nothing was generated there yet.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file exists but can't be read or parsed.
+    pub async fn load(output_dir: &std::path::Path) -> Result<Option<Self>, crate::Error> {
+        let path = output_dir.join(GENERATION_MANIFEST_FILE);
+        let content = match fs::read_to_string(&path).await {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        serde_json::from_str(&content).map(Some).map_err(|e| {
+            crate::Error::template(format!(
+                "Invalid generation manifest at {}: {}",
+                path.display(),
+                e
+            ))
+        })
+    }
+
+    /// Write the manifest into `output_dir`
+    pub async fn save(&self, output_dir: &std::path::Path) -> Result<(), crate::Error> {
+        let content = serde_json::to_string_pretty(self)?;
+        fs::write(output_dir.join(GENERATION_MANIFEST_FILE), content).await?;
+        Ok(())
+    }
+}
+
+/// `path` made absolute against the current directory, or as-is if that fails
+fn absolute_path(path: &Path) -> PathBuf {
+    std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// A spec location with its local file or archive path made absolute
+fn absolute_location(location: &str) -> String {
+    match location.parse::<SpecSource>() {
+        Ok(SpecSource::File(path)) => SpecSource::File(absolute_path(&path)).to_string(),
+        Ok(SpecSource::Archive { path, entry }) => SpecSource::Archive {
+            path: absolute_path(&path),
+            entry,
+        }
+        .to_string(),
+        _ => location.to_string(),
+    }
+}
+
 /// Helper function to deserialize either a single command or a list of commands
 fn deserialize_commands<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
 where
@@ -26,14 +26,81 @@
 //! ```
 
 // Internal imports (std, crate)
-use std::path::Path;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::{Component, Path, PathBuf},
+};
 
-use crate::Error;
+use crate::{
+    report::{Warning, WarningKind},
+    templates::OperationIdStrategy,
+    Error,
+};
 
 // External imports (alphabetized)
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value as JsonValue};
-use tokio::fs;
+use tokio::{
+    fs,
+    io::{AsyncRead, AsyncReadExt},
+};
+
+/// How deeply `allOf` compositions (including through `$ref`s) are merged
+/// before giving up, so self-referencing compositions terminate
+const MAX_ALL_OF_DEPTH: usize = 32;
+
+/// Local reference prefixes holding reusable schema definitions, paired with
+/// the JSON pointer of the section they resolve into
+const SCHEMA_REF_ROOTS: &[(&str, &str)] = &[
+    ("#/components/schemas/", "/components/schemas"),
+    ("#/$defs/", "/$defs"),
+];
+
+/// JSON pointer of the whole definition a local `$ref` points into, e.g.
+/// `/components/schemas/Pet` for `#/components/schemas/Pet/properties/id`
+fn definition_pointer(ref_str: &str) -> Option<String> {
+    let segments: Vec<&str> = ref_str.strip_prefix("#/")?.split('/').collect();
+    let depth = if segments.first() == Some(&"components") {
+        3
+    } else {
+        2
+    };
+    (segments.len() >= depth).then(|| format!("/{}", segments[..depth].join("/")))
+}
+
+/// Escape a key for use as a JSON pointer segment
+fn escape_pointer(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+/// Set the value at a JSON pointer, creating intermediate objects
+fn insert_at_pointer(root: &mut JsonValue, pointer: &str, value: JsonValue) {
+    let segments: Vec<String> = pointer
+        .trim_start_matches('/')
+        .split('/')
+        .map(|s| s.replace("~1", "/").replace("~0", "~"))
+        .collect();
+    let Some((last, parents)) = segments.split_last() else {
+        return;
+    };
+    let mut node = root;
+    for segment in parents {
+        if !node.get(segment).is_some_and(JsonValue::is_object) {
+            node[segment.as_str()] = json!({});
+        }
+        node = &mut node[segment.as_str()];
+    }
+    node[last.as_str()] = value;
+}
+
+/// HTTP methods that may appear as operations on an OpenAPI path item
+pub const HTTP_METHODS: &[&str] = &[
+    "get", "put", "post", "delete", "options", "head", "patch", "trace",
+];
+
+/// Default limit on the size of a spec fetched from a URL or read with
+/// [`OpenApiContext::from_reader`]: 32 MiB
+pub const DEFAULT_MAX_SPEC_BYTES: u64 = 32 * 1024 * 1024;
 
 /// Represents an OpenAPI specification
 #[derive(Debug, serde::Serialize)]
@@ -60,7 +127,21 @@ impl OpenApiContext {
     /// Create a new OpenAPISpec from a file (supports both YAML and JSON)
     pub async fn from_file<P: AsRef<Path>>(path: P) -> crate::Result<Self> {
         let path = path.as_ref();
-        let content = fs::read_to_string(path).await?;
+        let content = fs::read_to_string(path).await.map_err(|e| {
+            if e.kind() == std::io::ErrorKind::InvalidData {
+                crate::Error::openapi(format!(
+                    "file at {} is not a valid text OpenAPI spec (not UTF-8); did you mean to pass a bundle{}?",
+                    path.display(),
+                    if path.extension().is_some_and(|ext| ext == "zip") {
+                        format!(" entry, e.g. {}#openapi.yaml", path.display())
+                    } else {
+                        String::new()
+                    }
+                ))
+            } else {
+                e.into()
+            }
+        })?;
         Self::parse_content(&content).map_err(|e| {
             crate::Error::openapi(format!(
                 "Failed to parse OpenAPI spec at {}: {}",
@@ -72,7 +153,13 @@ impl OpenApiContext {
 
     /// Create a new OpenAPISpec from a URL (supports both YAML and JSON)
     pub async fn from_url(url: &str) -> crate::Result<Self> {
-        let response = reqwest::get(url).await.map_err(|e| {
+        Self::from_url_with_limit(url, DEFAULT_MAX_SPEC_BYTES).await
+    }
+
+    /// Like [`from_url`](Self::from_url), aborting the download as soon as the
+    /// response body exceeds `max_bytes`
+    pub async fn from_url_with_limit(url: &str, max_bytes: u64) -> crate::Result<Self> {
+        let mut response = reqwest::get(url).await.map_err(|e| {
             crate::Error::openapi(format!("Failed to fetch OpenAPI spec from {}: {}", url, e))
         })?;
 
@@ -84,25 +171,114 @@ impl OpenApiContext {
             )));
         }
 
-        let content = response.text().await.map_err(|e| {
+        let too_large = || {
+            crate::Error::openapi(format!(
+                "OpenAPI spec at {} is larger than the {} byte limit",
+                url, max_bytes
+            ))
+        };
+        if response.content_length().is_some_and(|len| len > max_bytes) {
+            return Err(too_large());
+        }
+        let mut body = Vec::new();
+        while let Some(chunk) = response.chunk().await.map_err(|e| {
             crate::Error::openapi(format!("Failed to read response from {}: {}", url, e))
-        })?;
+        })? {
+            if (body.len() + chunk.len()) as u64 > max_bytes {
+                return Err(too_large());
+            }
+            body.extend_from_slice(&chunk);
+        }
+        let content = String::from_utf8_lossy(&body);
 
         Self::parse_content(&content).map_err(|e| {
             crate::Error::openapi(format!("Failed to parse OpenAPI spec from {}: {}", url, e))
         })
     }
 
+    /// Read a spec (YAML or JSON) from any async source, such as stdin or a
+    /// decompressed stream.
+    ///
+    /// At most `max_bytes` are read, so untrusted input can't exhaust memory;
+    /// a larger spec is an error.
+    pub async fn from_reader<R: AsyncRead + Unpin>(
+        reader: R,
+        max_bytes: u64,
+    ) -> crate::Result<Self> {
+        let mut content = Vec::new();
+        reader
+            .take(max_bytes.saturating_add(1))
+            .read_to_end(&mut content)
+            .await?;
+        if content.len() as u64 > max_bytes {
+            return Err(Error::openapi(format!(
+                "OpenAPI spec is larger than the {} byte limit",
+                max_bytes
+            )));
+        }
+        let content = String::from_utf8(content)
+            .map_err(|_| Error::openapi("OpenAPI spec is not valid UTF-8"))?;
+        Self::parse_content(&content)
+            .map_err(|e| Error::openapi(format!("Failed to parse OpenAPI spec: {}", e)))
+    }
+
+    /// Read the spec from an entry of a zip archive, e.g. `openapi.yaml` in
+    /// `bundle.zip`.
+    ///
+    /// External `$ref`s are resolved against the other entries of the archive,
+    /// relative to the referencing entry, and inlined as by [`Self::bundle`].
+    pub async fn from_archive(archive: impl AsRef<Path>, entry: &str) -> crate::Result<Self> {
+        let archive = archive.as_ref().to_path_buf();
+        let entry = entry.to_string();
+        tokio::task::spawn_blocking(move || {
+            let entries = read_archive(&archive)?;
+            let root = normalize_path(Path::new(&entry));
+            let content = entries.get(&root).ok_or_else(|| {
+                Error::openapi(format!("{} has no entry '{}'", archive.display(), entry))
+            })?;
+            let mut json = Self::parse_content(content)
+                .map_err(|e| {
+                    Error::openapi(format!(
+                        "Failed to parse OpenAPI spec at {}#{}: {}",
+                        archive.display(),
+                        entry,
+                        e
+                    ))
+                })?
+                .json;
+            let base = RefBase::Dir(root.parent().map(Path::to_path_buf).unwrap_or_default());
+            let mut documents = RefDocuments {
+                archive: Some(entries),
+                ..Default::default()
+            };
+            inline_external_refs(&mut json, &base, None, &mut documents, &mut Vec::new())?;
+            Ok(Self { json })
+        })
+        .await
+        .map_err(|e| Error::openapi(format!("Failed to read spec archive: {}", e)))?
+    }
+
     /// Parse content as either JSON or YAML
-    fn parse_content(content: &str) -> Result<Self, String> {
+    pub(crate) fn parse_content(content: &str) -> Result<Self, String> {
         // Try to parse as JSON first
         if let Ok(json) = serde_json::from_str(content) {
             return Ok(Self { json });
         }
 
         // If JSON parsing fails, try YAML
-        if let Ok(json) = serde_yaml::from_str(content) {
-            return Ok(Self { json });
+        let yaml_err = match serde_yaml::from_str(content) {
+            Ok(json) => return Ok(Self { json }),
+            Err(e) => e,
+        };
+
+        // Tabs are the most common reason hand-written YAML fails to parse
+        if !content.trim_start().starts_with('{') {
+            if let Some(line) = tab_indented_line(content, &yaml_err) {
+                return Err(format!(
+                    "YAML spec uses tabs for indentation at line {}; YAML requires spaces",
+                    line
+                ));
+            }
         }
 
         // If both parsers fail, return an error
@@ -162,45 +338,111 @@ impl OpenApiContext {
         None
     }
 
+    /// URL of the `index`th entry of the root `servers` array
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the spec declares fewer servers, or the entry has no URL.
+    pub fn server_url(&self, index: usize) -> crate::Result<String> {
+        let servers = self
+            .json
+            .get("servers")
+            .and_then(JsonValue::as_array)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        let server = servers.get(index).ok_or_else(|| {
+            Error::openapi(format!(
+                "Server index {} is out of range: the spec declares {} server(s)",
+                index,
+                servers.len()
+            ))
+        })?;
+        server
+            .get("url")
+            .and_then(JsonValue::as_str)
+            .map(String::from)
+            .ok_or_else(|| Error::openapi(format!("Server {} has no url", index)))
+    }
+
     /// Parse all endpoints into structured contexts for template rendering
     pub async fn parse_operations(&self) -> crate::Result<Vec<OpenApiOperation>> {
+        self.parse_operations_with(OperationIdStrategy::default())
+            .await
+    }
+
+    /// Like [`parse_operations`](Self::parse_operations), synthesizing missing
+    /// operationIds with `strategy`
+    pub async fn parse_operations_with(
+        &self,
+        strategy: OperationIdStrategy,
+    ) -> crate::Result<Vec<OpenApiOperation>> {
         let mut operations = Vec::new();
+        let ids: BTreeMap<(&str, &str), String> = self
+            .identified_operations(strategy)
+            .into_iter()
+            .map(|(path, method, _, id)| ((path, method), id))
+            .collect();
+        // Explicit operationIds and the operations declaring them, to catch duplicates
+        let mut declared_ids: BTreeMap<String, Vec<String>> = BTreeMap::new();
         // Expect 'paths' object
         let paths = self
             .json
             .get("paths")
             .and_then(JsonValue::as_object)
             .ok_or_else(|| Error::openapi("Missing 'paths' object"))?;
-        for (path, item) in paths {
+        for (key, item) in paths {
+            // Routes need a leading slash even when the spec's key lacks one
+            let path = &with_leading_slash(key);
             // Handle both GET and POST operations
             for method in ["get", "post"] {
-                if let Some(method_item) = item.get(method).and_then(JsonValue::as_object) {
-                    let operation_id = method_item
-                        .get("operationId")
-                        .and_then(JsonValue::as_str)
-                        .map(String::from)
-                        .unwrap_or_else(|| {
-                            format!(
-                                "{}_{}",
-                                method,
-                                path.trim_start_matches('/').replace('/', "_")
-                            )
-                        });
+                let operation = item
+                    .get(method)
+                    .map(|op| self.resolve_operation_ref(op))
+                    .unwrap_or(&JsonValue::Null);
+                if let Some(method_item) = operation.as_object() {
+                    let explicit_id = method_item.get("operationId").and_then(JsonValue::as_str);
+                    if let Some(id) = explicit_id {
+                        declared_ids
+                            .entry(id.to_string())
+                            .or_default()
+                            .push(format!("{} {}", method.to_uppercase(), path));
+                    }
+                    let operation_id = ids[&(key.as_str(), method)].clone();
 
+                    // Path-item level docs apply to operations that don't declare their own
                     let summary = method_item
                         .get("summary")
+                        .or_else(|| item.get("summary"))
                         .and_then(JsonValue::as_str)
                         .map(String::from);
                     let description = method_item
                         .get("description")
+                        .or_else(|| item.get("description"))
                         .and_then(JsonValue::as_str)
                         .map(String::from);
                     let external_docs = method_item.get("externalDocs").cloned();
-                    let parameters = self.extract_parameters(item);
-                    let request_body = method_item.get("requestBody").cloned();
+                    let parameters = self.operation_parameters(item, operation);
+                    let mut request_body = method_item.get("requestBody").cloned();
+                    if let Some(content) = request_body
+                        .as_mut()
+                        .and_then(|body| body.get_mut("content"))
+                        .and_then(JsonValue::as_object_mut)
+                    {
+                        for media in content.values_mut() {
+                            if let Some(examples) =
+                                media.get_mut("examples").and_then(JsonValue::as_object_mut)
+                            {
+                                self.resolve_example_refs(examples.values_mut());
+                            }
+                        }
+                    }
                     let responses = self.extract_responses(method_item);
                     let callbacks = method_item.get("callbacks").cloned();
-                    let deprecated = method_item.get("deprecated").and_then(JsonValue::as_bool);
+                    // A deprecated path item deprecates operations that don't say otherwise
+                    let deprecated = method_item
+                        .get("deprecated")
+                        .or_else(|| item.get("deprecated"))
+                        .and_then(JsonValue::as_bool);
                     let security = method_item
                         .get("security")
                         .and_then(JsonValue::as_array)
@@ -223,7 +465,7 @@ impl OpenApiContext {
                     operations.push(OpenApiOperation {
                         id: operation_id,
                         method: method.to_string(),
-                        path: path.clone(),
+                        path: path.to_string(),
                         summary,
                         description,
                         external_docs,
@@ -240,9 +482,631 @@ impl OpenApiContext {
                 }
             }
         }
+
+        let duplicates: Vec<String> = declared_ids
+            .iter()
+            .filter(|(_, locations)| locations.len() > 1)
+            .map(|(id, locations)| format!("'{}' ({})", id, locations.join(", ")))
+            .collect();
+        if !duplicates.is_empty() {
+            return Err(Error::openapi(format!(
+                "Duplicate operationId(s) in spec: {}",
+                duplicates.join("; ")
+            )));
+        }
+
         Ok(operations)
     }
 
+    /// Iterate over the raw operations in the spec as `(path, method, operation)` triples.
+    ///
+    /// Unlike [`parse_operations`](Self::parse_operations), this covers every HTTP
+    /// method and borrows the operation JSON as-is, so nothing is lost in mapping.
+    pub fn paths(&self) -> impl Iterator<Item = (&str, &str, &JsonValue)> {
+        self.json
+            .get("paths")
+            .and_then(JsonValue::as_object)
+            .into_iter()
+            .flatten()
+            .flat_map(move |(path, item)| {
+                HTTP_METHODS.iter().filter_map(move |method| {
+                    item.get(*method)
+                        .map(|op| self.resolve_operation_ref(op))
+                        .filter(|op| op.is_object())
+                        .map(|op| (path.as_str(), *method, op))
+                })
+            })
+    }
+
+    /// Every operation in the spec as `(path, method, operation, id)`.
+    ///
+    /// Operations without an operationId get one synthesized by `strategy`;
+    /// a synthesized ID that is already taken gets a `_2`, `_3`, ... suffix,
+    /// so two operations never share an ID.
+    fn identified_operations(
+        &self,
+        strategy: OperationIdStrategy,
+    ) -> Vec<(&str, &str, &JsonValue, String)> {
+        fn explicit_id(op: &JsonValue) -> Option<&str> {
+            op.get("operationId").and_then(JsonValue::as_str)
+        }
+        let mut taken: BTreeSet<String> = self
+            .paths()
+            .filter_map(|(_, _, op)| explicit_id(op).map(String::from))
+            .collect();
+        self.paths()
+            .map(|(path, method, op)| {
+                let id = match explicit_id(op) {
+                    Some(id) => id.to_string(),
+                    None => {
+                        let base = synthesized_operation_id(strategy, method, path, op);
+                        let id = std::iter::once(base.clone())
+                            .chain((2..).map(|n| format!("{}_{}", base, n)))
+                            .find(|id| !taken.contains(id))
+                            .expect("unbounded suffixes");
+                        taken.insert(id.clone());
+                        id
+                    }
+                };
+                (path, method, op, id)
+            })
+            .collect()
+    }
+
+    /// Diff this spec against a newer version of it.
+    ///
+    /// Operations are matched by operationId (or the ID synthesized from method
+    /// and path when none is declared) across every HTTP method, and count as
+    /// modified when their JSON, path or path-level fields differ. Schemas are
+    /// compared by name only.
+    pub fn diff(&self, other: &OpenApiContext) -> SpecDiff {
+        self.diff_with(other, OperationIdStrategy::default())
+    }
+
+    /// Like [`diff`](Self::diff), matching operations without an operationId
+    /// by the ID `strategy` synthesizes
+    pub fn diff_with(&self, other: &OpenApiContext, strategy: OperationIdStrategy) -> SpecDiff {
+        let (before, after) = (
+            self.operations_by_id(strategy),
+            other.operations_by_id(strategy),
+        );
+        let (schemas_before, schemas_after) = (self.schema_names(), other.schema_names());
+        SpecDiff {
+            added_operations: after
+                .keys()
+                .filter(|id| !before.contains_key(*id))
+                .cloned()
+                .collect(),
+            removed_operations: before
+                .keys()
+                .filter(|id| !after.contains_key(*id))
+                .cloned()
+                .collect(),
+            modified_operations: before
+                .iter()
+                .filter(|(id, op)| after.get(*id).is_some_and(|new| new != *op))
+                .map(|(id, _)| id.clone())
+                .collect(),
+            added_schemas: schemas_after.difference(&schemas_before).cloned().collect(),
+            removed_schemas: schemas_before.difference(&schemas_after).cloned().collect(),
+        }
+    }
+
+    /// Raw operation JSON keyed by (possibly synthesized) operationId, along
+    /// with its path, method and the path item's shared fields (such as
+    /// path-level parameters), which all shape the generated operation
+    fn operations_by_id(&self, strategy: OperationIdStrategy) -> BTreeMap<String, JsonValue> {
+        self.identified_operations(strategy)
+            .into_iter()
+            .map(|(path, method, op, id)| {
+                let shared: serde_json::Map<String, JsonValue> = self
+                    .json
+                    .pointer(&format!("/paths/{}", escape_pointer(path)))
+                    .and_then(JsonValue::as_object)
+                    .into_iter()
+                    .flatten()
+                    .filter(|(key, _)| !HTTP_METHODS.contains(&key.as_str()))
+                    .map(|(key, value)| (key.clone(), value.clone()))
+                    .collect();
+                let definition = json!({
+                    "path": path,
+                    "method": method,
+                    "path_item": shared,
+                    "operation": op,
+                });
+                (id, definition)
+            })
+            .collect()
+    }
+
+    /// Names of all reusable schema definitions
+    fn schema_names(&self) -> BTreeSet<String> {
+        SCHEMA_REF_ROOTS
+            .iter()
+            .filter_map(|(_, pointer)| self.json.pointer(pointer)?.as_object())
+            .flat_map(|schemas| schemas.keys().cloned())
+            .collect()
+    }
+
+    /// Overview of the spec: metadata, operation counts and tag usage
+    pub fn summary(&self) -> SpecSummary {
+        let info = self.json.get("info");
+        let text = |key: &str| {
+            info.and_then(|info| info.get(key))
+                .and_then(JsonValue::as_str)
+                .map(str::to_string)
+        };
+
+        let mut operations_by_method = BTreeMap::new();
+        let mut tag_counts: BTreeMap<&str, usize> = BTreeMap::new();
+        for (_, method, op) in self.paths() {
+            *operations_by_method
+                .entry(method.to_uppercase())
+                .or_insert(0) += 1;
+            for tag in op
+                .get("tags")
+                .and_then(JsonValue::as_array)
+                .into_iter()
+                .flatten()
+                .filter_map(JsonValue::as_str)
+            {
+                *tag_counts.entry(tag).or_insert(0) += 1;
+            }
+        }
+        let mut tags: Vec<TagSummary> = tag_counts
+            .into_iter()
+            .map(|(name, operations)| TagSummary {
+                name: name.to_string(),
+                operations,
+            })
+            .collect();
+        // Most used first; the map already ordered equal counts by name
+        tags.sort_by_key(|tag| std::cmp::Reverse(tag.operations));
+
+        SpecSummary {
+            title: text("title"),
+            version: text("version"),
+            description: text("description"),
+            servers: self
+                .json
+                .get("servers")
+                .and_then(JsonValue::as_array)
+                .into_iter()
+                .flatten()
+                .filter_map(|server| server.get("url")?.as_str())
+                .map(str::to_string)
+                .collect(),
+            operations: operations_by_method.values().sum(),
+            operations_by_method,
+            component_schemas: self.schema_names().len(),
+            tags,
+        }
+    }
+
+    /// Non-fatal problems in the spec: operations generated under a
+    /// synthesized operationId and local `$ref`s that resolve to nothing
+    pub fn warnings(&self) -> Vec<Warning> {
+        self.warnings_with(OperationIdStrategy::default())
+    }
+
+    /// Like [`warnings`](Self::warnings), naming synthesized operationIds as
+    /// `strategy` builds them
+    pub fn warnings_with(&self, strategy: OperationIdStrategy) -> Vec<Warning> {
+        let mut warnings: Vec<Warning> = self
+            .identified_operations(strategy)
+            .into_iter()
+            // Only the methods `parse_operations` generates code for
+            .filter(|(_, method, op, _)| {
+                matches!(*method, "get" | "post") && op.get("operationId").is_none()
+            })
+            .map(|(path, method, _, id)| {
+                Warning::new(
+                    WarningKind::SynthesizedOperationId,
+                    format!(
+                        "{} {} has no operationId, using '{}'",
+                        method.to_uppercase(),
+                        path,
+                        id
+                    ),
+                )
+            })
+            .collect();
+
+        let mut refs = BTreeSet::new();
+        collect_local_refs(&self.json, &mut refs);
+        warnings.extend(
+            refs.into_iter()
+                .filter(|r| self.json.pointer(&r[1..]).is_none())
+                .map(|r| {
+                    Warning::new(
+                        WarningKind::DanglingRef,
+                        format!("'{}' does not resolve", r),
+                    )
+                }),
+        );
+        warnings.extend(
+            self.json
+                .get("paths")
+                .and_then(JsonValue::as_object)
+                .into_iter()
+                .flat_map(|paths| paths.keys())
+                .filter(|key| !key.starts_with('/'))
+                .map(|key| {
+                    Warning::new(
+                        WarningKind::MissingLeadingSlash,
+                        format!(
+                            "Path '{}' does not start with '/', using '{}'",
+                            key,
+                            with_leading_slash(key)
+                        ),
+                    )
+                }),
+        );
+        warnings.extend(self.path_parameter_mismatches());
+        warnings.extend(self.missing_success_responses());
+        warnings.extend(self.read_write_only_mismatches());
+        warnings
+    }
+
+    /// Check the spec for problems the generated code can't compile with.
+    ///
+    /// Currently these are path templates and `in: path` parameters that don't
+    /// match up, and operations without any success (2xx) response; the error
+    /// lists every problem found.
+    pub fn validate(&self) -> crate::Result<()> {
+        let problems: Vec<String> = self
+            .path_parameter_mismatches()
+            .into_iter()
+            .chain(self.missing_success_responses())
+            .map(|warning| warning.to_string())
+            .collect();
+        if problems.is_empty() {
+            return Ok(());
+        }
+        Err(Error::openapi(problems.join("; ")))
+    }
+
+    /// Generated operations whose declared responses are all errors or `default`,
+    /// which leaves them without a response type
+    fn missing_success_responses(&self) -> Vec<Warning> {
+        self.paths()
+            // Only the methods `parse_operations` generates code for
+            .filter(|(_, method, _)| matches!(*method, "get" | "post"))
+            .filter(|(_, _, op)| {
+                op.get("responses")
+                    .and_then(JsonValue::as_object)
+                    .is_some_and(|responses| !responses.keys().any(|s| s.starts_with('2')))
+            })
+            .map(|(path, method, _)| {
+                Warning::new(
+                    WarningKind::MissingSuccessResponse,
+                    format!(
+                        "{} {} declares no success (2xx) response",
+                        method.to_uppercase(),
+                        path
+                    ),
+                )
+            })
+            .collect()
+    }
+
+    /// Generated operations sending `readOnly` fields in their request body or
+    /// returning `writeOnly` fields in a success response, which usually means
+    /// one schema is shared by both directions
+    fn read_write_only_mismatches(&self) -> Vec<Warning> {
+        let mut warnings = Vec::new();
+        for (path, method, op) in self.paths() {
+            if !matches!(method, "get" | "post") {
+                continue;
+            }
+            let operation = format!("{} {}", method.to_uppercase(), path);
+            let request_schemas = op
+                .get("requestBody")
+                .map(|body| self.follow_ref(body))
+                .into_iter()
+                .flat_map(media_type_schemas);
+            let response_schemas = op
+                .get("responses")
+                .and_then(JsonValue::as_object)
+                .into_iter()
+                .flatten()
+                .filter(|(status, _)| status.starts_with('2') || *status == "default")
+                .flat_map(|(_, response)| media_type_schemas(self.follow_ref(response)));
+            for (direction, flag, schemas) in [
+                (
+                    "request body",
+                    "readOnly",
+                    request_schemas.collect::<Vec<_>>(),
+                ),
+                ("response", "writeOnly", response_schemas.collect()),
+            ] {
+                let mut fields = BTreeSet::new();
+                for schema in schemas {
+                    self.flagged_fields(schema, flag, "", &mut BTreeSet::new(), &mut fields);
+                }
+                if !fields.is_empty() {
+                    warnings.push(Warning::new(
+                        WarningKind::ReadWriteOnlyMismatch,
+                        format!(
+                            "{} {} includes {} fields: {}",
+                            operation,
+                            direction,
+                            flag,
+                            fields.into_iter().collect::<Vec<_>>().join(", ")
+                        ),
+                    ));
+                }
+            }
+        }
+        warnings
+    }
+
+    /// Collect the dotted paths (`[]` marks array items) of the fields in
+    /// `schema` that set `flag`, following local `$ref`s once per branch
+    fn flagged_fields(
+        &self,
+        schema: &JsonValue,
+        flag: &str,
+        prefix: &str,
+        refs: &mut BTreeSet<String>,
+        fields: &mut BTreeSet<String>,
+    ) {
+        if let Some(r) = schema.get("$ref").and_then(JsonValue::as_str) {
+            if refs.insert(r.to_string()) {
+                let target = self.follow_ref(schema);
+                if !std::ptr::eq(target, schema) {
+                    self.flagged_fields(target, flag, prefix, refs, fields);
+                }
+                refs.remove(r);
+            }
+        }
+        let field = |name: &str| match prefix {
+            "" => name.to_string(),
+            prefix => format!("{}.{}", prefix, name),
+        };
+        if let Some(properties) = schema.get("properties").and_then(JsonValue::as_object) {
+            for (name, property) in properties {
+                let is_flagged =
+                    |schema: &JsonValue| schema.get(flag) == Some(&JsonValue::Bool(true));
+                if is_flagged(property) || is_flagged(self.follow_ref(property)) {
+                    fields.insert(field(name));
+                }
+                self.flagged_fields(property, flag, &field(name), refs, fields);
+            }
+        }
+        if let Some(items) = schema.get("items") {
+            self.flagged_fields(items, flag, &format!("{}[]", prefix), refs, fields);
+        }
+        for key in ["allOf", "oneOf", "anyOf"] {
+            for branch in schema
+                .get(key)
+                .and_then(JsonValue::as_array)
+                .into_iter()
+                .flatten()
+            {
+                self.flagged_fields(branch, flag, prefix, refs, fields);
+            }
+        }
+    }
+
+    /// The value a local `$ref` in `value` points to, or `value` itself
+    fn follow_ref<'a>(&'a self, value: &'a JsonValue) -> &'a JsonValue {
+        value
+            .get("$ref")
+            .and_then(JsonValue::as_str)
+            .and_then(|r| r.strip_prefix('#'))
+            .and_then(|pointer| self.json.pointer(pointer))
+            .unwrap_or(value)
+    }
+
+    /// Cross-check each operation's `{name}` path tokens against its declared
+    /// path parameters, including those shared on the path item
+    fn path_parameter_mismatches(&self) -> Vec<Warning> {
+        let mut warnings = Vec::new();
+        for (path, method, op) in self.paths() {
+            let item = &self.json["paths"][path];
+            let tokens = path_template_names(path);
+            let declared: Vec<String> = self
+                .operation_parameters(item, op)
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|p| p.in_ == "path")
+                .map(|p| p.name)
+                .collect();
+            let operation = format!("{} {}", method.to_uppercase(), path);
+            for token in tokens.iter().filter(|t| !declared.contains(t)) {
+                warnings.push(Warning::new(
+                    WarningKind::PathParameterMismatch,
+                    format!(
+                        "{} has no path parameter declared for '{{{}}}'",
+                        operation, token
+                    ),
+                ));
+            }
+            for name in declared.iter().filter(|n| !tokens.contains(n)) {
+                warnings.push(Warning::new(
+                    WarningKind::PathParameterMismatch,
+                    format!(
+                        "{} declares path parameter '{}' that is not in the path",
+                        operation, name
+                    ),
+                ));
+            }
+        }
+        warnings
+    }
+
+    /// Inline every external `$ref` (e.g. `common.yaml#/components/schemas/Pet`)
+    /// so the spec becomes a single self-contained document.
+    ///
+    /// Relative references are resolved against `base_dir`, normally the
+    /// directory of the spec file, and references inside referenced files
+    /// against those files' own directories. Internal refs of this document are
+    /// kept as they are; internal refs of referenced files are inlined with them.
+    pub async fn bundle(&self, base_dir: impl AsRef<Path>) -> crate::Result<Self> {
+        self.bundle_from(RefBase::Dir(base_dir.as_ref().to_path_buf()))
+            .await
+    }
+
+    /// Like [`bundle`](Self::bundle) for a spec fetched from `base_url`:
+    /// relative references are resolved against that URL and fetched.
+    pub async fn bundle_url(&self, base_url: &str) -> crate::Result<Self> {
+        let base_url = url::Url::parse(base_url)
+            .map_err(|e| Error::openapi(format!("Invalid spec URL '{}': {}", base_url, e)))?;
+        self.bundle_from(RefBase::Url(base_url)).await
+    }
+
+    async fn bundle_from(&self, base: RefBase) -> crate::Result<Self> {
+        let mut json = self.json.clone();
+        let json = tokio::task::spawn_blocking(move || {
+            let mut documents = RefDocuments::default();
+            inline_external_refs(&mut json, &base, None, &mut documents, &mut Vec::new())
+                .map(|_| json)
+        })
+        .await
+        .map_err(|e| Error::openapi(format!("Failed to bundle spec: {}", e)))??;
+        Ok(Self { json })
+    }
+
+    /// Standalone OpenAPI document with just one operation: its path item
+    /// reduced to that method, plus every component it references directly or
+    /// transitively and the security schemes it requires.
+    ///
+    /// Returns `None` if the spec has no such operation.
+    pub fn operation_fragment(&self, path: &str, method: &str) -> Option<JsonValue> {
+        let paths = self.json.get("paths")?;
+        // Operations report slash-less path keys with a leading slash
+        let item = paths
+            .get(path)
+            .or_else(|| paths.get(path.strip_prefix('/')?))?
+            .as_object()?;
+        let operation = self.resolve_operation_ref(item.get(method)?).clone();
+
+        let mut path_item: serde_json::Map<String, JsonValue> = item
+            .iter()
+            .filter(|(key, _)| !HTTP_METHODS.contains(&key.as_str()))
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect();
+        let security = operation
+            .get("security")
+            .or_else(|| self.json.get("security"))
+            .cloned();
+        path_item.insert(method.to_string(), operation);
+        let path_item = JsonValue::Object(path_item);
+
+        let mut fragment = json!({
+            "openapi": self.json.get("openapi").cloned().unwrap_or_else(|| json!("3.0.0")),
+            "info": self.json.get("info").cloned().unwrap_or_else(|| json!({})),
+            "paths": {path: path_item.clone()},
+        });
+        if let Some(servers) = self.json.get("servers") {
+            fragment["servers"] = servers.clone();
+        }
+
+        // Copy referenced definitions until no new refs turn up
+        let mut pending = vec![path_item];
+        let mut copied = BTreeSet::new();
+        while let Some(value) = pending.pop() {
+            let mut refs = BTreeSet::new();
+            collect_local_refs(&value, &mut refs);
+            for ref_str in refs {
+                let Some(pointer) = definition_pointer(ref_str) else {
+                    continue;
+                };
+                if !copied.insert(pointer.clone()) {
+                    continue;
+                }
+                if let Some(target) = self.json.pointer(&pointer) {
+                    insert_at_pointer(&mut fragment, &pointer, target.clone());
+                    pending.push(target.clone());
+                }
+            }
+        }
+
+        // Security requirements name their schemes rather than `$ref` them
+        let schemes = security
+            .iter()
+            .filter_map(JsonValue::as_array)
+            .flatten()
+            .filter_map(JsonValue::as_object)
+            .flat_map(|requirement| requirement.keys());
+        for name in schemes {
+            let pointer = format!("/components/securitySchemes/{}", escape_pointer(name));
+            if let Some(scheme) = self.json.pointer(&pointer) {
+                insert_at_pointer(&mut fragment, &pointer, scheme.clone());
+            }
+        }
+        if let Some(global) = self.json.get("security") {
+            fragment["security"] = global.clone();
+        }
+        Some(fragment)
+    }
+
+    /// Compare the operations in this spec against a previous version of it.
+    ///
+    /// Returns the IDs of the operations [`diff`](Self::diff) reports as
+    /// modified, or `None` when the change is structural (operations added or
+    /// removed, or anything outside `paths` such as components or servers
+    /// modified) and a full regeneration is required.
+    pub async fn changed_operations(
+        &self,
+        previous: &OpenApiContext,
+    ) -> crate::Result<Option<Vec<String>>> {
+        self.changed_operations_with(previous, OperationIdStrategy::default())
+            .await
+    }
+
+    /// Like [`changed_operations`](Self::changed_operations), with operationIds
+    /// synthesized by `strategy` as generation names them
+    pub async fn changed_operations_with(
+        &self,
+        previous: &OpenApiContext,
+        strategy: OperationIdStrategy,
+    ) -> crate::Result<Option<Vec<String>>> {
+        let without_paths = |json: &JsonValue| {
+            let mut json = json.clone();
+            if let Some(obj) = json.as_object_mut() {
+                obj.remove("paths");
+            }
+            json
+        };
+        if without_paths(&self.json) != without_paths(&previous.json) {
+            return Ok(None);
+        }
+
+        let diff = previous.diff_with(self, strategy);
+        if !diff.added_operations.is_empty() || !diff.removed_operations.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(diff.modified_operations))
+    }
+
+    /// The operation a method's value stands for: the target of a local `$ref`
+    /// (e.g. `{"$ref": "#/components/x-operations/listPets"}`), else the value itself
+    pub fn resolve_operation_ref<'a>(&'a self, operation: &'a JsonValue) -> &'a JsonValue {
+        operation
+            .get("$ref")
+            .and_then(JsonValue::as_str)
+            .and_then(|ref_str| ref_str.strip_prefix('#'))
+            .and_then(|pointer| self.json.pointer(pointer))
+            .unwrap_or(operation)
+    }
+
+    /// Replace Example Objects that are local `$ref`s (e.g.
+    /// `#/components/examples/Pet`) with the examples they point to
+    fn resolve_example_refs<'a>(&self, examples: impl Iterator<Item = &'a mut JsonValue>) {
+        for example in examples {
+            if let Some(target) = example
+                .get("$ref")
+                .and_then(JsonValue::as_str)
+                .and_then(|ref_str| ref_str.strip_prefix('#'))
+                .and_then(|pointer| self.json.pointer(pointer))
+            {
+                *example = target.clone();
+            }
+        }
+    }
+
     pub fn extract_parameters(&self, path_item: &JsonValue) -> Option<Vec<OpenApiParameter>> {
         path_item
             .get("parameters")
@@ -250,23 +1114,63 @@ impl OpenApiContext {
             .map(|arr| {
                 arr.iter()
                     .filter_map(|param| {
-                        if let Some(ref_str) = param.get("$ref").and_then(JsonValue::as_str) {
-                            self.json
-                                .pointer(&ref_str[1..])
-                                .and_then(|p| serde_json::from_value(p.clone()).ok())
-                        } else {
-                            serde_json::from_value(param.clone()).ok()
+                        let mut param: OpenApiParameter =
+                            if let Some(ref_str) = param.get("$ref").and_then(JsonValue::as_str) {
+                                self.json
+                                    .pointer(&ref_str[1..])
+                                    .and_then(|p| serde_json::from_value(p.clone()).ok())
+                            } else {
+                                serde_json::from_value(param.clone()).ok()
+                            }?;
+                        if let Some(examples) = &mut param.examples {
+                            self.resolve_example_refs(examples.values_mut());
                         }
+                        Some(param)
                     })
                     .collect::<Vec<OpenApiParameter>>()
             })
     }
 
+    /// Effective parameters of an operation: the path item's parameters with
+    /// `$ref`s resolved, overridden by the operation's own definitions of the
+    /// same `(name, in)` and followed by any parameters only the operation declares
+    pub fn operation_parameters(
+        &self,
+        path_item: &JsonValue,
+        operation: &JsonValue,
+    ) -> Option<Vec<OpenApiParameter>> {
+        let shared = self.extract_parameters(path_item);
+        let own = self.extract_parameters(operation);
+        let (parameters, own) = match (shared, own) {
+            (None, None) => return None,
+            (shared, own) => (shared.unwrap_or_default(), own.unwrap_or_default()),
+        };
+        let mut merged = Vec::with_capacity(parameters.len() + own.len());
+        for param in parameters.into_iter().chain(own) {
+            merge_parameter(&mut merged, param);
+        }
+        Some(merged)
+    }
+
+    /// Effective parameters of every operation, under any HTTP method, keyed by
+    /// (possibly synthesized) operationId; see [`operation_parameters`](Self::operation_parameters)
+    pub fn all_parameters(&self) -> Vec<(String, Vec<OpenApiParameter>)> {
+        self.identified_operations(OperationIdStrategy::default())
+            .into_iter()
+            .map(|(path, _, op, id)| {
+                let params = self
+                    .operation_parameters(&self.json["paths"][path], op)
+                    .unwrap_or_default();
+                (id, params)
+            })
+            .collect()
+    }
+
     /// Extract responses from JSON object
     pub fn extract_responses(
         &self,
         get_item: &serde_json::Map<String, JsonValue>,
-    ) -> std::collections::HashMap<String, OpenApiResponse> {
+    ) -> BTreeMap<String, OpenApiResponse> {
         get_item
             .get("responses")
             .and_then(JsonValue::as_object)
@@ -286,7 +1190,7 @@ impl OpenApiContext {
     pub fn extract_vendor_extensions(
         &self,
         get_item: &serde_json::Map<String, JsonValue>,
-    ) -> std::collections::HashMap<String, JsonValue> {
+    ) -> BTreeMap<String, JsonValue> {
         get_item
             .iter()
             .filter(|(k, _)| k.starts_with("x-"))
@@ -529,12 +1433,23 @@ impl OpenApiContext {
 
     /// Extract properties from a schema, resolving $ref if necessary
     ///
+    /// An `allOf` schema yields the union of its members' properties (and its
+    /// own), later members winning when a name repeats.
+    ///
     /// Returns a tuple of (properties_json, schema_name) where:
     /// - properties_json: The schema properties as a JSON object
     /// - schema_name: The name of the schema if it was a $ref
     pub fn extract_schema_properties(
         &self,
         schema: &JsonValue,
+    ) -> crate::Result<(JsonValue, Option<String>)> {
+        self.schema_properties(schema, MAX_ALL_OF_DEPTH)
+    }
+
+    fn schema_properties(
+        &self,
+        schema: &JsonValue,
+        depth: usize,
     ) -> crate::Result<(JsonValue, Option<String>)> {
         // Handle null or non-object schemas
         let schema_obj = match schema.as_object() {
@@ -542,6 +1457,24 @@ impl OpenApiContext {
             None => return Ok((JsonValue::Null, None)),
         };
 
+        // Composition: merge the members' properties; the result is no longer
+        // any one named schema
+        if let Some(members) = schema_obj.get("allOf").and_then(JsonValue::as_array) {
+            if depth == 0 {
+                return Err(Error::openapi("allOf schemas are nested too deeply"));
+            }
+            let mut merged = serde_json::Map::new();
+            for member in members {
+                if let (JsonValue::Object(props), _) = self.schema_properties(member, depth - 1)? {
+                    merged.extend(props);
+                }
+            }
+            if let Some(JsonValue::Object(own)) = schema_obj.get("properties") {
+                merged.extend(own.clone());
+            }
+            return Ok((JsonValue::Object(merged), None));
+        }
+
         // Direct inline object schema with properties
         if schema_obj.get("properties").is_some()
             || schema_obj.get("additionalProperties").is_some()
@@ -578,31 +1511,47 @@ impl OpenApiContext {
         };
 
         // Resolve the reference
-        let key = "#/components/schemas/";
-        if !ref_str.starts_with(key) {
-            return Err(Error::openapi(format!(
-                "Unexpected schema ref '{}'",
-                ref_str
-            )));
-        }
-
-        let schema_name = &ref_str[key.len()..];
+        let (section, schema_name) = Self::split_schema_ref(ref_str)
+            .ok_or_else(|| Error::openapi(format!("Unexpected schema ref '{}'", ref_str)))?;
         let schemas = self
             .json
-            .get("components")
-            .and_then(JsonValue::as_object)
-            .and_then(|m| m.get("schemas"))
+            .pointer(section)
             .and_then(JsonValue::as_object)
-            .ok_or_else(|| Error::openapi("No components.schemas section"))?;
+            .ok_or_else(|| {
+                Error::openapi(format!(
+                    "No {} section",
+                    section.trim_start_matches('/').replace('/', ".")
+                ))
+            })?;
 
         let def = schemas
             .get(schema_name)
             .ok_or_else(|| Error::openapi(format!("Schema '{}' not found", schema_name)))?;
 
-        let props = def.get("properties").cloned().unwrap_or(JsonValue::Null);
+        let props = if def.get("allOf").is_some() {
+            self.schema_properties(def, depth)?.0
+        } else {
+            def.get("properties").cloned().unwrap_or(JsonValue::Null)
+        };
         Ok((props, Some(schema_name.to_string())))
     }
 
+    /// Split a local schema `$ref` (`#/components/schemas/Name` or `#/$defs/Name`)
+    /// into the JSON pointer of its definitions section and the schema name
+    pub fn split_schema_ref(ref_str: &str) -> Option<(&'static str, &str)> {
+        SCHEMA_REF_ROOTS.iter().find_map(|(prefix, section)| {
+            ref_str
+                .strip_prefix(prefix)
+                .map(|schema_name| (*section, schema_name))
+        })
+    }
+
+    /// Look up the schema definition a local schema `$ref` points to
+    pub fn resolve_schema_ref(&self, ref_str: &str) -> Option<&JsonValue> {
+        let (section, schema_name) = Self::split_schema_ref(ref_str)?;
+        self.json.pointer(section)?.get(schema_name)
+    }
+
     /// Extract request body properties from an operation
     ///
     /// Returns a tuple of (properties_json, schema_name) where:
@@ -617,6 +1566,11 @@ impl OpenApiContext {
             return Ok((serde_json::json!({}), None));
         };
 
+        // Raw byte streams have no properties to extract
+        if operation.request_body_is_binary() {
+            return Ok((serde_json::json!({}), None));
+        }
+
         // Extract content from request body
         let content = request_body
             .get("content")
@@ -639,6 +1593,365 @@ impl OpenApiContext {
     }
 }
 
+/// 1-based number of the line a YAML error points at if its indentation contains a
+/// tab, otherwise of the first tab-indented line
+fn tab_indented_line(content: &str, err: &serde_yaml::Error) -> Option<usize> {
+    let is_tab_indented = |line: &str| {
+        line.chars()
+            .take_while(|c| c.is_whitespace())
+            .any(|c| c == '\t')
+    };
+    let lines: Vec<&str> = content.lines().collect();
+    err.location()
+        .map(|loc| loc.line())
+        .filter(|&line| {
+            lines
+                .get(line.wrapping_sub(1))
+                .is_some_and(|l| is_tab_indented(l))
+        })
+        .or_else(|| lines.iter().position(|l| is_tab_indented(l)).map(|i| i + 1))
+}
+
+/// Add `param` to `parameters`, replacing an earlier definition of the same
+/// parameter. Header names are compared case-insensitively, as in HTTP.
+pub(crate) fn merge_parameter(parameters: &mut Vec<OpenApiParameter>, param: OpenApiParameter) {
+    match parameters.iter_mut().find(|p| p.is_same_as(&param)) {
+        Some(existing) => *existing = param,
+        None => parameters.push(param),
+    }
+}
+
+/// What relative external `$ref`s are resolved against while bundling
+#[derive(Debug, Clone)]
+enum RefBase {
+    /// A directory of the file system (or of the spec archive)
+    Dir(PathBuf),
+    /// The URL of the document containing the ref
+    Url(url::Url),
+}
+
+/// A document referenced by an external `$ref`
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum RefDocument {
+    File(PathBuf),
+    Url(url::Url),
+}
+
+impl RefBase {
+    /// The document `file` (the part of a `$ref` before `#`) refers to
+    fn resolve(&self, file: &str, ref_str: &str) -> crate::Result<RefDocument> {
+        match self {
+            RefBase::Dir(_) if file.starts_with("http://") || file.starts_with("https://") => Err(
+                Error::openapi(format!("Remote $ref '{}' can't be bundled", ref_str)),
+            ),
+            RefBase::Dir(dir) => Ok(RefDocument::File(dir.join(file))),
+            RefBase::Url(url) => url.join(file).map(RefDocument::Url).map_err(|e| {
+                Error::openapi(format!("$ref '{}' is not a valid URL: {}", ref_str, e))
+            }),
+        }
+    }
+}
+
+impl RefDocument {
+    /// What refs inside this document are resolved against
+    fn base(&self) -> RefBase {
+        match self {
+            RefDocument::File(path) => {
+                RefBase::Dir(path.parent().map(Path::to_path_buf).unwrap_or_default())
+            }
+            RefDocument::Url(url) => RefBase::Url(url.clone()),
+        }
+    }
+}
+
+impl std::fmt::Display for RefDocument {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RefDocument::File(path) => write!(f, "{}", path.display()),
+            RefDocument::Url(url) => f.write_str(url.as_str()),
+        }
+    }
+}
+
+/// Replace the external `$ref`s in `value` with the content they point at.
+///
+/// `document` is the referenced document `value` was taken from, if any; its
+/// internal refs would dangle in the bundled spec, so they are inlined too.
+/// `visiting` holds the document and fragment of every ref being expanded
+/// around `value`, so a ref back to one of them is reported as circular.
+fn inline_external_refs(
+    value: &mut JsonValue,
+    base: &RefBase,
+    document: Option<(&RefDocument, &JsonValue)>,
+    documents: &mut RefDocuments,
+    visiting: &mut Vec<(RefDocument, String)>,
+) -> crate::Result<()> {
+    match value {
+        JsonValue::Object(map) => {
+            if let Some(ref_str) = map.get("$ref").and_then(JsonValue::as_str) {
+                let (file, fragment) = ref_str.split_once('#').unwrap_or((ref_str, ""));
+                let (location, target) = match document {
+                    None if file.is_empty() => return Ok(()),
+                    Some((location, target)) if file.is_empty() => {
+                        (location.clone(), target.clone())
+                    }
+                    _ => {
+                        let location = documents.normalize(base.resolve(file, ref_str)?);
+                        let target = documents.load(&location)?;
+                        (location, target)
+                    }
+                };
+                let key = (location, fragment.to_string());
+                if visiting.contains(&key) {
+                    return Err(Error::openapi(format!(
+                        "$ref '{}' to {} is circular and can't be inlined",
+                        ref_str, key.0
+                    )));
+                }
+                let location = key.0.clone();
+
+                let mut resolved = if fragment.is_empty() {
+                    target.clone()
+                } else {
+                    target.pointer(fragment).cloned().ok_or_else(|| {
+                        Error::openapi(format!("$ref '{}' does not resolve", ref_str))
+                    })?
+                };
+                visiting.push(key);
+                inline_external_refs(
+                    &mut resolved,
+                    &location.base(),
+                    Some((&location, &target)),
+                    documents,
+                    visiting,
+                )?;
+                visiting.pop();
+                *value = resolved;
+                return Ok(());
+            }
+            for v in map.values_mut() {
+                inline_external_refs(v, base, document, documents, visiting)?;
+            }
+        }
+        JsonValue::Array(items) => {
+            for v in items {
+                inline_external_refs(v, base, document, documents, visiting)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Documents referenced by external `$ref`s, each parsed once
+#[derive(Default)]
+struct RefDocuments {
+    parsed: BTreeMap<RefDocument, JsonValue>,
+    /// Entries of the zip archive the spec came from; when set, refs resolve
+    /// against these instead of the file system
+    archive: Option<BTreeMap<PathBuf, String>>,
+}
+
+impl RefDocuments {
+    /// `document` in the form it's cached and compared under
+    fn normalize(&self, document: RefDocument) -> RefDocument {
+        match document {
+            RefDocument::File(path) if self.archive.is_some() => {
+                RefDocument::File(normalize_path(&path))
+            }
+            other => other,
+        }
+    }
+
+    /// Read and parse `document`
+    fn load(&mut self, document: &RefDocument) -> crate::Result<JsonValue> {
+        if let Some(parsed) = self.parsed.get(document) {
+            return Ok(parsed.clone());
+        }
+        let path = match document {
+            RefDocument::File(path) => path,
+            RefDocument::Url(url) => {
+                // Bundling runs on a blocking thread, which may wait on the runtime
+                let parsed = tokio::runtime::Handle::current()
+                    .block_on(OpenApiContext::from_url(url.as_str()))
+                    .map_err(|e| {
+                        Error::openapi(format!("Failed to load referenced {}: {}", url, e))
+                    })?
+                    .json;
+                self.parsed.insert(document.clone(), parsed.clone());
+                return Ok(parsed);
+            }
+        };
+        let content = match &self.archive {
+            Some(entries) => entries.get(path).cloned().ok_or_else(|| {
+                Error::openapi(format!(
+                    "Referenced file {} is not in the archive",
+                    path.display()
+                ))
+            })?,
+            None => std::fs::read_to_string(path).map_err(|e| {
+                Error::openapi(format!(
+                    "Failed to read referenced file {}: {}",
+                    path.display(),
+                    e
+                ))
+            })?,
+        };
+        let parsed = OpenApiContext::parse_content(&content)
+            .map_err(|e| {
+                Error::openapi(format!(
+                    "Failed to parse referenced file {}: {}",
+                    path.display(),
+                    e
+                ))
+            })?
+            .json;
+        self.parsed.insert(document.clone(), parsed.clone());
+        Ok(parsed)
+    }
+}
+
+/// Contents of every file in a zip archive, keyed by normalized entry path
+fn read_archive(path: &Path) -> crate::Result<BTreeMap<PathBuf, String>> {
+    let archive_error = |e: zip::result::ZipError| {
+        Error::openapi(format!("Failed to read {}: {}", path.display(), e))
+    };
+    let mut archive = zip::ZipArchive::new(std::fs::File::open(path)?).map_err(archive_error)?;
+    let mut entries = BTreeMap::new();
+    for index in 0..archive.len() {
+        let mut file = archive.by_index(index).map_err(archive_error)?;
+        if !file.is_file() {
+            continue;
+        }
+        let mut content = String::new();
+        // Entries that aren't text can't be specs or fragments; skip them
+        if std::io::Read::read_to_string(&mut file, &mut content).is_ok() {
+            entries.insert(normalize_path(Path::new(file.name())), content);
+        }
+    }
+    Ok(entries)
+}
+
+/// Resolve `.` and `..` components without touching the file system
+fn normalize_path(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+/// Names of the `{name}` templates in a path, in order
+pub(crate) fn path_template_names(path: &str) -> Vec<String> {
+    path.split('{')
+        .skip(1)
+        .filter_map(|rest| rest.split_once('}'))
+        .map(|(name, _)| name.to_string())
+        .collect()
+}
+
+/// Every `$ref` pointing into the same document (`#/...`)
+fn collect_local_refs<'a>(value: &'a JsonValue, refs: &mut BTreeSet<&'a str>) {
+    match value {
+        JsonValue::Object(map) => {
+            if let Some(r) = map.get("$ref").and_then(JsonValue::as_str) {
+                if r.starts_with('#') {
+                    refs.insert(r);
+                }
+            }
+            map.values().for_each(|v| collect_local_refs(v, refs));
+        }
+        JsonValue::Array(items) => items.iter().for_each(|v| collect_local_refs(v, refs)),
+        _ => {}
+    }
+}
+
+/// `path` with a leading `/`, e.g. `/pets` for a `pets:` paths key
+fn with_leading_slash(path: &str) -> String {
+    if path.starts_with('/') {
+        path.to_string()
+    } else {
+        format!("/{}", path)
+    }
+}
+
+/// Schemas of every media type in a request body's or response's `content`
+fn media_type_schemas(body: &JsonValue) -> impl Iterator<Item = &JsonValue> {
+    body.get("content")
+        .and_then(JsonValue::as_object)
+        .into_iter()
+        .flatten()
+        .filter_map(|(_, media_type)| media_type.get("schema"))
+}
+
+/// Operation ID used when `operation` doesn't declare one, e.g. `get_pets_id`
+fn synthesized_operation_id(
+    strategy: OperationIdStrategy,
+    method: &str,
+    path: &str,
+    operation: &JsonValue,
+) -> String {
+    let summary = operation.get("summary").and_then(JsonValue::as_str);
+    strategy.synthesize(method, path, summary)
+}
+
+/// Overview of a spec, see [`OpenApiContext::summary`]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct SpecSummary {
+    pub title: Option<String>,
+    pub version: Option<String>,
+    pub description: Option<String>,
+    /// URLs of the root `servers`
+    pub servers: Vec<String>,
+    /// Number of operations across all HTTP methods
+    pub operations: usize,
+    /// Number of operations per uppercase HTTP method
+    pub operations_by_method: BTreeMap<String, usize>,
+    /// Number of named schemas under `components/schemas` (or `definitions`)
+    pub component_schemas: usize,
+    /// Tags by number of operations carrying them, most used first
+    pub tags: Vec<TagSummary>,
+}
+
+/// How many operations carry a tag
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TagSummary {
+    pub name: String,
+    pub operations: usize,
+}
+
+/// Differences between two versions of a spec, see [`OpenApiContext::diff`]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct SpecDiff {
+    /// IDs of operations only in the newer spec
+    pub added_operations: Vec<String>,
+    /// IDs of operations only in the older spec
+    pub removed_operations: Vec<String>,
+    /// IDs of operations in both specs whose definition changed
+    pub modified_operations: Vec<String>,
+    /// Names of schemas only in the newer spec
+    pub added_schemas: Vec<String>,
+    /// Names of schemas only in the older spec
+    pub removed_schemas: Vec<String>,
+}
+
+impl SpecDiff {
+    /// Whether the specs have the same operations and schemas
+    pub fn is_empty(&self) -> bool {
+        self.added_operations.is_empty()
+            && self.removed_operations.is_empty()
+            && self.modified_operations.is_empty()
+            && self.added_schemas.is_empty()
+            && self.removed_schemas.is_empty()
+    }
+}
+
 /// Parsed OpenAPI operation for template rendering
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct OpenApiOperation {
@@ -665,7 +1978,7 @@ pub struct OpenApiOperation {
     #[serde(rename = "requestBody")]
     pub request_body: Option<serde_json::Value>,
     /// The list of possible responses as they are returned from executing this operation.
-    pub responses: std::collections::HashMap<String, OpenApiResponse>,
+    pub responses: BTreeMap<String, OpenApiResponse>,
     /// A map of possible out-of band callbacks related to the parent operation.
     pub callbacks: Option<serde_json::Value>,
     /// Declares this operation to be deprecated. Consumers SHOULD refrain from usage of the declared operation.
@@ -676,7 +1989,100 @@ pub struct OpenApiOperation {
     pub servers: Option<Vec<serde_json::Value>>,
     /// Specification extensions (fields starting with `x-`).
     #[serde(flatten)]
-    pub vendor_extensions: std::collections::HashMap<String, serde_json::Value>,
+    pub vendor_extensions: BTreeMap<String, serde_json::Value>,
+}
+
+impl OpenApiOperation {
+    /// Example values for parameters that declare one, as `{name, in, value}` objects.
+    ///
+    /// Looks at `example`, then the first of `examples`, then the schema's
+    /// `example`, `default` and first `enum` value.
+    pub fn example_parameters(&self) -> Vec<JsonValue> {
+        self.parameters
+            .iter()
+            .flatten()
+            .filter_map(|p| {
+                let value = p
+                    .example
+                    .clone()
+                    .or_else(|| first_example(p.examples.as_ref()?.values()))
+                    .or_else(|| schema_example(p.schema.as_ref()?))?;
+                Some(serde_json::json!({"name": p.name, "in": p.in_, "value": value}))
+            })
+            .collect()
+    }
+
+    /// Example JSON request body, from the media type's `example`/`examples`
+    /// or its schema's `example`
+    pub fn example_request_body(&self) -> Option<JsonValue> {
+        let content = self.request_body.as_ref()?.get("content")?.as_object()?;
+        let media = content
+            .get("application/json")
+            .or_else(|| content.values().next())?;
+        media
+            .get("example")
+            .cloned()
+            .or_else(|| first_example(media.get("examples")?.as_object()?.values()))
+            .or_else(|| schema_example(media.get("schema")?))
+    }
+
+    /// Name of the component the request body refers to, either as a whole
+    /// (`#/components/requestBodies/Pet`) or through its JSON schema
+    /// (`#/components/schemas/Pet`)
+    pub fn request_body_component(&self) -> Option<&str> {
+        let body = self.request_body.as_ref()?;
+        let content = body.get("content").and_then(JsonValue::as_object);
+        body.get("$ref")
+            .or_else(|| {
+                content?
+                    .get("application/json")
+                    .or_else(|| content?.values().next())?
+                    .get("schema")?
+                    .get("$ref")
+            })
+            .and_then(JsonValue::as_str)
+            .and_then(|r| r.rsplit('/').next())
+    }
+
+    /// Every media type the request body accepts, e.g. `application/json` and
+    /// `application/xml`; properties are still extracted from the JSON one
+    pub fn request_media_types(&self) -> Vec<String> {
+        self.request_body
+            .as_ref()
+            .and_then(|body| body.get("content")?.as_object())
+            .map(|content| content.keys().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Whether the request body is a raw byte stream (`type: string, format: binary`),
+    /// e.g. an `application/octet-stream` file upload
+    pub fn request_body_is_binary(&self) -> bool {
+        self.request_body
+            .as_ref()
+            .and_then(|body| body.get("content")?.as_object())
+            .is_some_and(|content| {
+                content.values().any(|media| {
+                    media.get("schema").is_some_and(|schema| {
+                        schema.get("type").and_then(JsonValue::as_str) == Some("string")
+                            && schema.get("format").and_then(JsonValue::as_str) == Some("binary")
+                    })
+                })
+            })
+    }
+}
+
+/// Value of the first Example Object in an `examples` map
+fn first_example<'a>(mut examples: impl Iterator<Item = &'a JsonValue>) -> Option<JsonValue> {
+    examples.next()?.get("value").cloned()
+}
+
+/// Example value declared directly on a schema
+fn schema_example(schema: &JsonValue) -> Option<JsonValue> {
+    schema
+        .get("example")
+        .or_else(|| schema.get("default"))
+        .or_else(|| schema.get("enum").and_then(|e| e.get(0)))
+        .cloned()
 }
 
 /// Info about a single OpenAPI parameter
@@ -733,12 +2139,29 @@ pub struct OpenApiParameter {
     /// Example of the parameter's potential value. The example SHOULD match the specified schema and encoding properties if present.
     pub example: Option<serde_json::Value>,
     /// Examples of the parameter's potential value. Each example SHOULD contain a value in the correct format as specified in the parameter encoding.
-    pub examples: Option<std::collections::HashMap<String, serde_json::Value>>,
+    pub examples: Option<BTreeMap<String, serde_json::Value>>,
     /// A map containing the representations for the parameter. The key is the media type and the value describes it.
-    pub content: Option<std::collections::HashMap<String, serde_json::Value>>,
+    pub content: Option<BTreeMap<String, serde_json::Value>>,
     /// Specification extensions (fields starting with `x-`).
     #[serde(flatten)]
-    pub vendor_extensions: std::collections::HashMap<String, serde_json::Value>,
+    pub vendor_extensions: BTreeMap<String, serde_json::Value>,
+}
+
+impl OpenApiParameter {
+    /// Name used to identify the parameter: lowercased for headers, which
+    /// HTTP treats case-insensitively, and as written otherwise
+    pub fn canonical_name(&self) -> String {
+        if self.in_ == "header" {
+            self.name.to_ascii_lowercase()
+        } else {
+            self.name.clone()
+        }
+    }
+
+    /// Whether both define the same parameter (same location and canonical name)
+    pub fn is_same_as(&self, other: &OpenApiParameter) -> bool {
+        self.in_ == other.in_ && self.canonical_name() == other.canonical_name()
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -746,14 +2169,14 @@ pub struct OpenApiResponse {
     /// A short description of the response. CommonMark syntax MAY be used for rich text representation.
     pub description: Option<String>,
     /// Maps a header name to its definition. The key is the name of the header, and the value describes it.
-    pub headers: Option<std::collections::HashMap<String, serde_json::Value>>,
+    pub headers: Option<BTreeMap<String, serde_json::Value>>,
     /// A map containing descriptions of potential response payloads. The key is a media type, and the value describes it.
-    pub content: Option<std::collections::HashMap<String, serde_json::Value>>,
+    pub content: Option<BTreeMap<String, serde_json::Value>>,
     /// A map of operations links that can be followed from the response. The key is the link name, the value describes the link.
-    pub links: Option<std::collections::HashMap<String, serde_json::Value>>,
+    pub links: Option<BTreeMap<String, serde_json::Value>>,
     /// Specification extensions (fields starting with `x-`).
     #[serde(flatten)]
-    pub vendor_extensions: std::collections::HashMap<String, serde_json::Value>,
+    pub vendor_extensions: BTreeMap<String, serde_json::Value>,
 }
 
 #[cfg(test)]
@@ -960,4 +2383,783 @@ mod tests {
             .collect();
         assert_eq!(names, vec!["p".to_string(), "q".to_string()]);
     }
+
+    #[tokio::test]
+    async fn test_changed_operations() -> crate::Result<()> {
+        let base = json!({"paths": {
+            "/a": {"get": {"operationId": "getA", "summary": "A"}},
+            "/b": {"get": {"operationId": "getB", "summary": "B"}}
+        }});
+        let previous = OpenApiContext { json: base.clone() };
+
+        let mut edited = base.clone();
+        edited["paths"]["/a"]["get"]["summary"] = json!("A, reworded");
+        let spec = OpenApiContext { json: edited };
+        assert_eq!(
+            spec.changed_operations(&previous).await?,
+            Some(vec!["getA".to_string()])
+        );
+
+        // Path-level fields belong to every operation of the path
+        let mut shared = base.clone();
+        shared["paths"]["/b"]["parameters"] =
+            json!([{"name": "q", "in": "query", "schema": {"type": "string"}}]);
+        let spec = OpenApiContext { json: shared };
+        assert_eq!(
+            spec.changed_operations(&previous).await?,
+            Some(vec!["getB".to_string()])
+        );
+
+        let mut added = base.clone();
+        added["paths"]["/c"] = json!({"get": {"operationId": "getC"}});
+        let spec = OpenApiContext { json: added };
+        assert_eq!(spec.changed_operations(&previous).await?, None);
+
+        let mut components = base;
+        components["components"] = json!({"schemas": {"X": {}}});
+        let spec = OpenApiContext { json: components };
+        assert_eq!(spec.changed_operations(&previous).await?, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_tab_indented_yaml_error() {
+        let yaml = "openapi: 3.0.0\ninfo:\n\ttitle: Tabs\n\tversion: 1.0.0\npaths: {}\n";
+        let err = OpenApiContext::parse_content(yaml).unwrap_err();
+        assert_eq!(
+            err,
+            "YAML spec uses tabs for indentation at line 3; YAML requires spaces"
+        );
+
+        let err = OpenApiContext::parse_content("openapi: [3.0.0\n").unwrap_err();
+        assert_eq!(err, "content is neither valid JSON nor YAML");
+    }
+
+    #[tokio::test]
+    async fn test_operation_parameter_overrides_shared_ref() -> crate::Result<()> {
+        let spec = OpenApiContext {
+            json: json!({
+                "paths": {"/pets": {
+                    "parameters": [
+                        {"$ref": "#/components/parameters/limitParam"},
+                        {"name": "offset", "in": "query", "schema": {"type": "integer"}}
+                    ],
+                    "get": {
+                        "operationId": "listPets",
+                        "parameters": [
+                            {"name": "limit", "in": "query", "required": true,
+                             "schema": {"type": "integer"}},
+                            {"name": "limit", "in": "header", "schema": {"type": "integer"}}
+                        ]
+                    }
+                }},
+                "components": {"parameters": {"limitParam": {
+                    "name": "limit", "in": "query", "required": false,
+                    "description": "Max items", "schema": {"type": "integer"}
+                }}}
+            }),
+        };
+
+        let operations = spec.parse_operations().await?;
+        let params = operations[0].parameters.as_ref().unwrap();
+        let locations: Vec<(&str, &str)> = params
+            .iter()
+            .map(|p| (p.name.as_str(), p.in_.as_str()))
+            .collect();
+
+        assert_eq!(
+            locations,
+            vec![("limit", "query"), ("offset", "query"), ("limit", "header")]
+        );
+        assert_eq!(params[0].required, Some(true));
+        assert_eq!(params[0].description, None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_diff_reports_added_operation() {
+        let base = json!({
+            "paths": {
+                "/a": {"get": {"operationId": "getA", "summary": "A"}},
+                "/b": {"delete": {"summary": "B"}}
+            },
+            "components": {"schemas": {"A": {"type": "object"}}}
+        });
+        let previous = OpenApiContext { json: base.clone() };
+        assert!(previous.diff(&previous).is_empty());
+
+        let mut next = base;
+        next["paths"]["/c"] = json!({"post": {"operationId": "createC"}});
+        next["paths"]["/b"]["delete"]["summary"] = json!("B, reworded");
+        next["components"]["schemas"]["C"] = json!({"type": "object"});
+        let diff = previous.diff(&OpenApiContext { json: next });
+
+        assert_eq!(
+            diff,
+            SpecDiff {
+                added_operations: vec!["createC".to_string()],
+                removed_operations: vec![],
+                modified_operations: vec!["delete_b".to_string()],
+                added_schemas: vec!["C".to_string()],
+                removed_schemas: vec![],
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_extract_schema_properties_resolves_defs() -> crate::Result<()> {
+        let manifest = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        let base = manifest.parent().unwrap().parent().unwrap();
+        let spec =
+            OpenApiContext::from_file(base.join("tests/fixtures/openapi/defs.openapi.v31.json"))
+                .await?;
+
+        let schema = json!({"type": "array", "items": {"$ref": "#/$defs/Widget"}});
+        let (props, name) = spec.extract_schema_properties(&schema)?;
+        assert_eq!(name.as_deref(), Some("Widget"));
+        assert_eq!(props["id"], json!({"type": "integer"}));
+        assert_eq!(
+            spec.resolve_schema_ref("#/$defs/Owner"),
+            Some(&json!({"type": "object", "properties": {"name": {"type": "string"}}}))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_all_of_request_body_merges_properties() -> crate::Result<()> {
+        let spec = OpenApiContext {
+            json: json!({
+                "components": {"schemas": {
+                    "Base": {"type": "object", "properties": {
+                        "id": {"type": "integer"},
+                        "name": {"type": "string"}
+                    }},
+                    "Tagged": {"allOf": [
+                        {"$ref": "#/components/schemas/Base"},
+                        {"properties": {"tags": {"type": "array", "items": {"type": "string"}}}}
+                    ]}
+                }}
+            }),
+        };
+        let operation: OpenApiOperation = serde_json::from_value(json!({
+            "operationId": "createPet",
+            "method": "post",
+            "path": "/pets",
+            "requestBody": {"content": {"application/json": {"schema": {"allOf": [
+                {"$ref": "#/components/schemas/Tagged"},
+                {"type": "object", "properties": {
+                    "name": {"type": "string", "maxLength": 20},
+                    "weight": {"type": "number"}
+                }}
+            ]}}}},
+            "responses": {}
+        }))?;
+
+        let (props, name) = spec.extract_request_body_properties(&operation)?;
+        assert_eq!(name, None);
+        assert_eq!(
+            props,
+            json!({
+                "id": {"type": "integer"},
+                "name": {"type": "string", "maxLength": 20},
+                "tags": {"type": "array", "items": {"type": "string"}},
+                "weight": {"type": "number"}
+            })
+        );
+
+        // A self-referencing composition is an error, not a stack overflow
+        let looping = OpenApiContext {
+            json: json!({"components": {"schemas": {
+                "Loop": {"allOf": [{"$ref": "#/components/schemas/Loop"}]}
+            }}}),
+        };
+        assert!(looping
+            .extract_schema_properties(&json!({"$ref": "#/components/schemas/Loop"}))
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_operation_fragment_keeps_request_body_and_statuses() {
+        let spec = OpenApiContext {
+            json: json!({
+                "openapi": "3.0.3",
+                "paths": {"/pets": {"post": {
+                    "operationId": "createPet",
+                    "parameters": [{"name": "dryRun", "in": "query", "schema": {"type": "boolean"}}],
+                    "requestBody": {"required": true, "content": {
+                        "application/json": {"schema": {"$ref": "#/components/schemas/NewPet"}},
+                        "application/x-www-form-urlencoded": {"schema": {"$ref": "#/components/schemas/NewPet"}}
+                    }},
+                    "responses": {
+                        "201": {"description": "Created", "content": {
+                            "application/json": {"schema": {"$ref": "#/components/schemas/Pet"}}
+                        }},
+                        "400": {"description": "Invalid pet"}
+                    }
+                }}},
+                "components": {"schemas": {
+                    "NewPet": {"type": "object"},
+                    "Pet": {"type": "object"}
+                }}
+            }),
+        };
+
+        let fragment = spec.operation_fragment("/pets", "post").unwrap();
+        let create_pet = &fragment["paths"]["/pets"]["post"];
+        let media_types: Vec<&String> = create_pet["requestBody"]["content"]
+            .as_object()
+            .unwrap()
+            .keys()
+            .collect();
+        assert_eq!(
+            media_types,
+            ["application/json", "application/x-www-form-urlencoded"]
+        );
+        assert!(create_pet["responses"]["201"]["content"]["application/json"].is_object());
+        assert_eq!(create_pet["responses"]["400"]["description"], "Invalid pet");
+        assert_eq!(create_pet["parameters"][0]["name"], "dryRun");
+        assert!(fragment["components"]["schemas"]["NewPet"].is_object());
+        assert!(fragment["components"]["schemas"]["Pet"].is_object());
+    }
+
+    #[tokio::test]
+    async fn test_paths_without_leading_slash_are_normalized() -> crate::Result<()> {
+        let spec = OpenApiContext {
+            json: json!({"paths": {
+                "pets/{petId}": {"get": {
+                    "operationId": "getPet",
+                    "parameters": [{"name": "petId", "in": "path", "required": true}],
+                    "responses": {"200": {"description": "The pet"}}
+                }},
+                "/owners": {"get": {"operationId": "listOwners", "responses": {"200": {"description": "Owners"}}}}
+            }}),
+        };
+        let ops = spec.parse_operations().await?;
+        let path = |id: &str| ops.iter().find(|op| op.id == id).unwrap().path.clone();
+        assert_eq!(path("getPet"), "/pets/{petId}");
+        assert_eq!(path("listOwners"), "/owners");
+
+        let warnings = spec.warnings();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].kind, WarningKind::MissingLeadingSlash);
+        assert_eq!(
+            warnings[0].message,
+            "Path 'pets/{petId}' does not start with '/', using '/pets/{petId}'"
+        );
+        assert!(spec.operation_fragment("/pets/{petId}", "get").is_some());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_from_reader() -> crate::Result<()> {
+        let yaml = "openapi: 3.0.0\ninfo:\n  title: Pets\n  version: '1'\npaths: {}\n";
+        let spec = OpenApiContext::from_reader(yaml.as_bytes(), DEFAULT_MAX_SPEC_BYTES).await?;
+        assert_eq!(spec.json["info"]["title"], "Pets");
+
+        // Exactly at the limit is fine
+        let limit = yaml.len() as u64;
+        assert!(OpenApiContext::from_reader(yaml.as_bytes(), limit)
+            .await
+            .is_ok());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_from_reader_enforces_size_limit() {
+        let oversized = format!(
+            "{{\"openapi\": \"3.0.0\", \"x-padding\": \"{}\"}}",
+            "a".repeat(1024)
+        );
+        let err = OpenApiContext::from_reader(oversized.as_bytes(), 512)
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "OpenAPI error: OpenAPI spec is larger than the 512 byte limit"
+        );
+    }
+
+    #[test]
+    fn test_all_parameters() {
+        let spec = OpenApiContext {
+            json: json!({
+                "paths": {"/pets": {
+                    "parameters": [{"$ref": "#/components/parameters/limitParam"}],
+                    "get": {
+                        "operationId": "listPets",
+                        "parameters": [{"name": "tag", "in": "query"}]
+                    },
+                    "delete": {"parameters": [{"name": "all", "in": "query"}]}
+                }},
+                "components": {"parameters": {"limitParam": {
+                    "name": "limit", "in": "query", "schema": {"type": "integer"}
+                }}}
+            }),
+        };
+
+        let params: BTreeMap<String, Vec<String>> = spec
+            .all_parameters()
+            .into_iter()
+            .map(|(id, params)| (id, params.into_iter().map(|p| p.name).collect()))
+            .collect();
+        assert_eq!(params["listPets"], ["limit", "tag"]);
+        assert_eq!(params["delete_pets"], ["limit", "all"]);
+    }
+
+    #[tokio::test]
+    async fn test_parse_operations_resolves_operation_refs() -> crate::Result<()> {
+        let manifest = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        let base = manifest.parent().unwrap().parent().unwrap();
+        let spec = OpenApiContext::from_file(
+            base.join("tests/fixtures/openapi/operation-ref.openapi.v3.json"),
+        )
+        .await?;
+
+        let operations = spec.parse_operations().await?;
+        assert_eq!(operations.len(), 1);
+        let op = &operations[0];
+        assert_eq!(op.id, "listWidgets");
+        assert_eq!(op.summary.as_deref(), Some("List widgets"));
+        assert_eq!(op.tags, Some(vec!["widgets".to_string()]));
+        assert!(op.responses.contains_key("200"));
+        let params = op.parameters.as_deref().unwrap_or_default();
+        assert_eq!(params.len(), 1);
+        assert_eq!(params[0].name, "limit");
+
+        let (_, _, raw) = spec.paths().next().unwrap();
+        assert_eq!(raw["operationId"], "listWidgets");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_examples_resolve_component_refs() -> crate::Result<()> {
+        let manifest = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        let base = manifest.parent().unwrap().parent().unwrap();
+        let spec =
+            OpenApiContext::from_file(base.join("tests/fixtures/openapi/examples.openapi.v3.json"))
+                .await?;
+
+        let operations = spec.parse_operations().await?;
+        let get_pet = operations.iter().find(|op| op.id == "getPet").unwrap();
+        assert_eq!(
+            get_pet.example_parameters(),
+            vec![json!({"name": "petId", "in": "path", "value": 42})]
+        );
+        let create_pet = operations.iter().find(|op| op.id == "createPet").unwrap();
+        assert_eq!(
+            create_pet.example_request_body(),
+            Some(json!({"name": "Rex"}))
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_bundle_inlines_external_refs() -> crate::Result<()> {
+        let dir = tempdir()?;
+        std::fs::create_dir(dir.path().join("schemas"))?;
+        std::fs::write(
+            dir.path().join("schemas/pet.yaml"),
+            "Pet:\n  type: object\n  properties:\n    tag:\n      $ref: '#/Tag'\n    owner:\n      $ref: 'owner.json'\nTag:\n  type: string\n",
+        )?;
+        std::fs::write(
+            dir.path().join("schemas/owner.json"),
+            r#"{"type": "object", "properties": {"name": {"type": "string"}}}"#,
+        )?;
+        let spec = OpenApiContext {
+            json: json!({
+                "paths": {"/pets": {"get": {"responses": {"200": {"content": {"application/json": {
+                    "schema": {"$ref": "schemas/pet.yaml#/Pet"}
+                }}}}}}},
+                "components": {"schemas": {"Local": {"$ref": "#/components/schemas/Other"}}}
+            }),
+        };
+
+        let bundled = spec.bundle(dir.path()).await?;
+        let mut refs = BTreeSet::new();
+        collect_local_refs(&bundled.json, &mut refs);
+        assert_eq!(refs, BTreeSet::from(["#/components/schemas/Other"]));
+        assert!(!bundled.json.to_string().contains("pet.yaml"));
+        let schema = &bundled.json["paths"]["/pets"]["get"]["responses"]["200"]["content"]
+            ["application/json"]["schema"];
+        assert_eq!(schema["properties"]["tag"], json!({"type": "string"}));
+        assert_eq!(
+            schema["properties"]["owner"]["properties"]["name"],
+            json!({"type": "string"})
+        );
+
+        let missing = OpenApiContext {
+            json: json!({"schema": {"$ref": "missing.yaml"}}),
+        };
+        assert!(missing.bundle(dir.path()).await.is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_bundle_rejects_circular_external_refs() -> crate::Result<()> {
+        let dir = tempdir()?;
+        std::fs::write(
+            dir.path().join("a.yaml"),
+            "Node:\n  type: object\n  properties:\n    next:\n      $ref: 'b.yaml#/Node'\n",
+        )?;
+        std::fs::write(
+            dir.path().join("b.yaml"),
+            "Node:\n  type: object\n  properties:\n    next:\n      $ref: 'a.yaml#/Node'\n",
+        )?;
+        let spec = OpenApiContext {
+            json: json!({"schema": {"$ref": "a.yaml#/Node"}}),
+        };
+
+        let err = spec.bundle(dir.path()).await.unwrap_err().to_string();
+        assert!(err.contains("is circular"), "{}", err);
+
+        // The same document referenced twice side by side isn't a cycle
+        let spec = OpenApiContext {
+            json: json!({"a": {"$ref": "b.yaml#/Node/type"}, "b": {"$ref": "b.yaml#/Node/type"}}),
+        };
+        assert_eq!(
+            spec.bundle(dir.path()).await?.json,
+            json!({"a": "object", "b": "object"})
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_ref_base_resolves_relative_to_spec_url() -> crate::Result<()> {
+        let base = RefBase::Url(url::Url::parse("https://example.com/api/openapi.yaml").unwrap());
+        let document = base.resolve("schemas/pet.yaml", "schemas/pet.yaml#/Pet")?;
+        assert_eq!(
+            document.to_string(),
+            "https://example.com/api/schemas/pet.yaml"
+        );
+        assert_eq!(
+            document
+                .base()
+                .resolve("../common.yaml", "../common.yaml")?
+                .to_string(),
+            "https://example.com/api/common.yaml"
+        );
+
+        let local = RefBase::Dir(PathBuf::from("specs"));
+        assert!(local.resolve("https://example.com/pet.yaml", "").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_warnings_report_dangling_refs() {
+        let spec = OpenApiContext {
+            json: json!({
+                "paths": {"/pets": {"get": {
+                    "operationId": "listPets",
+                    "responses": {"200": {"content": {"application/json": {"schema": {"$ref": "#/components/schemas/Missing"}}}}}
+                }}},
+                "components": {"schemas": {"Pet": {"$ref": "#/components/schemas/Pet2"}, "Pet2": {"type": "object"}}}
+            }),
+        };
+        let warnings = spec.warnings();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].kind, WarningKind::DanglingRef);
+        assert!(warnings[0].message.contains("#/components/schemas/Missing"));
+    }
+
+    #[tokio::test]
+    async fn test_from_file_rejects_binary_files() -> crate::Result<()> {
+        let dir = tempdir()?;
+        let path = dir.path().join("spec.zip");
+        std::fs::write(&path, [0x50, 0x4b, 0x03, 0x04, 0xff, 0xfe, 0x00, 0x80])?;
+
+        let err = OpenApiContext::from_file(&path)
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("is not a valid text OpenAPI spec (not UTF-8)"));
+        assert!(err.contains("did you mean to pass a bundle"));
+        assert!(err.contains("spec.zip#openapi.yaml"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_summary() {
+        let spec = OpenApiContext {
+            json: json!({
+                "info": {"title": "Pets", "version": "2.0"},
+                "servers": [{"url": "https://api.example.com"}],
+                "paths": {
+                    "/pets": {
+                        "get": {"tags": ["pets"]},
+                        "post": {"tags": ["pets", "admin"]}
+                    },
+                    "/stores": {"get": {"tags": ["stores"]}}
+                },
+                "components": {"schemas": {"Pet": {}, "Store": {}}}
+            }),
+        };
+        let summary = spec.summary();
+        assert_eq!(summary.title.as_deref(), Some("Pets"));
+        assert_eq!(summary.version.as_deref(), Some("2.0"));
+        assert_eq!(summary.description, None);
+        assert_eq!(summary.servers, vec!["https://api.example.com"]);
+        assert_eq!(summary.operations, 3);
+        assert_eq!(summary.operations_by_method["GET"], 2);
+        assert_eq!(summary.operations_by_method["POST"], 1);
+        assert_eq!(summary.component_schemas, 2);
+        let tags: Vec<(&str, usize)> = summary
+            .tags
+            .iter()
+            .map(|t| (t.name.as_str(), t.operations))
+            .collect();
+        assert_eq!(tags, vec![("pets", 2), ("admin", 1), ("stores", 1)]);
+    }
+
+    #[test]
+    fn test_path_parameter_mismatches() {
+        let spec = OpenApiContext {
+            json: json!({"paths": {
+                "/pets/{petId}": {"get": {"operationId": "getPet"}},
+                "/owners/{ownerId}": {
+                    "parameters": [{"name": "ownerId", "in": "path", "required": true}],
+                    "get": {
+                        "operationId": "getOwner",
+                        "parameters": [{"name": "petId", "in": "path", "required": true}]
+                    }
+                }
+            }}),
+        };
+        let warnings = spec.warnings();
+        assert_eq!(warnings.len(), 2);
+        assert!(warnings
+            .iter()
+            .all(|w| w.kind == WarningKind::PathParameterMismatch));
+        let messages: Vec<&str> = warnings.iter().map(|w| w.message.as_str()).collect();
+        assert!(
+            messages.contains(&"GET /pets/{petId} has no path parameter declared for '{petId}'")
+        );
+        assert!(messages.contains(
+            &"GET /owners/{ownerId} declares path parameter 'petId' that is not in the path"
+        ));
+
+        let err = spec.validate().unwrap_err().to_string();
+        assert!(err.contains("'{petId}'"));
+        assert!(err.contains("'petId' that is not in the path"));
+    }
+
+    #[test]
+    fn test_missing_success_responses() {
+        let spec = OpenApiContext {
+            json: json!({"paths": {
+                "/pets/{petId}": {"get": {
+                    "operationId": "getPet",
+                    "parameters": [{"name": "petId", "in": "path", "required": true}],
+                    "responses": {"404": {"description": "Not found"}}
+                }},
+                "/pets": {
+                    "get": {"operationId": "listPets", "responses": {"2XX": {"description": "Pets"}}},
+                    "post": {"operationId": "createPet", "responses": {"201": {"description": "Created"}}}
+                }
+            }}),
+        };
+        let warnings = spec.warnings();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].kind, WarningKind::MissingSuccessResponse);
+        assert_eq!(
+            warnings[0].message,
+            "GET /pets/{petId} declares no success (2xx) response"
+        );
+        let err = spec.validate().unwrap_err().to_string();
+        assert!(err.contains("GET /pets/{petId} declares no success (2xx) response"));
+    }
+
+    #[test]
+    fn test_read_write_only_mismatches() {
+        let spec = OpenApiContext {
+            json: json!({
+                "paths": {"/users": {"post": {
+                    "operationId": "createUser",
+                    "requestBody": {"content": {"application/json": {
+                        "schema": {"$ref": "#/components/schemas/User"}
+                    }}},
+                    "responses": {"201": {"description": "Created", "content": {
+                        "application/json": {"schema": {"$ref": "#/components/schemas/User"}}
+                    }}}
+                }}},
+                "components": {"schemas": {
+                    "User": {"type": "object", "properties": {
+                        "id": {"type": "integer", "readOnly": true},
+                        "password": {"type": "string", "writeOnly": true},
+                        "manager": {"$ref": "#/components/schemas/User"},
+                        "tags": {"type": "array", "items": {"$ref": "#/components/schemas/Tag"}}
+                    }},
+                    "Tag": {"type": "object", "properties": {
+                        "createdAt": {"type": "string", "readOnly": true}
+                    }}
+                }}
+            }),
+        };
+        let messages: Vec<String> = spec
+            .warnings()
+            .into_iter()
+            .filter(|w| w.kind == WarningKind::ReadWriteOnlyMismatch)
+            .map(|w| w.message)
+            .collect();
+        assert_eq!(
+            messages,
+            [
+                "POST /users request body includes readOnly fields: id, tags[].createdAt",
+                "POST /users response includes writeOnly fields: password"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_parse_operations_rejects_duplicate_operation_ids() {
+        let spec = OpenApiContext {
+            json: json!({"paths": {
+                "/pets": {"get": {"operationId": "getPets"}},
+                "/animals": {"get": {"operationId": "getPets"}},
+                "/owners": {"get": {"operationId": "getOwners"}}
+            }}),
+        };
+        let err = spec.parse_operations().await.unwrap_err().to_string();
+        assert!(err.contains("Duplicate operationId"));
+        assert!(err.contains("'getPets' (GET /animals, GET /pets)"));
+        assert!(!err.contains("getOwners"));
+    }
+
+    #[tokio::test]
+    async fn test_paths_iterates_all_operations() -> crate::Result<()> {
+        let manifest = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        let base = manifest.parent().unwrap().parent().unwrap();
+        let spec =
+            OpenApiContext::from_file(base.join("tests/fixtures/openapi/petstore.openapi.v3.json"))
+                .await?;
+
+        let triples: Vec<_> = spec.paths().collect();
+        assert_eq!(triples.len(), 19);
+        let (path, method, operation) = triples
+            .iter()
+            .find(|(_, _, op)| op["operationId"] == "deletePet")
+            .unwrap();
+        assert_eq!((*path, *method), ("/pet/{petId}", "delete"));
+        assert!(operation.get("responses").is_some());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_parse_operations_inherits_path_item_docs() -> crate::Result<()> {
+        let spec = OpenApiContext {
+            json: json!({"paths": {"/pets": {
+                "summary": "Pet collection",
+                "description": "Operations on the pet collection",
+                "get": {"operationId": "listPets"},
+                "post": {"operationId": "createPet", "summary": "Create a pet"}
+            }}}),
+        };
+        let ops = spec.parse_operations().await?;
+        let list = ops.iter().find(|op| op.id == "listPets").unwrap();
+        assert_eq!(list.summary.as_deref(), Some("Pet collection"));
+        assert_eq!(
+            list.description.as_deref(),
+            Some("Operations on the pet collection")
+        );
+        let create = ops.iter().find(|op| op.id == "createPet").unwrap();
+        assert_eq!(create.summary.as_deref(), Some("Create a pet"));
+        assert_eq!(
+            create.description.as_deref(),
+            Some("Operations on the pet collection")
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_parse_operations_inherits_path_item_deprecated() -> crate::Result<()> {
+        let spec = OpenApiContext {
+            json: json!({"paths": {
+                "/v1/pets": {
+                    "deprecated": true,
+                    "get": {"operationId": "listPetsV1"},
+                    "post": {"operationId": "createPetV1", "deprecated": false}
+                },
+                "/pets": {"get": {"operationId": "listPets"}}
+            }}),
+        };
+        let ops = spec.parse_operations().await?;
+        let deprecated = |id: &str| ops.iter().find(|op| op.id == id).unwrap().deprecated;
+        assert_eq!(deprecated("listPetsV1"), Some(true));
+        assert_eq!(deprecated("createPetV1"), Some(false));
+        assert_eq!(deprecated("listPets"), None);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_operation_id_strategy_names_missing_ids() -> crate::Result<()> {
+        let spec = OpenApiContext {
+            json: json!({"paths": {
+                "/pets/{petId}": {"get": {"summary": "Find a pet by ID"}},
+                "/pets": {"get": {}, "post": {"operationId": "addPet"}}
+            }}),
+        };
+        let spec = &spec;
+        let ids = |strategy| async move {
+            let mut ids: Vec<String> = spec
+                .parse_operations_with(strategy)
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|op| op.id)
+                .collect();
+            ids.sort();
+            ids
+        };
+        assert_eq!(
+            ids(OperationIdStrategy::MethodPath).await,
+            ["addPet", "get_pets", "get_pets_petId"]
+        );
+        assert_eq!(
+            ids(OperationIdStrategy::PathMethod).await,
+            ["addPet", "pets_get", "pets_petId_get"]
+        );
+        // Operations without a summary fall back to method_path
+        assert_eq!(
+            ids(OperationIdStrategy::Summary).await,
+            ["addPet", "find_a_pet_by_id", "get_pets"]
+        );
+        assert!(spec
+            .warnings_with(OperationIdStrategy::Summary)
+            .iter()
+            .any(|w| w.message.contains("'find_a_pet_by_id'")));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_synthesized_operation_ids_are_unique() -> crate::Result<()> {
+        let spec = OpenApiContext {
+            json: json!({"paths": {
+                "/pets/id": {"get": {"summary": "List pets"}},
+                "/pets/{id}": {"get": {"summary": "List pets"}},
+                "/pets": {"get": {"operationId": "get_pets_id_2"}}
+            }}),
+        };
+        let ids = |operations: Vec<OpenApiOperation>| -> Vec<String> {
+            operations.into_iter().map(|op| op.id).collect()
+        };
+        // Sanitized paths collide, and so do shared summaries; explicit IDs are never renamed
+        assert_eq!(
+            ids(spec.parse_operations().await?),
+            ["get_pets_id_2", "get_pets_id", "get_pets_id_3"]
+        );
+        assert_eq!(
+            ids(spec
+                .parse_operations_with(OperationIdStrategy::Summary)
+                .await?),
+            ["get_pets_id_2", "list_pets", "list_pets_2"]
+        );
+
+        // Diffs match operations by the same IDs generation uses
+        let mut next = spec.json.clone();
+        next["paths"]["/pets/{id}"]["get"]["description"] = json!("One pet");
+        let diff = spec.diff_with(&OpenApiContext { json: next }, OperationIdStrategy::Summary);
+        assert_eq!(diff.modified_operations, ["list_pets_2"]);
+        Ok(())
+    }
 }
@@ -0,0 +1,290 @@
+//! Declarative edits to a spec before it is parsed.
+//!
+//! Two patch formats are supported, told apart by their shape:
+//!
+//! - a JSON array is a [JSON Patch](https://www.rfc-editor.org/rfc/rfc6902)
+//!   (RFC 6902), a list of `add`, `remove`, `replace`, `move`, `copy` and
+//!   `test` operations addressed by JSON Pointer
+//! - a JSON object is a [JSON Merge Patch](https://www.rfc-editor.org/rfc/rfc7386)
+//!   (RFC 7386), merged into the spec with `null` removing keys
+//!
+//! A JSON Patch is applied atomically: when any operation fails the target is
+//! left unchanged and the error names the failing operation.
+
+use serde_json::Value as JsonValue;
+
+use crate::error::{Error, Result};
+
+/// Apply `patch` to `target`, as a JSON Patch when it is an array and as a
+/// merge patch otherwise
+pub fn apply_patch(target: &mut JsonValue, patch: &JsonValue) -> Result<()> {
+    if patch.is_array() {
+        apply_json_patch(target, patch)
+    } else {
+        apply_merge_patch(target, patch);
+        Ok(())
+    }
+}
+
+/// Apply an RFC 6902 JSON Patch document
+///
+/// # Errors
+///
+/// Returns an error if the document is not an array of well-formed
+/// operations, or if an operation fails; `target` is unchanged in both cases.
+pub fn apply_json_patch(target: &mut JsonValue, patch: &JsonValue) -> Result<()> {
+    let raw = patch
+        .as_array()
+        .ok_or_else(|| Error::config("JSON Patch must be an array of operations"))?;
+    let operations = raw
+        .iter()
+        .enumerate()
+        .map(|(index, op)| Operation::parse(op).map_err(|e| operation_error(index, op, &e)))
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut patched = target.clone();
+    for (index, (op, raw)) in operations.iter().zip(raw).enumerate() {
+        op.apply(&mut patched)
+            .map_err(|e| operation_error(index, raw, &e))?;
+    }
+    *target = patched;
+    Ok(())
+}
+
+/// Apply an RFC 7386 JSON Merge Patch: objects merge recursively, `null`
+/// removes a key and any other value replaces the target
+pub fn apply_merge_patch(target: &mut JsonValue, patch: &JsonValue) {
+    let JsonValue::Object(patch) = patch else {
+        *target = patch.clone();
+        return;
+    };
+    if !target.is_object() {
+        *target = JsonValue::Object(Default::default());
+    }
+    if let JsonValue::Object(target) = target {
+        for (key, value) in patch {
+            if value.is_null() {
+                target.remove(key);
+            } else {
+                apply_merge_patch(target.entry(key.clone()).or_insert(JsonValue::Null), value);
+            }
+        }
+    }
+}
+
+/// Error for the operation at `index`, e.g. `JSON Patch operation 2 (remove /paths/~1admin) failed: ...`
+fn operation_error(index: usize, op: &JsonValue, reason: &str) -> Error {
+    let name = op.get("op").and_then(JsonValue::as_str).unwrap_or("?");
+    let path = op.get("path").and_then(JsonValue::as_str).unwrap_or("?");
+    Error::config(format!(
+        "JSON Patch operation {} ({} {}) failed: {}",
+        index, name, path, reason
+    ))
+}
+
+/// One validated JSON Patch operation
+enum Operation<'a> {
+    Add(&'a str, &'a JsonValue),
+    Remove(&'a str),
+    Replace(&'a str, &'a JsonValue),
+    Move { from: &'a str, path: &'a str },
+    Copy { from: &'a str, path: &'a str },
+    Test(&'a str, &'a JsonValue),
+}
+
+impl<'a> Operation<'a> {
+    fn parse(op: &'a JsonValue) -> std::result::Result<Self, String> {
+        let pointer = |key: &str| -> std::result::Result<&'a str, String> {
+            let pointer = op
+                .get(key)
+                .and_then(JsonValue::as_str)
+                .ok_or_else(|| format!("missing '{}'", key))?;
+            if !pointer.is_empty() && !pointer.starts_with('/') {
+                return Err(format!("'{}' is not a JSON Pointer: '{}'", key, pointer));
+            }
+            Ok(pointer)
+        };
+        let value = || op.get("value").ok_or_else(|| "missing 'value'".to_string());
+        let path = pointer("path")?;
+        match op.get("op").and_then(JsonValue::as_str) {
+            Some("add") => Ok(Operation::Add(path, value()?)),
+            Some("remove") => Ok(Operation::Remove(path)),
+            Some("replace") => Ok(Operation::Replace(path, value()?)),
+            Some("move") => Ok(Operation::Move {
+                from: pointer("from")?,
+                path,
+            }),
+            Some("copy") => Ok(Operation::Copy {
+                from: pointer("from")?,
+                path,
+            }),
+            Some("test") => Ok(Operation::Test(path, value()?)),
+            Some(other) => Err(format!("unknown op '{}'", other)),
+            None => Err("missing 'op'".to_string()),
+        }
+    }
+
+    fn apply(&self, target: &mut JsonValue) -> std::result::Result<(), String> {
+        match *self {
+            Operation::Add(path, value) => add(target, path, value.clone()),
+            Operation::Remove(path) => remove(target, path).map(drop),
+            Operation::Replace(path, value) => {
+                let slot = target
+                    .pointer_mut(path)
+                    .ok_or_else(|| format!("'{}' does not exist", path))?;
+                *slot = value.clone();
+                Ok(())
+            }
+            Operation::Move { from, path } => {
+                if path.starts_with(&format!("{}/", from)) {
+                    return Err(format!("cannot move '{}' into itself", from));
+                }
+                let value = remove(target, from)?;
+                add(target, path, value)
+            }
+            Operation::Copy { from, path } => {
+                let value = target
+                    .pointer(from)
+                    .cloned()
+                    .ok_or_else(|| format!("'{}' does not exist", from))?;
+                add(target, path, value)
+            }
+            Operation::Test(path, expected) => match target.pointer(path) {
+                Some(actual) if actual == expected => Ok(()),
+                Some(actual) => Err(format!("value at '{}' is {}", path, actual)),
+                None => Err(format!("'{}' does not exist", path)),
+            },
+        }
+    }
+}
+
+/// Split a non-empty pointer into its parent pointer and unescaped last token
+fn split_pointer(path: &str) -> (&str, String) {
+    let (parent, token) = path.rsplit_once('/').unwrap_or(("", path));
+    (parent, token.replace("~1", "/").replace("~0", "~"))
+}
+
+/// Array index named by `token`, up to `len` inclusive when `allow_end`
+fn array_index(token: &str, len: usize, allow_end: bool) -> std::result::Result<usize, String> {
+    let index = match token {
+        "-" if allow_end => len,
+        _ => token
+            .parse::<usize>()
+            .ok()
+            .filter(|_| token == "0" || !token.starts_with('0'))
+            .ok_or_else(|| format!("'{}' is not an array index", token))?,
+    };
+    if index > len || (index == len && !allow_end) {
+        return Err(format!("index {} is out of bounds", index));
+    }
+    Ok(index)
+}
+
+fn add(target: &mut JsonValue, path: &str, value: JsonValue) -> std::result::Result<(), String> {
+    if path.is_empty() {
+        *target = value;
+        return Ok(());
+    }
+    let (parent, token) = split_pointer(path);
+    match target.pointer_mut(parent) {
+        Some(JsonValue::Object(map)) => {
+            map.insert(token, value);
+            Ok(())
+        }
+        Some(JsonValue::Array(items)) => {
+            let index = array_index(&token, items.len(), true)?;
+            items.insert(index, value);
+            Ok(())
+        }
+        Some(_) => Err(format!("'{}' is not an object or array", parent)),
+        None => Err(format!("'{}' does not exist", parent)),
+    }
+}
+
+fn remove(target: &mut JsonValue, path: &str) -> std::result::Result<JsonValue, String> {
+    if path.is_empty() {
+        return Err("cannot remove the whole document".to_string());
+    }
+    let (parent, token) = split_pointer(path);
+    let removed = match target.pointer_mut(parent) {
+        Some(JsonValue::Object(map)) => map.remove(&token),
+        Some(JsonValue::Array(items)) => {
+            let index = array_index(&token, items.len(), false)?;
+            Some(items.remove(index))
+        }
+        _ => None,
+    };
+    removed.ok_or_else(|| format!("'{}' does not exist", path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_apply_json_patch() -> Result<()> {
+        let mut spec = json!({"paths": {"/pets": {}, "/admin": {}}, "tags": ["a"]});
+        apply_patch(
+            &mut spec,
+            &json!([
+                {"op": "test", "path": "/tags/0", "value": "a"},
+                {"op": "add", "path": "/paths/~1owners", "value": {"get": {}}},
+                {"op": "remove", "path": "/paths/~1admin"},
+                {"op": "add", "path": "/tags/-", "value": "b"},
+                {"op": "copy", "from": "/tags/1", "path": "/tags/0"},
+                {"op": "move", "from": "/paths/~1pets", "path": "/paths/~1animals"},
+                {"op": "replace", "path": "/paths/~1animals", "value": {"post": {}}}
+            ]),
+        )?;
+        assert_eq!(
+            spec,
+            json!({
+                "paths": {"/owners": {"get": {}}, "/animals": {"post": {}}},
+                "tags": ["b", "a", "b"]
+            })
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_failed_operation_is_reported_and_nothing_applied() {
+        let mut spec = json!({"paths": {"/pets": {}}});
+        let err = apply_patch(
+            &mut spec,
+            &json!([
+                {"op": "remove", "path": "/paths/~1pets"},
+                {"op": "remove", "path": "/paths/~1admin"}
+            ]),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Configuration error: JSON Patch operation 1 (remove /paths/~1admin) failed: '/paths/~1admin' does not exist"
+        );
+        assert_eq!(spec, json!({"paths": {"/pets": {}}}));
+
+        let err = apply_patch(&mut spec, &json!([{"op": "add", "path": "/x"}])).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("operation 0 (add /x) failed: missing 'value'"));
+    }
+
+    #[test]
+    fn test_apply_merge_patch() -> Result<()> {
+        let mut spec = json!({"info": {"title": "Pets", "version": "1"}, "paths": {"/admin": {}}});
+        apply_patch(
+            &mut spec,
+            &json!({"info": {"title": "Animals"}, "paths": {"/admin": null}, "servers": [{"url": "/"}]}),
+        )?;
+        assert_eq!(
+            spec,
+            json!({
+                "info": {"title": "Animals", "version": "1"},
+                "paths": {},
+                "servers": [{"url": "/"}]
+            })
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,430 @@
+//! Postman collection import.
+//!
+//! This module parses Postman v2.1 collections and converts them into an
+//! OpenAPI document so that they can be fed through the regular generation
+//! pipeline. Folders become tags, saved requests become operations, and
+//! request/response bodies get a schema inferred from their JSON examples.
+//!
+//! The conversion is best-effort: Postman collections describe concrete
+//! requests rather than an API contract, so the resulting spec only contains
+//! what can be observed in the collection.
+
+use serde::Deserialize;
+use serde_json::{json, Map, Value as JsonValue};
+use std::collections::BTreeMap;
+use std::path::Path;
+use tokio::fs;
+
+use crate::{
+    openapi::{path_template_names, OpenApiContext},
+    utils::to_lower_camel_case,
+    Error,
+};
+
+/// Top level structure of a Postman v2.1 collection.
+#[derive(Debug, Deserialize)]
+struct PostmanCollection {
+    info: PostmanInfo,
+    #[serde(default)]
+    item: Vec<PostmanItem>,
+    #[serde(default)]
+    variable: Vec<PostmanVariable>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PostmanInfo {
+    name: String,
+    #[serde(default)]
+    description: Option<JsonValue>,
+}
+
+/// A request or a folder of further items.
+#[derive(Debug, Deserialize)]
+struct PostmanItem {
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    item: Option<Vec<PostmanItem>>,
+    #[serde(default)]
+    request: Option<JsonValue>,
+    #[serde(default)]
+    response: Vec<JsonValue>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PostmanVariable {
+    key: String,
+    #[serde(default)]
+    value: Option<JsonValue>,
+}
+
+/// Parsed representation of a Postman collection.
+pub struct PostmanContext {
+    collection: PostmanCollection,
+}
+
+impl PostmanContext {
+    /// Load a Postman v2.1 collection from disk.
+    pub async fn from_file<P: AsRef<Path>>(path: P) -> crate::Result<Self> {
+        let content = fs::read_to_string(&path).await?;
+        let collection: PostmanCollection = serde_json::from_str(&content).map_err(|e| {
+            Error::config(format!(
+                "Failed to parse Postman collection {}: {}",
+                path.as_ref().display(),
+                e
+            ))
+        })?;
+        Ok(Self { collection })
+    }
+
+    /// Convert the collection into an OpenAPI 3.0 document.
+    ///
+    /// Collection variables are substituted into request URLs; any that remain
+    /// (e.g. an unset `{{baseUrl}}`) leave the server relative, so a base URL
+    /// must be supplied at generation time.
+    pub fn to_openapi(&self) -> OpenApiContext {
+        let variables: BTreeMap<&str, String> = self
+            .collection
+            .variable
+            .iter()
+            .filter_map(|v| {
+                let value = match v.value.as_ref()? {
+                    JsonValue::String(s) => s.clone(),
+                    other => other.to_string(),
+                };
+                Some((v.key.as_str(), value))
+            })
+            .collect();
+
+        let mut requests = Vec::new();
+        collect_requests(&self.collection.item, None, &mut requests);
+
+        let mut paths = Map::new();
+        let mut server = None;
+        let mut used_ids: BTreeMap<String, usize> = BTreeMap::new();
+        for (tag, item, request) in requests {
+            let Some(raw_url) = request_url(request) else {
+                continue;
+            };
+            let url = substitute_variables(&raw_url, &variables);
+            let (origin, path, query) = split_url(&url);
+            if server.is_none() {
+                server = origin;
+            }
+            let method = request
+                .get("method")
+                .and_then(JsonValue::as_str)
+                .unwrap_or("GET")
+                .to_lowercase();
+
+            let mut operation = Map::new();
+            if !item.name.is_empty() {
+                let base_id = to_lower_camel_case(&item.name);
+                let count = used_ids.entry(base_id.clone()).or_insert(0);
+                *count += 1;
+                let id = if *count == 1 {
+                    base_id
+                } else {
+                    format!("{}{}", base_id, count)
+                };
+                operation.insert("operationId".into(), json!(id));
+                operation.insert("summary".into(), json!(item.name));
+            }
+            if let Some(description) = request.get("description").and_then(description_text) {
+                operation.insert("description".into(), json!(description));
+            }
+            if let Some(tag) = tag {
+                operation.insert("tags".into(), json!([tag]));
+            }
+
+            let mut parameters: Vec<JsonValue> = path_template_names(&path)
+                .into_iter()
+                .map(|name| {
+                    json!({"name": name, "in": "path", "required": true, "schema": {"type": "string"}})
+                })
+                .collect();
+            parameters.extend(query.into_iter().map(|(name, value)| {
+                json!({"name": name, "in": "query", "schema": {"type": "string"}, "example": value})
+            }));
+            if !parameters.is_empty() {
+                operation.insert("parameters".into(), json!(parameters));
+            }
+
+            if let Some(body) = request.get("body").and_then(request_body) {
+                operation.insert("requestBody".into(), body);
+            }
+            operation.insert("responses".into(), responses(&item.response));
+
+            let path_item = paths
+                .entry(path)
+                .or_insert_with(|| JsonValue::Object(Map::new()));
+            if let Some(path_item) = path_item.as_object_mut() {
+                path_item.insert(method, JsonValue::Object(operation));
+            }
+        }
+
+        let mut info = json!({"title": self.collection.info.name, "version": "1.0.0"});
+        if let Some(description) = self
+            .collection
+            .info
+            .description
+            .as_ref()
+            .and_then(description_text)
+        {
+            info["description"] = json!(description);
+        }
+
+        OpenApiContext {
+            json: json!({
+                "openapi": "3.0.0",
+                "info": info,
+                "servers": [{"url": server.unwrap_or_else(|| "/".to_string())}],
+                "paths": paths,
+            }),
+        }
+    }
+}
+
+/// Flatten folders into `(tag, item, request)` triples; the tag is the top-level folder name
+fn collect_requests<'a>(
+    items: &'a [PostmanItem],
+    tag: Option<&'a str>,
+    out: &mut Vec<(Option<&'a str>, &'a PostmanItem, &'a JsonValue)>,
+) {
+    for item in items {
+        if let Some(children) = &item.item {
+            collect_requests(children, tag.or(Some(item.name.as_str())), out);
+        } else if let Some(request) = &item.request {
+            out.push((tag, item, request));
+        }
+    }
+}
+
+/// Raw URL of a request, which may be a bare string or a URL object
+fn request_url(request: &JsonValue) -> Option<String> {
+    let url = match request {
+        JsonValue::String(url) => return Some(url.clone()),
+        other => other.get("url")?,
+    };
+    match url {
+        JsonValue::String(raw) => Some(raw.clone()),
+        JsonValue::Object(obj) => {
+            if let Some(raw) = obj.get("raw").and_then(JsonValue::as_str) {
+                return Some(raw.to_string());
+            }
+            let join = |key: &str, sep: &str| {
+                obj.get(key)
+                    .and_then(JsonValue::as_array)
+                    .map(|parts| {
+                        parts
+                            .iter()
+                            .filter_map(JsonValue::as_str)
+                            .collect::<Vec<_>>()
+                            .join(sep)
+                    })
+                    .unwrap_or_default()
+            };
+            Some(format!("{}/{}", join("host", "."), join("path", "/")))
+        }
+        _ => None,
+    }
+}
+
+/// Replace `{{name}}` with collection variable values, leaving unknown variables as-is
+fn substitute_variables(url: &str, variables: &BTreeMap<&str, String>) -> String {
+    let mut result = url.to_string();
+    for (key, value) in variables {
+        result = result.replace(&format!("{{{{{}}}}}", key), value);
+    }
+    result
+}
+
+/// Split a URL into its origin (when absolute), OpenAPI path template and query pairs
+fn split_url(url: &str) -> (Option<String>, String, Vec<(String, String)>) {
+    let url = url.split('#').next().unwrap_or_default();
+    let (location, query) = url.split_once('?').unwrap_or((url, ""));
+
+    let (origin, path) = match location.split_once("://") {
+        Some((scheme, rest)) => {
+            let (host, path) = rest.split_once('/').unwrap_or((rest, ""));
+            (Some(format!("{}://{}", scheme, host)), path)
+        }
+        // No scheme: the first segment is a host or an unresolved `{{baseUrl}}`
+        None => (None, location.split_once('/').map_or("", |(_, path)| path)),
+    };
+
+    let segments: Vec<String> = path
+        .split('/')
+        .filter(|s| !s.is_empty())
+        .map(|segment| {
+            if let Some(name) = segment.strip_prefix(':') {
+                format!("{{{}}}", name)
+            } else if let Some(name) = segment
+                .strip_prefix("{{")
+                .and_then(|s| s.strip_suffix("}}"))
+            {
+                format!("{{{}}}", name)
+            } else {
+                segment.to_string()
+            }
+        })
+        .collect();
+
+    let query = query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (name.to_string(), value.to_string())
+        })
+        .collect();
+
+    (origin, format!("/{}", segments.join("/")), query)
+}
+
+/// Description text, which Postman stores either as a string or as `{ content }`
+fn description_text(value: &JsonValue) -> Option<String> {
+    match value {
+        JsonValue::String(s) => Some(s.clone()),
+        other => other
+            .get("content")
+            .and_then(JsonValue::as_str)
+            .map(String::from),
+    }
+}
+
+/// OpenAPI `requestBody` for a Postman body, inferring a schema from JSON examples
+fn request_body(body: &JsonValue) -> Option<JsonValue> {
+    match body.get("mode").and_then(JsonValue::as_str)? {
+        "raw" => {
+            let raw = body.get("raw").and_then(JsonValue::as_str)?;
+            let content = match serde_json::from_str::<JsonValue>(raw) {
+                Ok(example) => json!({"application/json": {
+                    "schema": infer_schema(&example),
+                    "example": example
+                }}),
+                Err(_) => json!({"text/plain": {"schema": {"type": "string"}, "example": raw}}),
+            };
+            Some(json!({"content": content}))
+        }
+        mode @ ("urlencoded" | "formdata") => {
+            let properties: Map<String, JsonValue> = body
+                .get(mode)
+                .and_then(JsonValue::as_array)
+                .into_iter()
+                .flatten()
+                .filter_map(|field| field.get("key").and_then(JsonValue::as_str))
+                .map(|key| (key.to_string(), json!({"type": "string"})))
+                .collect();
+            let media_type = if mode == "urlencoded" {
+                "application/x-www-form-urlencoded"
+            } else {
+                "multipart/form-data"
+            };
+            Some(json!({"content": {
+                media_type: {"schema": {"type": "object", "properties": properties}}
+            }}))
+        }
+        _ => None,
+    }
+}
+
+/// OpenAPI responses from saved example responses, defaulting to a bare 200
+fn responses(saved: &[JsonValue]) -> JsonValue {
+    let mut responses = Map::new();
+    for response in saved {
+        let code = response
+            .get("code")
+            .and_then(JsonValue::as_u64)
+            .unwrap_or(200)
+            .to_string();
+        let description = response
+            .get("status")
+            .or_else(|| response.get("name"))
+            .and_then(JsonValue::as_str)
+            .unwrap_or("Response");
+        let mut entry = json!({"description": description});
+        if let Some(example) = response
+            .get("body")
+            .and_then(JsonValue::as_str)
+            .and_then(|body| serde_json::from_str::<JsonValue>(body).ok())
+        {
+            entry["content"] = json!({"application/json": {
+                "schema": infer_schema(&example),
+                "example": example
+            }});
+        }
+        responses.entry(code).or_insert(entry);
+    }
+    if responses.is_empty() {
+        responses.insert("200".into(), json!({"description": "Successful response"}));
+    }
+    JsonValue::Object(responses)
+}
+
+/// Infer a JSON schema from an example value
+fn infer_schema(example: &JsonValue) -> JsonValue {
+    match example {
+        JsonValue::Object(obj) => {
+            let properties: Map<String, JsonValue> = obj
+                .iter()
+                .map(|(k, v)| (k.clone(), infer_schema(v)))
+                .collect();
+            json!({"type": "object", "properties": properties})
+        }
+        JsonValue::Array(items) => match items.first() {
+            Some(first) => json!({"type": "array", "items": infer_schema(first)}),
+            None => json!({"type": "array", "items": {}}),
+        },
+        JsonValue::String(_) => json!({"type": "string"}),
+        JsonValue::Number(n) if n.is_i64() || n.is_u64() => json!({"type": "integer"}),
+        JsonValue::Number(_) => json!({"type": "number"}),
+        JsonValue::Bool(_) => json!({"type": "boolean"}),
+        JsonValue::Null => json!({}),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[tokio::test]
+    async fn test_to_openapi() -> crate::Result<()> {
+        let manifest = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        let base = manifest.parent().unwrap().parent().unwrap();
+        let collection_path = base.join("tests/fixtures/postman/petstore.postman_collection.json");
+        let ctx = PostmanContext::from_file(&collection_path).await?;
+        let spec = ctx.to_openapi();
+
+        assert_eq!(spec.paths().count(), 5);
+        assert_eq!(
+            spec.json["servers"][0]["url"],
+            "https://petstore.example.com"
+        );
+
+        let get_pet = &spec.json["paths"]["/v1/pets/{petId}"]["get"];
+        assert_eq!(get_pet["operationId"], "getPet");
+        assert_eq!(get_pet["tags"], json!(["Pets"]));
+        assert_eq!(get_pet["parameters"][0]["in"], "path");
+
+        let create_pet = &spec.json["paths"]["/v1/pets"]["post"];
+        assert_eq!(
+            create_pet["requestBody"]["content"]["application/json"]["schema"]["properties"]["age"]
+                ["type"],
+            "integer"
+        );
+
+        let operations = spec.parse_operations().await?;
+        assert!(operations.iter().any(|op| op.id == "listPets"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_split_url() {
+        let (origin, path, query) = split_url("{{baseUrl}}/pets/:petId/toys?limit=10");
+        assert_eq!(origin, None);
+        assert_eq!(path, "/pets/{petId}/toys");
+        assert_eq!(query, vec![("limit".to_string(), "10".to_string())]);
+    }
+}
@@ -0,0 +1,73 @@
+//! Structured outcome of a generation run.
+//!
+//! Problems that don't stop generation are collected as [`Warning`]s instead of
+//! only being logged, so embedders can inspect them and the CLI can print them.
+
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::builders::UnmappedType;
+
+/// What a [`Warning`] is about
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WarningKind {
+    /// An operation has no `operationId`, so one was derived from method and path
+    SynthesizedOperationId,
+    /// A schema has no precise target type and falls back to a generic one
+    UnmappedType,
+    /// A local `$ref` points at nothing in the spec
+    DanglingRef,
+    /// The template manifest's `language` differs from the template kind's
+    LanguageMismatch,
+    /// A path template `{name}` has no `in: path` parameter, or a path
+    /// parameter has no `{name}` in the path
+    PathParameterMismatch,
+    /// An operation declares responses but none of them is a success (2xx) response
+    MissingSuccessResponse,
+    /// A `paths` key doesn't start with `/`; the operation is generated with one
+    MissingLeadingSlash,
+    /// A request body schema includes `readOnly` fields, or a success response
+    /// schema includes `writeOnly` fields
+    ReadWriteOnlyMismatch,
+}
+
+/// A non-fatal problem found while generating
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Warning {
+    pub kind: WarningKind,
+    pub message: String,
+}
+
+impl Warning {
+    pub fn new(kind: WarningKind, message: impl Into<String>) -> Self {
+        Self {
+            kind,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl From<UnmappedType> for Warning {
+    fn from(unmapped: UnmappedType) -> Self {
+        Warning::new(WarningKind::UnmappedType, unmapped.to_string())
+    }
+}
+
+/// Result of [`TemplateManager::generate`](crate::TemplateManager::generate)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct GenerationReport {
+    /// Non-fatal problems, in the order they were found
+    pub warnings: Vec<Warning>,
+
+    /// Nothing was generated because the inputs match the last generation
+    /// into the output directory (see [`TemplateOptions::force`](crate::TemplateOptions::force))
+    pub skipped: bool,
+}
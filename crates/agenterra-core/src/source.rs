@@ -0,0 +1,267 @@
+//! Where an OpenAPI spec is loaded from.
+//!
+//! [`SpecSource`] is the single place that decides how a `--schema-path`
+//! style argument is read: `-` means standard input, `http://`/`https://`
+//! locations are fetched, `bundle.zip#openapi.yaml` names an entry of a zip
+//! archive, and anything else is a local file.
+//!
+//! ```no_run
+//! use agenterra_core::SpecSource;
+//!
+//! # #[tokio::main]
+//! # async fn main() -> agenterra_core::Result<()> {
+//! let source: SpecSource = "https://petstore3.swagger.io/api/v3/openapi.json".parse()?;
+//! let spec = source.load().await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{fmt, path::PathBuf, str::FromStr};
+
+use crate::{
+    openapi::{OpenApiContext, DEFAULT_MAX_SPEC_BYTES},
+    Error,
+};
+
+/// Location of an OpenAPI spec (YAML or JSON)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SpecSource {
+    /// A local file
+    File(PathBuf),
+    /// An `http://` or `https://` URL
+    Url(String),
+    /// An entry of a zip archive, written as `bundle.zip#openapi.yaml`; sibling
+    /// `$ref`s are resolved from the other entries
+    Archive { path: PathBuf, entry: String },
+    /// Standard input, written as `-` on the command line
+    Stdin,
+    /// The spec content itself
+    Inline(String),
+}
+
+impl SpecSource {
+    /// Read and parse the spec
+    pub async fn load(&self) -> crate::Result<OpenApiContext> {
+        self.load_with_limit(DEFAULT_MAX_SPEC_BYTES).await
+    }
+
+    /// Read and parse the spec, failing if a URL or stdin yields more than
+    /// `max_bytes`; local files and archives are trusted and not limited
+    pub async fn load_with_limit(&self, max_bytes: u64) -> crate::Result<OpenApiContext> {
+        match self {
+            SpecSource::File(path) => OpenApiContext::from_file(path).await,
+            SpecSource::Url(url) => OpenApiContext::from_url_with_limit(url, max_bytes).await,
+            SpecSource::Archive { path, entry } => OpenApiContext::from_archive(path, entry).await,
+            SpecSource::Stdin => OpenApiContext::from_reader(tokio::io::stdin(), max_bytes).await,
+            SpecSource::Inline(content) => self.parse(content),
+        }
+    }
+
+    fn parse(&self, content: &str) -> crate::Result<OpenApiContext> {
+        OpenApiContext::parse_content(content).map_err(|e| {
+            Error::openapi(format!("Failed to parse OpenAPI spec from {}: {}", self, e))
+        })
+    }
+}
+
+impl FromStr for SpecSource {
+    type Err = Error;
+
+    /// Parse a command line location; never yields [`SpecSource::Inline`]
+    fn from_str(location: &str) -> crate::Result<Self> {
+        if location.is_empty() {
+            return Err(Error::config("Spec location must not be empty"));
+        }
+        Ok(if location == "-" {
+            SpecSource::Stdin
+        } else if location.starts_with("http://") || location.starts_with("https://") {
+            SpecSource::Url(location.to_string())
+        } else if let Some((archive, entry)) = location.split_once(".zip#") {
+            SpecSource::Archive {
+                path: PathBuf::from(format!("{}.zip", archive)),
+                entry: entry.to_string(),
+            }
+        } else {
+            SpecSource::File(PathBuf::from(location))
+        })
+    }
+}
+
+impl fmt::Display for SpecSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SpecSource::File(path) => write!(f, "{}", path.display()),
+            SpecSource::Url(url) => f.write_str(url),
+            SpecSource::Archive { path, entry } => write!(f, "{}#{}", path.display(), entry),
+            SpecSource::Stdin => f.write_str("stdin"),
+            SpecSource::Inline(_) => f.write_str("inline spec"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    const SPEC: &str = "openapi: 3.0.0\ninfo:\n  title: Inline\n  version: 1.0.0\npaths: {}\n";
+
+    #[test]
+    fn test_from_str() -> crate::Result<()> {
+        assert_eq!("-".parse::<SpecSource>()?, SpecSource::Stdin);
+        assert_eq!(
+            "https://example.com/openapi.json".parse::<SpecSource>()?,
+            SpecSource::Url("https://example.com/openapi.json".to_string())
+        );
+        assert_eq!(
+            "specs/openapi.yaml".parse::<SpecSource>()?,
+            SpecSource::File(PathBuf::from("specs/openapi.yaml"))
+        );
+        assert_eq!(
+            "specs/bundle.zip#api/openapi.yaml".parse::<SpecSource>()?,
+            SpecSource::Archive {
+                path: PathBuf::from("specs/bundle.zip"),
+                entry: "api/openapi.yaml".to_string()
+            }
+        );
+        assert!("".parse::<SpecSource>().is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_load_file() -> crate::Result<()> {
+        let manifest = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        let path = manifest
+            .join("../../tests/fixtures/openapi/petstore.openapi.v3.json")
+            .to_string_lossy()
+            .to_string();
+        let spec = path.parse::<SpecSource>()?.load().await?;
+        assert!(spec.title().is_some());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_load_archive() -> crate::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let archive = dir.path().join("bundle.zip");
+        let mut zip = zip::ZipWriter::new(std::fs::File::create(&archive)?);
+        let entries = [
+            (
+                "api/openapi.yaml",
+                "openapi: 3.0.0\ninfo:\n  title: Zipped\n  version: 1.0.0\nservers:\n  - url: https://api.example.com\npaths:\n  /pets:\n    get:\n      operationId: listPets\n      tags: [pets]\n      responses:\n        '200':\n          description: ok\n          content:\n            application/json:\n              schema:\n                $ref: '../schemas/pet.yaml#/Pet'\n",
+            ),
+            (
+                "schemas/pet.yaml",
+                "Pet:\n  type: object\n  properties:\n    name:\n      type: string\n",
+            ),
+        ];
+        for (name, content) in entries {
+            zip.start_file(name, zip::write::SimpleFileOptions::default())
+                .map_err(|e| crate::Error::openapi(e.to_string()))?;
+            zip.write_all(content.as_bytes())?;
+        }
+        zip.finish()
+            .map_err(|e| crate::Error::openapi(e.to_string()))?;
+
+        let location = format!("{}#api/openapi.yaml", archive.display());
+        let spec = location.parse::<SpecSource>()?.load().await?;
+        assert_eq!(spec.title(), Some("Zipped"));
+        let schema = &spec.json["paths"]["/pets"]["get"]["responses"]["200"]["content"]
+            ["application/json"]["schema"];
+        assert_eq!(schema["properties"]["name"]["type"], "string");
+
+        let templates = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../../templates");
+        let out = dir.path().join("out");
+        let mut config = crate::Config::new("zipped", location, out.to_string_lossy());
+        config.template_dir = Some(templates.to_string_lossy().to_string());
+        crate::generate(&config, None).await?;
+        assert!(out.join("src/handlers/list_pets.rs").is_file());
+
+        let missing = format!("{}#api/missing.yaml", archive.display());
+        assert!(missing.parse::<SpecSource>()?.load().await.is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_load_url() -> crate::Result<()> {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0")?;
+        let url = format!("http://{}/openapi.yaml", listener.local_addr()?);
+        let server = std::thread::spawn(move || -> std::io::Result<()> {
+            let (mut stream, _) = listener.accept()?;
+            let mut request = [0u8; 1024];
+            let _ = std::io::Read::read(&mut stream, &mut request)?;
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                SPEC.len(),
+                SPEC
+            )
+        });
+
+        let spec = url.parse::<SpecSource>()?.load().await?;
+        server.join().unwrap()?;
+        assert_eq!(spec.title(), Some("Inline"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_load_url_aborts_past_size_limit() -> crate::Result<()> {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0")?;
+        let url = format!("http://{}/openapi.yaml", listener.local_addr()?);
+        // Streams far past the limit without announcing a length
+        let server = std::thread::spawn(move || -> std::io::Result<()> {
+            let (mut stream, _) = listener.accept()?;
+            let mut request = [0u8; 1024];
+            let _ = std::io::Read::read(&mut stream, &mut request)?;
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\nConnection: close\r\n\r\n"
+            )?;
+            let chunk = "x".repeat(4096);
+            for _ in 0..1024 {
+                if write!(stream, "{:x}\r\n{}\r\n", chunk.len(), chunk).is_err() {
+                    // The client hung up once the limit was hit
+                    return Ok(());
+                }
+            }
+            write!(stream, "0\r\n\r\n")
+        });
+
+        let err = url
+            .parse::<SpecSource>()?
+            .load_with_limit(64 * 1024)
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "OpenAPI error: OpenAPI spec at {} is larger than the 65536 byte limit",
+                url
+            )
+        );
+        // Joined off the runtime so it can close the abandoned connection
+        tokio::task::spawn_blocking(move || server.join())
+            .await
+            .unwrap()
+            .unwrap()?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_load_inline() -> crate::Result<()> {
+        let spec = SpecSource::Inline(SPEC.to_string()).load().await?;
+        assert_eq!(spec.title(), Some("Inline"));
+
+        let err = SpecSource::Inline("[unclosed".to_string())
+            .load()
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("inline spec"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_stdin_display() {
+        assert_eq!(SpecSource::Stdin.to_string(), "stdin");
+    }
+}
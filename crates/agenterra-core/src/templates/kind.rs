@@ -77,6 +77,17 @@ impl TemplateKind {
         }
     }
 
+    /// Language the kind's endpoint context builder targets, as written in
+    /// template manifests (`None` for custom templates, which may target any)
+    pub fn language(&self) -> Option<&'static str> {
+        match self {
+            Self::RustAxum => Some("rust"),
+            Self::PythonFastAPI => Some("python"),
+            Self::TypeScriptExpress => Some("typescript"),
+            Self::Custom => None,
+        }
+    }
+
     /// Returns an iterator over all available template kinds
     pub fn all() -> impl Iterator<Item = Self> {
         use TemplateKind::*;
@@ -2,37 +2,64 @@
 
 // Internal imports (std, crate)
 use std::{
+    collections::BTreeMap,
     io,
     path::{Path, PathBuf},
-    sync::Arc,
+    sync::{Arc, RwLock},
+    time::SystemTime,
 };
-use tokio::task;
+use tokio::{sync::Semaphore, task};
 
 use crate::{
-    builders::EndpointContext,
+    builders::{EndpointContext, UnmappedType},
     config::Config,
     error::Result,
-    manifest::TemplateManifest,
+    manifest::{GenerationManifest, TemplateManifest},
     openapi::{OpenApiContext, OpenApiOperation},
-    utils::to_snake_case,
+    report::{GenerationReport, Warning, WarningKind},
+    utils::{is_valid_package_name, to_snake_case},
 };
 
-use super::{TemplateDir, TemplateKind, TemplateOptions};
+use super::{FilenameCase, Layout, TemplateDir, TemplateKind, TemplateOptions};
 
 // External imports (alphabetized)
 use serde::Serialize;
 use serde_json::{json, Map, Value as JsonValue};
 use tera::{Context, Tera};
 
+/// Nested `$ref` expansions allowed per path when writing schema files
+pub const DEFAULT_MAX_DEREF_DEPTH: usize = 20;
+
+/// Schema file of each operation under `schemas/`, see
+/// [`TemplateOptions::schema_filename_pattern`]
+pub const DEFAULT_SCHEMA_FILENAME_PATTERN: &str = "{operation}.json";
+
+/// Tag that `for_each: tag` files use for operations without tags
+pub const UNTAGGED: &str = "default";
+
+/// Package name used when neither the spec title nor the config yields a valid one
+const DEFAULT_PROJECT_NAME: &str = "agenterra_mcp_server";
+
+/// Compiled templates and their manifest, tagged with the template directory
+/// state they were loaded from
+#[derive(Debug)]
+struct CompiledTemplates {
+    tera: Arc<Tera>,
+    manifest: Arc<TemplateManifest>,
+    /// Newest modification time in the template directory at load time
+    newest_mtime: Option<SystemTime>,
+}
+
 /// Manages loading and rendering of code generation templates
 #[derive(Debug, Clone)]
 pub struct TemplateManager {
-    /// Cached Tera template engine instance
-    tera: Arc<Tera>,
+    /// Cached Tera template engine instance and manifest, reloaded when
+    /// template files change
+    tera: Arc<RwLock<CompiledTemplates>>,
     /// Template directory
     template_dir: TemplateDir,
-    /// The template manifest
-    manifest: TemplateManifest,
+    /// Problems with the template itself, found while loading it
+    warnings: Vec<Warning>,
 }
 
 impl TemplateManager {
@@ -47,12 +74,15 @@ impl TemplateManager {
     pub async fn new(template_kind: TemplateKind, template_dir: Option<PathBuf>) -> Result<Self> {
         // Convert PathBuf to TemplateDir
         let template_dir = if let Some(dir) = template_dir {
-            // Check if the directory already ends with the template kind
+            // Check if the directory already ends with the template kind; a custom
+            // template directory without a `custom` subdirectory is the template itself
             if dir
                 .file_name()
                 .and_then(|name| name.to_str())
                 .map(|name| name == template_kind.as_str())
                 .unwrap_or(false)
+                || (template_kind == TemplateKind::Custom
+                    && !dir.join(template_kind.as_str()).is_dir())
             {
                 // Directory already points to the specific template
                 TemplateDir::new(
@@ -78,58 +108,36 @@ impl TemplateManager {
             )
         })?;
 
-        // Load the template manifest - try YAML first, then TOML
-        let yaml_manifest_path = template_path.join("manifest.yaml");
-        let toml_manifest_path = template_path.join("manifest.toml");
-
-        let manifest = if yaml_manifest_path.exists() {
-            let manifest_content = tokio::fs::read_to_string(&yaml_manifest_path)
-                .await
-                .map_err(|e| {
-                    io::Error::new(
-                        io::ErrorKind::InvalidData,
-                        format!("Failed to read template manifest: {}", e),
-                    )
-                })?;
-            serde_yaml::from_str(&manifest_content).map_err(|e| {
-                io::Error::new(
-                    io::ErrorKind::InvalidData,
-                    format!("Failed to parse template manifest: {}", e),
-                )
-            })?
-        } else if toml_manifest_path.exists() {
-            let manifest_content = tokio::fs::read_to_string(&toml_manifest_path)
-                .await
-                .map_err(|e| {
-                    io::Error::new(
-                        io::ErrorKind::InvalidData,
-                        format!("Failed to read template manifest: {}", e),
-                    )
-                })?;
-            toml::from_str(&manifest_content).map_err(|e| {
-                io::Error::new(
-                    io::ErrorKind::InvalidData,
-                    format!("Failed to parse template manifest: {}", e),
-                )
-            })?
-        } else {
-            // Default empty manifest
-            TemplateManifest::default()
-        };
+        let manifest = load_manifest(template_path).await?;
 
         // Create Tera instance with the template directory
-        let tera = Tera::new(&format!("{}/**/*", template_dir_str)).map_err(|e| {
-            io::Error::new(
-                io::ErrorKind::InvalidData,
-                format!("Failed to parse templates: {}", e),
-            )
-        })?;
+        let newest_mtime = newest_mtime(template_path);
+        let tera = load_tera(template_dir_str)?;
+
+        // The glob above also matches non-template files (e.g. the manifest itself),
+        // so an empty or misnamed template set only surfaces later as one
+        // "Template not found" per file; catch it here instead.
+        if !manifest.files.is_empty() && !tera.get_template_names().any(|n| n.ends_with(".tera")) {
+            return Err(crate::error::Error::template(format!(
+                "Template directory {} appears empty or templates have the wrong extension: the manifest lists {} file(s) but no .tera templates were found",
+                template_path.display(),
+                manifest.files.len()
+            )));
+        }
+
+        let warnings = language_mismatch(&manifest, template_kind)
+            .into_iter()
+            .collect();
 
         // Create the TemplateManager
         let manager = TemplateManager {
-            tera: Arc::new(tera),
+            tera: Arc::new(RwLock::new(CompiledTemplates {
+                tera: Arc::new(tera),
+                manifest: Arc::new(manifest),
+                newest_mtime,
+            })),
             template_dir,
-            manifest,
+            warnings,
         };
 
         Ok(manager)
@@ -140,6 +148,12 @@ impl TemplateManager {
         self.template_dir.kind()
     }
 
+    /// Problems found while loading the template, such as a manifest
+    /// `language` that doesn't match the template kind
+    pub fn warnings(&self) -> &[Warning] {
+        &self.warnings
+    }
+
     /// Get the template directory
     pub fn template_dir(&self) -> &TemplateDir {
         &self.template_dir
@@ -150,16 +164,69 @@ impl TemplateManager {
         self.template_dir.template_path()
     }
 
-    /// Get a reference to the Tera template engine
-    pub fn tera(&self) -> &Tera {
-        &self.tera
+    /// Get the current Tera template engine.
+    ///
+    /// Templates are reloaded when their files change, so this returns a
+    /// snapshot of the engine rather than a reference tied to `self`.
+    pub fn tera(&self) -> Arc<Tera> {
+        self.tera
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .tera
+            .clone()
     }
 
-    /// Reload all templates from the template directory.
-    /// This is a no-op in the cached implementation since templates are loaded on demand.
+    /// Reload all templates and the manifest from the template directory
     pub async fn reload_templates(&self) -> Result<()> {
-        // In the cached implementation, we don't need to do anything here
-        // since templates are loaded on demand.
+        let template_path = self.template_dir.template_path().to_path_buf();
+        let manifest = load_manifest(&template_path).await?;
+        let (tera, newest_mtime) = task::spawn_blocking(move || {
+            let newest_mtime = newest_mtime(&template_path);
+            load_tera(&template_path.to_string_lossy()).map(|tera| (tera, newest_mtime))
+        })
+        .await
+        .map_err(|e| io::Error::other(format!("Failed to reload templates: {}", e)))??;
+
+        *self.tera.write().unwrap_or_else(|e| e.into_inner()) = CompiledTemplates {
+            tera: Arc::new(tera),
+            manifest: Arc::new(manifest),
+            newest_mtime,
+        };
+        Ok(())
+    }
+
+    /// A copy of this manager whose templates can call the functions reading
+    /// `spec` (see [`register_spec_functions`]). The shared templates are left
+    /// untouched, so concurrent generations don't see each other's spec.
+    fn with_spec_functions(&self, spec: &OpenApiContext) -> TemplateManager {
+        let compiled = self.tera.read().unwrap_or_else(|e| e.into_inner());
+        let mut tera = (*compiled.tera).clone();
+        register_spec_functions(&mut tera, spec);
+        TemplateManager {
+            tera: Arc::new(RwLock::new(CompiledTemplates {
+                tera: Arc::new(tera),
+                manifest: compiled.manifest.clone(),
+                newest_mtime: compiled.newest_mtime,
+            })),
+            ..self.clone()
+        }
+    }
+
+    /// Reload the templates if any file in the template directory changed since they were loaded
+    async fn reload_if_changed(&self) -> Result<()> {
+        let template_path = self.template_dir.template_path().to_path_buf();
+        let current = task::spawn_blocking(move || newest_mtime(&template_path))
+            .await
+            .map_err(|e| io::Error::other(format!("Failed to scan templates: {}", e)))?;
+        let loaded = self
+            .tera
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .newest_mtime;
+        if current != loaded {
+            log::debug!("Template files changed, reloading templates");
+            self.reload_templates().await?;
+        }
         Ok(())
     }
 
@@ -211,9 +278,14 @@ impl TemplateManager {
             .await
     }
 
-    /// Get a reference to the template manifest
-    pub fn manifest(&self) -> &TemplateManifest {
-        &self.manifest
+    /// Get the current template manifest, reloaded along with the templates
+    /// (see [`tera`](Self::tera))
+    pub fn manifest(&self) -> Arc<TemplateManifest> {
+        self.tera
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .manifest
+            .clone()
     }
 
     /// Generate a file from a template with a custom context
@@ -261,18 +333,18 @@ impl TemplateManager {
 
         // Verify template exists
         log::debug!("Checking if template exists: {}", template_name);
-        self.tera.get_template(template_name).map_err(|e| {
+        self.tera().get_template(template_name).map_err(|e| {
             crate::error::Error::template(format!("Template not found: {} - {}", template_name, e))
         })?;
 
         log::debug!("Found template: {}", template_name);
         log::debug!(
             "Available templates: {:?}",
-            self.tera.get_template_names().collect::<Vec<_>>()
+            self.tera().get_template_names().collect::<Vec<_>>()
         );
 
         // Render the template with detailed error reporting
-        let content = match self.tera.render(template_name, &tera_context) {
+        let content = match self.tera().render(template_name, &tera_context) {
             Ok(content) => content,
             Err(e) => {
                 // Get the template source for better error reporting
@@ -315,21 +387,23 @@ impl TemplateManager {
 
         // Write the output file
         log::debug!("Writing to output file: {}", output_path.display());
-        tokio::fs::write(&output_path, &content).await?;
+        write_atomically(output_path, content.as_bytes()).await?;
 
         log::debug!("Successfully wrote template to: {}", output_path.display());
         Ok(())
     }
 
-    /// List all available templates
     /// Check if a template exists
     pub fn has_template(&self, name: &str) -> bool {
-        self.tera.get_template(name).is_ok()
+        self.tera().get_template(name).is_ok()
     }
 
-    /// List all available templates
+    /// List the generatable templates as `(source, destination)` pairs.
+    ///
+    /// Only files declared in the manifest are included; helper templates such
+    /// as macros and includes are reported by [`partials`](Self::partials).
     pub fn list_templates(&self) -> Vec<(String, String)> {
-        self.manifest
+        self.manifest()
             .files
             .iter()
             .filter(|f| self.has_template(&f.source))
@@ -337,67 +411,480 @@ impl TemplateManager {
             .collect()
     }
 
+    /// Names of the generatable templates, in manifest order.
+    ///
+    /// Unlike Tera's own listing this only reflects the manifest: a source
+    /// rendered to several destinations is named once, and partials are left
+    /// to [`partials`](Self::partials).
+    pub fn template_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = Vec::new();
+        for (source, _) in self.list_templates() {
+            if !names.contains(&source) {
+                names.push(source);
+            }
+        }
+        names
+    }
+
+    /// List `.tera` templates that aren't declared in the manifest (macros,
+    /// includes and other partials), sorted by name
+    pub fn partials(&self) -> Vec<String> {
+        let manifest = self.manifest();
+        let mut partials: Vec<String> = self
+            .tera()
+            .get_template_names()
+            .filter(|name| name.ends_with(".tera"))
+            .filter(|name| !manifest.files.iter().any(|f| f.source == *name))
+            .map(String::from)
+            .collect();
+        partials.sort();
+        partials
+    }
+
     /// Generate code from loaded templates based on the OpenAPI spec and options
     pub async fn generate(
         &self,
         spec: &OpenApiContext,
         config: &Config,
         template_opts: Option<TemplateOptions>,
-    ) -> Result<()> {
+    ) -> Result<GenerationReport> {
+        self.reload_if_changed().await?;
+        let preprocessed = preprocess_spec(spec, &template_opts)?;
+        let spec = preprocessed.as_ref().unwrap_or(spec);
+
+        let output_dir = Path::new(&config.output_dir);
+        let previous = GenerationManifest::load(output_dir).await?;
+        let inputs_hash = self.inputs_hash(spec, config, &template_opts).await?;
+        let force = template_opts.as_ref().is_some_and(|opts| opts.force);
+        if !force
+            && previous
+                .as_ref()
+                .is_some_and(|manifest| manifest.inputs_hash.as_ref() == Some(&inputs_hash))
+        {
+            log::info!(
+                "No changes since the last generation into {}, skipping",
+                output_dir.display()
+            );
+            return Ok(GenerationReport {
+                skipped: true,
+                ..Default::default()
+            });
+        }
+        let this = self.with_spec_functions(spec);
+
         // Build the base context
         let (base_context, operations) = self.build_context(spec, &template_opts, config).await?;
+        let mut report = GenerationReport {
+            warnings: self.warnings.clone(),
+            skipped: false,
+        };
+        let id_strategy = template_opts
+            .as_ref()
+            .map(|opts| opts.operation_id_strategy)
+            .unwrap_or_default();
+        report.warnings.extend(spec.warnings_with(id_strategy));
+        report.warnings.extend(
+            self.unmapped_types(&operations, &template_opts)?
+                .into_iter()
+                .map(Warning::from),
+        );
 
         // Create output directory
-        let output_dir = Path::new(&config.output_dir);
         tokio::fs::create_dir_all(output_dir).await?;
+        let first_run = previous.is_none();
 
         // Process each template file
-        for file in &self.manifest.files {
-            log::debug!("Processing file: {} -> {}", file.source, file.destination);
-            if let Some(for_each) = &file.for_each {
-                log::debug!("File has for_each: {}", for_each);
-                match for_each.as_str() {
-                    "endpoint" | "operation" => {
-                        // Convert base_context to Tera Context for operation processing
-                        let mut tera_context = Context::new();
-                        if let serde_json::Value::Object(obj) = &base_context {
-                            for (k, v) in obj {
-                                tera_context.insert(k, v);
-                            }
-                        }
-
-                        self.process_operation_file(
-                            file,
-                            &tera_context,
-                            output_dir,
-                            &operations,
-                            &template_opts,
-                            spec,
-                        )
-                        .await?;
-                    }
-                    _ => {
-                        return Err(crate::error::Error::template(format!(
-                            "Unknown for_each directive: {}",
-                            for_each
-                        )));
-                    }
-                }
-            } else {
-                // This is a single file template
-                log::debug!("Processing single file template: {}", file.source);
-                let dest_path = output_dir.join(&file.destination);
-                self.process_single_file(file, &base_context, &dest_path)
-                    .await?;
+        let mut output = Output::Disk(BTreeMap::new());
+        for file in &this.manifest().files {
+            if !is_enabled(file, &base_context) {
+                log::debug!("Skipping disabled file: {}", file.source);
+                continue;
             }
+            this.process_file(
+                file,
+                &base_context,
+                output_dir,
+                &operations,
+                &template_opts,
+                spec,
+                &mut output,
+            )
+            .await?;
+        }
+
+        if template_opts
+            .as_ref()
+            .is_some_and(|opts| opts.emit_mcp_descriptor)
+        {
+            let descriptor =
+                mcp_descriptor(spec, &operations, &base_context, &template_opts, config)?;
+            // Next to the server's Cargo.toml, in its crate with the workspace layout
+            let server_dir = crate_dir(
+                Some("{project_name}"),
+                output_dir,
+                &base_context,
+                &template_opts,
+            );
+            let path = server_dir
+                .as_deref()
+                .unwrap_or(output_dir)
+                .join(MCP_DESCRIPTOR_FILE);
+            output
+                .write_rendered(
+                    &template_opts,
+                    &path,
+                    serde_json::to_string_pretty(&descriptor)?,
+                    false,
+                )
+                .await?;
         }
+        write_operation_fragments(spec, &operations, &template_opts, output_dir).await?;
 
         // Execute post-generation hooks
-        self.execute_post_generation_hooks(output_dir).await?;
+        this.execute_post_generation_hooks(output_dir).await?;
+        if first_run {
+            run_hooks(&this.manifest().hooks.post_generate_once, output_dir).await?;
+        }
+        if template_opts.as_ref().is_some_and(|opts| opts.lint_output)
+            && this.manifest().language.eq_ignore_ascii_case("rust")
+        {
+            run_clippy(output_dir).await?;
+        }
+        let Output::Disk(generated) = output else {
+            unreachable!("generate writes to disk")
+        };
+        GenerationManifest::new(&this.manifest())
+            .with_inputs(config, &template_opts)
+            .with_inputs_hash(inputs_hash)
+            .with_files(output_dir, generated)
+            .save(output_dir)
+            .await?;
+
+        Ok(report)
+    }
+
+    /// Hash of everything a generation's output depends on: the spec, the
+    /// template (its version and file contents, so edits without a version
+    /// bump count), the generator version, the config and the template options
+    /// (except `force`, which only decides whether the hash is checked)
+    async fn inputs_hash(
+        &self,
+        spec: &OpenApiContext,
+        config: &Config,
+        template_opts: &Option<TemplateOptions>,
+    ) -> Result<String> {
+        let template_path = self.template_dir.template_path().to_path_buf();
+        let template_hash = task::spawn_blocking(move || {
+            hash_dir(FNV_OFFSET_BASIS, &template_path, &template_path)
+        })
+        .await
+        .map_err(|e| io::Error::other(format!("Failed to hash templates: {}", e)))??;
+        let template_opts = TemplateOptions {
+            force: false,
+            ..template_opts.clone().unwrap_or_default()
+        };
+        let inputs = serde_json::to_vec(&json!({
+            "spec": spec.json,
+            "template": self.manifest().name,
+            "template_version": self.manifest().version,
+            "generator_version": env!("CARGO_PKG_VERSION"),
+            "config": config,
+            "template_options": template_opts,
+        }))?;
+        Ok(format!("{:016x}", fnv1a_64(template_hash, &inputs)))
+    }
+
+    /// Regenerate only the per-operation files for the given operation IDs.
+    ///
+    /// Single-file templates and `for_each: all` files, which list every
+    /// operation, are rendered again too, while post-generation hooks are
+    /// skipped. This is only suitable when the rest of the spec is unchanged
+    /// (see [`OpenApiContext::changed_operations`]).
+    pub async fn generate_operations(
+        &self,
+        spec: &OpenApiContext,
+        config: &Config,
+        template_opts: Option<TemplateOptions>,
+        operation_ids: &[String],
+    ) -> Result<()> {
+        self.reload_if_changed().await?;
+        let preprocessed = preprocess_spec(spec, &template_opts)?;
+        let spec = preprocessed.as_ref().unwrap_or(spec);
+        let (base_context, all_operations) =
+            self.build_context(spec, &template_opts, config).await?;
+        let operations: Vec<OpenApiOperation> = all_operations
+            .iter()
+            .filter(|op| operation_ids.contains(&op.id))
+            .cloned()
+            .collect();
+
+        let output_dir = Path::new(&config.output_dir);
+        tokio::fs::create_dir_all(output_dir).await?;
+
+        let this = self.with_spec_functions(spec);
+        let mut output = Output::Disk(BTreeMap::new());
+        for file in this
+            .manifest()
+            .files
+            .iter()
+            .filter(|f| is_enabled(f, &base_context))
+        {
+            let operations = match file.for_each.as_deref() {
+                Some(for_each) if for_each != "all" => &operations,
+                _ => &all_operations,
+            };
+            this.process_file(
+                file,
+                &base_context,
+                output_dir,
+                operations,
+                &template_opts,
+                spec,
+                &mut output,
+            )
+            .await?;
+        }
+        write_operation_fragments(spec, &operations, &template_opts, output_dir).await?;
+
+        // Post-generation hooks didn't run for this spec, so the next full
+        // generation must not be skipped as up to date
+        if let Some(mut manifest) = GenerationManifest::load(output_dir).await? {
+            let Output::Disk(generated) = output else {
+                unreachable!("generate_operations writes to disk")
+            };
+            manifest.inputs_hash = None;
+            manifest
+                .with_files(output_dir, generated)
+                .save(output_dir)
+                .await?;
+        }
 
         Ok(())
     }
 
+    /// Render a single manifest file (by its `source`) without writing anything.
+    ///
+    /// `for_each` files expand to one entry per operation or tag, while the
+    /// per-operation schema files are not produced. Returns each output path,
+    /// relative to the output directory, with its rendered content.
+    pub async fn render_one(
+        &self,
+        source: &str,
+        spec: &OpenApiContext,
+        config: &Config,
+        template_opts: Option<TemplateOptions>,
+    ) -> Result<Vec<(PathBuf, String)>> {
+        self.reload_if_changed().await?;
+        let manifest = self.manifest();
+        let file = manifest
+            .files
+            .iter()
+            .find(|f| f.source == source)
+            .ok_or_else(|| {
+                crate::Error::template(format!("No file with source '{}' in the manifest", source))
+            })?;
+        let preprocessed = preprocess_spec(spec, &template_opts)?;
+        let spec = preprocessed.as_ref().unwrap_or(spec);
+        let (base_context, operations) = self.build_context(spec, &template_opts, config).await?;
+
+        let output_dir = Path::new(&config.output_dir);
+        let mut output = Output::Memory(Vec::new());
+        self.with_spec_functions(spec)
+            .process_file(
+                file,
+                &base_context,
+                output_dir,
+                &operations,
+                &template_opts,
+                spec,
+                &mut output,
+            )
+            .await?;
+
+        let Output::Memory(files) = output else {
+            unreachable!("render_one collects output in memory")
+        };
+        Ok(files
+            .into_iter()
+            .map(|(path, content)| {
+                let relative = path.strip_prefix(output_dir).unwrap_or(&path).to_path_buf();
+                (relative, content)
+            })
+            .collect())
+    }
+
+    /// Render one manifest file, expanding its `for_each` directive if set
+    #[allow(clippy::too_many_arguments)]
+    async fn process_file(
+        &self,
+        file: &crate::manifest::TemplateFile,
+        base_context: &serde_json::Value,
+        output_dir: &Path,
+        operations: &[OpenApiOperation],
+        template_opts: &Option<TemplateOptions>,
+        spec: &OpenApiContext,
+        output: &mut Output,
+    ) -> Result<()> {
+        log::debug!("Processing file: {} -> {}", file.source, file.destination);
+        let crate_dir = crate_dir(
+            file.crate_name.as_deref(),
+            output_dir,
+            base_context,
+            template_opts,
+        );
+        let output_dir = crate_dir.as_deref().unwrap_or(output_dir);
+        if let Some(for_each) = &file.for_each {
+            self.process_for_each_file(
+                file,
+                for_each,
+                base_context,
+                output_dir,
+                operations,
+                template_opts,
+                spec,
+                output,
+            )
+            .await
+        } else {
+            // This is a single file template
+            log::debug!("Processing single file template: {}", file.source);
+            let dest_path = output_dir.join(&file.destination);
+            self.process_single_file(file, base_context, &dest_path, template_opts, output)
+                .await
+        }
+    }
+
+    /// The context a `for_each: endpoint` template receives for one operation,
+    /// as JSON and without rendering anything; useful for debugging templates
+    pub async fn operation_context(
+        &self,
+        spec: &OpenApiContext,
+        operation_id: &str,
+        config: &Config,
+        template_opts: Option<TemplateOptions>,
+    ) -> Result<JsonValue> {
+        let preprocessed = preprocess_spec(spec, &template_opts)?;
+        let spec = preprocessed.as_ref().unwrap_or(spec);
+        let (base_context, operations) = self.build_context(spec, &template_opts, config).await?;
+        let operation = operations
+            .iter()
+            .find(|op| op.id == operation_id)
+            .ok_or_else(|| {
+                crate::Error::template(format!(
+                    "No operation with ID '{}' in the spec",
+                    operation_id
+                ))
+            })?;
+        let base_context = Context::from_value(base_context)?;
+        let context =
+            self.operation_template_context(&base_context, operation, &template_opts, spec)?;
+        Ok(context.into_json())
+    }
+
+    /// Every selected operation field the builder can't map to a precise type
+    fn unmapped_types(
+        &self,
+        operations: &[OpenApiOperation],
+        template_opts: &Option<TemplateOptions>,
+    ) -> Result<Vec<UnmappedType>> {
+        let builder =
+            EndpointContext::get_builder_with(self.template_kind(), template_opts.as_ref())?;
+        let mut unmapped = Vec::new();
+        for operation in operations {
+            if is_selected(operation, template_opts)? {
+                unmapped.extend(builder.unmapped_types(operation));
+            }
+        }
+        Ok(unmapped)
+    }
+
+    /// Fail with every selected operation field the builder can't map to a precise type
+    fn check_type_mappings(
+        &self,
+        operations: &[OpenApiOperation],
+        template_opts: &Option<TemplateOptions>,
+    ) -> Result<()> {
+        let unmapped = self.unmapped_types(operations, template_opts)?;
+        if unmapped.is_empty() {
+            return Ok(());
+        }
+        Err(crate::Error::template(format!(
+            "{} schema(s) have no precise type mapping:\n{}",
+            unmapped.len(),
+            unmapped
+                .iter()
+                .map(|u| format!("  - {}", u))
+                .collect::<Vec<_>>()
+                .join("\n")
+        )))
+    }
+
+    /// Dispatch a manifest file with a `for_each` directive
+    #[allow(clippy::too_many_arguments)]
+    async fn process_for_each_file(
+        &self,
+        file: &crate::manifest::TemplateFile,
+        for_each: &str,
+        base_context: &serde_json::Value,
+        output_dir: &Path,
+        operations: &[OpenApiOperation],
+        template_opts: &Option<TemplateOptions>,
+        spec: &OpenApiContext,
+        output: &mut Output,
+    ) -> Result<()> {
+        log::debug!("File has for_each: {}", for_each);
+        match for_each {
+            "endpoint" | "operation" => {
+                // Convert base_context to Tera Context for operation processing
+                let mut tera_context = Context::new();
+                if let serde_json::Value::Object(obj) = base_context {
+                    for (k, v) in obj {
+                        tera_context.insert(k, v);
+                    }
+                }
+
+                self.process_operation_file(
+                    file,
+                    &tera_context,
+                    output_dir,
+                    operations,
+                    template_opts,
+                    spec,
+                    output,
+                )
+                .await
+            }
+            "tag" => {
+                self.process_tag_file(
+                    file,
+                    base_context,
+                    output_dir,
+                    operations,
+                    template_opts,
+                    spec,
+                    output,
+                )
+                .await
+            }
+            "all" => {
+                self.process_all_file(
+                    file,
+                    base_context,
+                    output_dir,
+                    operations,
+                    template_opts,
+                    spec,
+                    output,
+                )
+                .await
+            }
+            _ => Err(crate::error::Error::template(format!(
+                "Unknown for_each directive: {}",
+                for_each
+            ))),
+        }
+    }
+
     /// Build the complete template context from OpenAPI spec
     async fn build_context(
         &self,
@@ -405,21 +892,44 @@ impl TemplateManager {
         template_opts: &Option<TemplateOptions>,
         config: &crate::Config,
     ) -> Result<(serde_json::Value, Vec<OpenApiOperation>)> {
+        if template_opts.as_ref().is_some_and(|opts| opts.strict) {
+            if let Some(warning) = self
+                .warnings
+                .iter()
+                .find(|w| w.kind == WarningKind::LanguageMismatch)
+            {
+                return Err(crate::Error::template(warning.to_string()));
+            }
+            openapi_context.validate()?;
+        }
+
         let mut base_map = serde_json::Map::new();
 
-        // Add project name from spec title
+        // Add project title from spec
         if let Some(title) = openapi_context
             .json
             .get("info")
             .and_then(|info| info.get("title"))
             .and_then(|t| t.as_str())
         {
-            // Sanitize the title to be a valid Rust package name
-            let sanitized_name = to_snake_case(title);
-            base_map.insert("project_name".to_string(), json!(sanitized_name));
             base_map.insert("project_title".to_string(), json!(title));
         }
 
+        // A configured project name takes precedence; otherwise derive one from
+        // the spec title, falling back to a default when neither is a valid package name
+        let spec_title = openapi_context
+            .json
+            .get("info")
+            .and_then(|info| info.get("title"))
+            .and_then(|t| t.as_str());
+        let project_name = [Some(config.project_name.as_str()), spec_title]
+            .into_iter()
+            .flatten()
+            .map(to_snake_case)
+            .find(|name| is_valid_package_name(name))
+            .unwrap_or_else(|| DEFAULT_PROJECT_NAME.to_string());
+        base_map.insert("project_name".to_string(), json!(project_name));
+
         // Add API version from spec
         if let Some(api_version) = openapi_context
             .json
@@ -441,6 +951,24 @@ impl TemplateManager {
             base_map.insert("agent_instructions".to_string(), json!(""));
         }
 
+        // Root tag metadata (name -> description) for module-level docs
+        let tag_descriptions: Map<String, JsonValue> = openapi_context
+            .json
+            .get("tags")
+            .and_then(JsonValue::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(|tag| {
+                let name = tag.get("name")?.as_str()?;
+                let description = tag.get("description")?.as_str()?;
+                Some((name.to_string(), json!(description)))
+            })
+            .collect();
+        base_map.insert(
+            "tag_descriptions".to_string(),
+            JsonValue::Object(tag_descriptions),
+        );
+
         // Add the full spec to the context if needed
         if let Ok(spec_value) = serde_json::to_value(openapi_context) {
             base_map.insert("spec".to_string(), spec_value);
@@ -454,15 +982,42 @@ impl TemplateManager {
         base_map.insert("spec_file_name".to_string(), json!(spec_file));
 
         // Extract operations from the OpenAPI spec
-        let operations = openapi_context.parse_operations().await?;
+        let id_strategy = template_opts
+            .as_ref()
+            .map(|opts| opts.operation_id_strategy)
+            .unwrap_or_default();
+        let operations = openapi_context.parse_operations_with(id_strategy).await?;
+        if template_opts
+            .as_ref()
+            .is_some_and(|opts| opts.fail_on_unmapped_type)
+        {
+            self.check_type_mappings(&operations, template_opts)?;
+        }
 
         // Transform endpoints using language-specific builder
-        let endpoints =
-            EndpointContext::transform_endpoints(self.template_kind(), operations.clone())?;
+        let mut endpoints = EndpointContext::transform_endpoints_with(
+            self.template_kind(),
+            operations.clone(),
+            openapi_context,
+            template_opts.as_ref(),
+        )?;
+        for endpoint in &mut endpoints {
+            apply_schema_file(endpoint, template_opts)?;
+        }
+        // Flat list for registration modules, in `endpoints` order
+        let handler_fn_names: Vec<&JsonValue> = endpoints
+            .iter()
+            .filter_map(|endpoint| endpoint.get("fn_name"))
+            .collect();
+        base_map.insert("handler_fn_names".to_string(), json!(handler_fn_names));
         base_map.insert("endpoints".to_string(), json!(endpoints));
 
-        // Add server configuration variables needed by templates
-        base_map.insert("log_file".to_string(), json!("agenterra"));
+        // Add server configuration variables needed by templates; the log file
+        // defaults to the one the generated server falls back to
+        base_map.insert(
+            "log_file".to_string(),
+            json!(format!("{}-mcp.log", project_name)),
+        );
         base_map.insert("server_port".to_string(), json!(8080));
 
         // Add any template options to the context if provided
@@ -475,9 +1030,50 @@ impl TemplateManager {
                 base_map.insert("log_file".to_string(), json!(log_file));
             }
         }
+        let with_dockerfile = template_opts
+            .as_ref()
+            .is_some_and(|opts| opts.with_dockerfile);
+        base_map.insert("with_dockerfile".to_string(), json!(with_dockerfile));
+        let with_env_example = template_opts
+            .as_ref()
+            .is_some_and(|opts| opts.with_env_example);
+        base_map.insert("with_env_example".to_string(), json!(with_env_example));
+        let layout = template_opts
+            .as_ref()
+            .map(|opts| opts.layout)
+            .unwrap_or_default();
+        base_map.insert("layout".to_string(), json!(layout.as_str()));
+        base_map.insert("workspace".to_string(), json!(layout == Layout::Workspace));
+        let include_tests = template_opts
+            .as_ref()
+            .is_some_and(|opts| opts.include_tests);
+        base_map.insert("include_tests".to_string(), json!(include_tests));
+        let generate_client = template_opts
+            .as_ref()
+            .is_some_and(|opts| opts.generate_client);
+        base_map.insert("generate_client".to_string(), json!(generate_client));
+
+        // Handler return types, defaulting to the MCP tool result
+        let handler_error_type = template_opts
+            .as_ref()
+            .and_then(|opts| opts.handler_error_type.clone())
+            .unwrap_or_else(|| "rmcp::Error".to_string());
+        let handler_result_type = template_opts
+            .as_ref()
+            .and_then(|opts| opts.handler_result_type.clone())
+            .unwrap_or_else(|| format!("Result<CallToolResult, {}>", handler_error_type));
+        base_map.insert("handler_error_type".to_string(), json!(handler_error_type));
+        base_map.insert(
+            "handler_result_type".to_string(),
+            json!(handler_result_type),
+        );
 
         // Add base API URL from OpenAPI spec and user-provided base URL
-        if let Some(spec_url) = openapi_context.base_path() {
+        let spec_url = match template_opts.as_ref().and_then(|opts| opts.server_index) {
+            Some(index) => Some(openapi_context.server_url(index)?),
+            None => openapi_context.base_path(),
+        };
+        if let Some(spec_url) = spec_url {
             let final_url = if spec_url.starts_with("http://") || spec_url.starts_with("https://") {
                 // Spec contains a fully qualified URL, use it directly
                 spec_url
@@ -519,6 +1115,8 @@ impl TemplateManager {
         file: &crate::manifest::TemplateFile,
         base_context: &serde_json::Value,
         output_path: &Path,
+        template_opts: &Option<TemplateOptions>,
+        output: &mut Output,
     ) -> Result<()> {
         log::debug!(
             "Processing single file: {} -> {}",
@@ -526,16 +1124,6 @@ impl TemplateManager {
             output_path.display()
         );
 
-        // Create the output directory if it doesn't exist
-        if let Some(parent) = output_path.parent() {
-            if !parent.exists() {
-                log::debug!("Creating parent directory: {}", parent.display());
-                tokio::fs::create_dir_all(parent).await.map_err(|e| {
-                    io::Error::other(format!("Failed to create output directory: {}", e))
-                })?;
-            }
-        }
-
         // Create the file context
         let file_context = self.create_file_context(base_context, file)?;
         log::debug!(
@@ -582,7 +1170,7 @@ impl TemplateManager {
         }
 
         // Render the template with detailed error handling
-        let rendered = match self.tera.render(&file.source, &tera_context) {
+        let rendered = match self.tera().render(&file.source, &tera_context) {
             Ok(content) => {
                 log::debug!("Successfully rendered template {}", file.source);
                 content
@@ -601,7 +1189,7 @@ impl TemplateManager {
                 );
 
                 // Check if template exists
-                if let Err(template_err) = self.tera.get_template(&file.source) {
+                if let Err(template_err) = self.tera().get_template(&file.source) {
                     log::error!("Template not found: {}", template_err);
                 }
 
@@ -617,17 +1205,21 @@ impl TemplateManager {
         };
 
         // Write the file
+        let rendered = Self::finalize_output(rendered, output_path, template_opts);
         log::debug!("Writing rendered content to: {}", output_path.display());
-        tokio::fs::write(output_path, rendered).await.map_err(|e| {
-            log::error!("Failed to write file {}: {}", output_path.display(), e);
-            crate::error::Error::Io(e)
-        })?;
+        output
+            .write_rendered(template_opts, output_path, rendered, false)
+            .await?;
 
         log::debug!("Successfully processed file: {}", output_path.display());
         Ok(())
     }
 
     /// Process a template file for each operation
+    ///
+    /// Operations are rendered concurrently, at most
+    /// [`TemplateOptions::max_concurrency`] at a time, and written in spec order.
+    #[allow(clippy::too_many_arguments)]
     async fn process_operation_file(
         &self,
         file: &crate::manifest::TemplateFile,
@@ -636,294 +1228,588 @@ impl TemplateManager {
         operations: &[OpenApiOperation],
         template_opts: &Option<TemplateOptions>,
         spec: &OpenApiContext,
+        output: &mut Output,
     ) -> Result<()> {
-        // Create schemas directory
-        let schemas_dir = output_path.join("schemas");
-        tokio::fs::create_dir_all(&schemas_dir)
-            .await
-            .map_err(|e| io::Error::other(format!("Failed to create schemas directory: {}", e)))?;
-
+        let mut selected = Vec::new();
         for operation in operations {
-            // Language-specific fields like fn_name must be injected by a builder; OpenApiOperation is language-agnostic.
-            let include = template_opts
-                .as_ref()
-                .map(|opts| {
-                    opts.all_operations
-                        || opts.include_operations.is_empty()
-                        || opts.include_operations.contains(&operation.id)
-                })
-                .unwrap_or(true);
-            let exclude = template_opts
-                .as_ref()
-                .map(|opts| opts.exclude_operations.contains(&operation.id))
-                .unwrap_or(false);
-
-            if include && !exclude {
-                let mut context = base_context.clone();
-
-                let builder = EndpointContext::get_builder(self.template_kind())?;
-                let endpoint_context = builder.build(operation)?;
-
-                // Merge the endpoint context into the template context
-                if let Some(obj) = endpoint_context.as_object() {
-                    for (key, value) in obj {
-                        context.insert(key, &value);
-                    }
-                }
+            if is_selected(operation, template_opts)? {
+                selected.push(operation);
+            }
+        }
 
-                // Add operation metadata
-                context.insert("operation_id", &operation.id);
-                context.insert("method", &operation.method);
-                context.insert("path", &operation.path);
-
-                // Insert OpenAPI-native fields
-                context.insert("operation_id", &operation.id);
-
-                // Sanitize and add text fields
-                let sanitized_summary = operation.summary.as_deref().map(|s| {
-                    s.chars()
-                        .filter(|c| c.is_ascii_alphanumeric() || c.is_whitespace())
-                        .collect::<String>()
-                        .trim()
-                        .to_string()
-                });
+        let with_schema = matches!(output, Output::Disk(_));
+        let keeps_refs = with_schema && !selected.is_empty() && keep_schema_refs(template_opts);
+        let components = keeps_refs.then(|| {
+            let components = spec.json.get("components").cloned();
+            let mut file = json!({ "components": components.unwrap_or_else(|| json!({})) });
+            if let Some(defs) = spec.json.get("$defs") {
+                file["$defs"] = defs.clone();
+            }
+            file
+        });
+        let semaphore = Semaphore::new(max_concurrency(template_opts));
+        let rendered = futures::future::try_join_all(selected.into_iter().map(|operation| {
+            let semaphore = &semaphore;
+            async move {
+                let _permit = semaphore
+                    .acquire()
+                    .await
+                    .map_err(|e| io::Error::other(e.to_string()))?;
+                self.render_operation(
+                    file,
+                    base_context,
+                    output_path,
+                    operation,
+                    template_opts,
+                    spec,
+                    with_schema,
+                )
+                .await
+            }
+        }))
+        .await?;
 
-                let sanitized_description = operation.description.as_deref().map(|s| {
-                    s.chars()
-                        .filter(|c| {
-                            c.is_ascii_alphanumeric() || c.is_whitespace() || *c == '.' || *c == ','
-                        })
-                        .collect::<String>()
-                        .trim()
-                        .to_string()
-                });
+        for (path, content) in rendered.into_iter().flatten() {
+            output
+                .write_rendered(template_opts, &path, content, true)
+                .await?;
+        }
 
-                context.insert("summary", &sanitized_summary);
-                context.insert("description", &sanitized_description);
-                context.insert("deprecated", &operation.deprecated);
+        // Kept refs like `components.json#/components/schemas/Pet` resolve against this file
+        if let Some(components) = components {
+            let path = output_path.join("schemas").join(COMPONENTS_SCHEMA_FILE);
+            let content = template_opts
+                .as_ref()
+                .map(|opts| opts.schema_json_style)
+                .unwrap_or_default()
+                .to_string(&components)?;
+            output
+                .write_rendered(template_opts, &path, content, true)
+                .await?;
+        }
+        Ok(())
+    }
 
-                // Add tags with proper sanitization
-                let sanitized_tags: Vec<String> = operation
-                    .tags
+    /// Render one operation's file (and its schema file when `with_schema` is set),
+    /// returning the `(path, content)` pairs to write
+    #[allow(clippy::too_many_arguments)]
+    async fn render_operation(
+        &self,
+        file: &crate::manifest::TemplateFile,
+        base_context: &Context,
+        output_path: &Path,
+        operation: &OpenApiOperation,
+        template_opts: &Option<TemplateOptions>,
+        spec: &OpenApiContext,
+        with_schema: bool,
+    ) -> Result<Vec<(PathBuf, String)>> {
+        let mut files = Vec::new();
+        // Language-specific fields like fn_name must be injected by a builder; OpenApiOperation is language-agnostic.
+        let context =
+            self.operation_template_context(base_context, operation, template_opts, spec)?;
+        let endpoint_fs = context
+            .get("endpoint_fs")
+            .and_then(JsonValue::as_str)
+            .unwrap_or(&operation.id)
+            .to_string();
+        let endpoint_fs = endpoint_fs.as_str();
+        let endpoint_name = context
+            .get("endpoint")
+            .and_then(JsonValue::as_str)
+            .unwrap_or(&operation.id)
+            .to_string();
+        let endpoint_name = endpoint_name.as_str();
+
+        log::debug!("Processing template for operation: {}", operation.id);
+
+        if with_schema {
+            // Generate schema file with proper schema extraction
+            // Path from the endpoint context, laid out by `schema_filename_pattern`
+            let schema_file = match context.get("schema_file").and_then(JsonValue::as_str) {
+                Some(schema_file) => schema_file.to_string(),
+                None => format!("{}.json", to_snake_case(&operation.id)),
+            };
+            let schema_path = output_path.join("schemas").join(&schema_file);
+            let mut schema_value = serde_json::to_value(operation)?;
+
+            if keep_schema_refs(template_opts) {
+                // Point local refs at the components file, relative to this one
+                let depth = Path::new(&schema_file)
+                    .components()
+                    .count()
+                    .saturating_sub(1);
+                let components = format!("{}{}", "../".repeat(depth), COMPONENTS_SCHEMA_FILE);
+                rebase_local_refs(&mut schema_value, &components);
+            } else {
+                // Dereference all $ref in the schema
+                let max_deref_depth = template_opts
                     .as_ref()
-                    .map(|tags| {
-                        tags.iter()
-                            .map(|t| t.trim().replace("\n", " ").replace("\r", " "))
-                            .collect()
-                    })
-                    .unwrap_or_default();
-                context.insert("tags", &sanitized_tags);
+                    .and_then(|opts| opts.max_deref_depth)
+                    .unwrap_or(DEFAULT_MAX_DEREF_DEPTH);
+                Self::dereference_schema_refs(&mut schema_value, spec, max_deref_depth)?;
+            }
 
-                // Extract and process parameters with proper error handling
-                let parameter_info: Vec<serde_json::Value> = operation
-                    .parameters
-                    .as_ref()
-                    .map(|params| {
-                        params
-                            .iter()
-                            .map(|p| {
-                                let mut param_obj = serde_json::Map::new();
-
-                                // Required fields
-                                param_obj.insert("name".to_string(), json!(&p.name));
-                                param_obj.insert("in".to_string(), json!(&p.in_));
-
-                                // Optional fields with their correct names
-                                if let Some(desc) = &p.description {
-                                    param_obj.insert("description".to_string(), json!(desc));
-                                }
+            // Remove null values from the schema
+            schema_value
+                .as_object_mut()
+                .unwrap()
+                .retain(|_, v| v != &json!(null));
 
-                                // Handle required field with path parameter default
-                                let is_required = p.required.unwrap_or_else(|| p.in_ == "path");
-                                param_obj.insert("required".to_string(), json!(is_required));
+            if template_opts
+                .as_ref()
+                .is_some_and(|opts| opts.strip_vendor_extensions)
+            {
+                prune_schema_keys(&mut schema_value, &|key| key.starts_with("x-"));
+            }
+            if template_opts
+                .as_ref()
+                .is_some_and(|opts| opts.minify_schemas)
+            {
+                prune_schema_keys(&mut schema_value, &|key| DOCUMENTATION_KEYS.contains(&key));
+            }
 
-                                // Add schema if available
-                                if let Some(schema) = &p.schema {
-                                    param_obj.insert("schema".to_string(), schema.clone());
-                                }
+            let schema_json = template_opts
+                .as_ref()
+                .map(|opts| opts.schema_json_style)
+                .unwrap_or_default()
+                .to_string(&schema_value)?;
+            files.push((schema_path, schema_json));
+        }
 
-                                // Add content if available (for complex parameters)
-                                if let Some(content) = &p.content {
-                                    param_obj.insert("content".to_string(), json!(content));
-                                }
+        // Generate the output path with sanitized operation_id
+        let output_file = file
+            .destination
+            .replace("{{operation_id}}", endpoint_fs)
+            .replace("{operation_id}", endpoint_fs)
+            .replace("{{endpoint}}", endpoint_name)
+            .replace("{endpoint}", endpoint_name);
+        let output_path = output_path.join(&output_file);
+
+        // Render the template off the async runtime so operations render in parallel
+        let tera = self.tera();
+        let source = file.source.clone();
+        let rendered = task::spawn_blocking(move || tera.render(&source, &context))
+            .await
+            .map_err(|e| {
+                io::Error::other(format!("Failed to render template {}: {}", file.source, e))
+            })?
+            .map_err(|e| {
+                io::Error::other(format!("Failed to render template {}: {}", file.source, e))
+            })?;
 
-                                // Add examples if available
-                                if let Some(examples) = &p.examples {
-                                    param_obj.insert("examples".to_string(), json!(examples));
-                                }
+        let rendered = Self::finalize_output(rendered, &output_path, template_opts);
+        files.push((output_path, rendered));
+        Ok(files)
+    }
 
-                                // Add other optional fields
-                                if let Some(deprecated) = p.deprecated {
-                                    param_obj.insert("deprecated".to_string(), json!(deprecated));
-                                }
+    /// Template context of one operation: the base context merged with the
+    /// endpoint builder's output and the operation's metadata
+    fn operation_template_context(
+        &self,
+        base_context: &Context,
+        operation: &OpenApiOperation,
+        template_opts: &Option<TemplateOptions>,
+        spec: &OpenApiContext,
+    ) -> Result<Context> {
+        let mut context = base_context.clone();
+
+        let builder =
+            EndpointContext::get_builder_with(self.template_kind(), template_opts.as_ref())?;
+        let mut endpoint_context = builder.build_with_spec(operation, spec)?;
+        apply_schema_file(&mut endpoint_context, template_opts)?;
+
+        // Merge the endpoint context into the template context
+        if let Some(obj) = endpoint_context.as_object() {
+            for (key, value) in obj {
+                context.insert(key, &value);
+            }
+        }
 
-                                if let Some(style) = &p.style {
-                                    param_obj.insert("style".to_string(), json!(style));
-                                }
+        // Add operation metadata
+        context.insert("operation_id", &operation.id);
+        context.insert("method", &operation.method);
+        context.insert("path", &operation.path);
+        let path_params: Vec<&str> = operation
+            .parameters
+            .iter()
+            .flatten()
+            .filter(|p| p.in_ == "path")
+            .map(|p| p.name.as_str())
+            .collect();
+        context.insert("path_params", &path_params);
+
+        // Insert OpenAPI-native fields
+        context.insert("operation_id", &operation.id);
+
+        // Sanitize and add text fields
+        let sanitized_summary = operation.summary.as_deref().map(|s| {
+            s.chars()
+                .filter(|c| c.is_ascii_alphanumeric() || c.is_whitespace())
+                .collect::<String>()
+                .trim()
+                .to_string()
+        });
+
+        let sanitized_description = operation.description.as_deref().map(|s| {
+            s.chars()
+                .filter(|c| {
+                    c.is_ascii_alphanumeric() || c.is_whitespace() || *c == '.' || *c == ','
+                })
+                .collect::<String>()
+                .trim()
+                .to_string()
+        });
+
+        context.insert("summary", &sanitized_summary);
+        context.insert("description", &sanitized_description);
+        context.insert("deprecated", &operation.deprecated);
+
+        // Add tags with proper sanitization
+        let sanitized_tags: Vec<String> = operation
+            .tags
+            .as_ref()
+            .map(|tags| {
+                tags.iter()
+                    .map(|t| t.trim().replace("\n", " ").replace("\r", " "))
+                    .collect()
+            })
+            .unwrap_or_default();
+        context.insert("tags", &sanitized_tags);
+
+        // Every `x-` extension of the operation, e.g. `x-rate-limit`, for templates
+        // that drive custom behavior from them
+        let vendor_extensions: BTreeMap<&String, &JsonValue> = operation
+            .vendor_extensions
+            .iter()
+            .filter(|(key, _)| key.starts_with("x-"))
+            .collect();
+        context.insert("vendor_extensions", &vendor_extensions);
+
+        // Extract and process parameters with proper error handling
+        let parameter_info: Vec<serde_json::Value> = operation
+            .parameters
+            .as_ref()
+            .map(|params| {
+                params
+                    .iter()
+                    .map(|p| {
+                        let mut param_obj = serde_json::Map::new();
+
+                        // Required fields
+                        param_obj.insert("name".to_string(), json!(&p.name));
+                        param_obj.insert("in".to_string(), json!(&p.in_));
+
+                        // Optional fields with their correct names
+                        if let Some(desc) = &p.description {
+                            param_obj.insert("description".to_string(), json!(desc));
+                        }
 
-                                if let Some(explode) = p.explode {
-                                    param_obj.insert("explode".to_string(), json!(explode));
-                                }
+                        // Handle required field with path parameter default
+                        let is_required = p.required.unwrap_or_else(|| p.in_ == "path");
+                        param_obj.insert("required".to_string(), json!(is_required));
 
-                                // Add allow_empty_value with correct serialization name
-                                if let Some(allow_empty) = p.allow_empty_value {
-                                    param_obj
-                                        .insert("allowEmptyValue".to_string(), json!(allow_empty));
-                                }
+                        // Add schema if available
+                        if let Some(schema) = &p.schema {
+                            param_obj.insert("schema".to_string(), schema.clone());
+                        }
 
-                                // Add allow_reserved with correct serialization name
-                                if let Some(allow_reserved) = p.allow_reserved {
-                                    param_obj
-                                        .insert("allowReserved".to_string(), json!(allow_reserved));
-                                }
+                        // Add content if available (for complex parameters)
+                        if let Some(content) = &p.content {
+                            param_obj.insert("content".to_string(), json!(content));
+                        }
 
-                                // Add any vendor extensions
-                                if !p.vendor_extensions.is_empty() {
-                                    for (key, value) in &p.vendor_extensions {
-                                        if key.starts_with("x-") {
-                                            param_obj.insert(key.clone(), value.clone());
-                                        }
-                                    }
-                                }
+                        // Add examples if available
+                        if let Some(examples) = &p.examples {
+                            param_obj.insert("examples".to_string(), json!(examples));
+                        }
 
-                                json!(param_obj)
-                            })
-                            .collect()
-                    })
-                    .unwrap_or_default();
+                        // Add other optional fields
+                        if let Some(deprecated) = p.deprecated {
+                            param_obj.insert("deprecated".to_string(), json!(deprecated));
+                        }
 
-                // Add parameters to context
-                context.insert(
-                    "parameters",
-                    &operation.parameters.clone().unwrap_or_default(),
-                );
-                context.insert("parameter_info", &parameter_info);
+                        if let Some(style) = &p.style {
+                            param_obj.insert("style".to_string(), json!(style));
+                        }
 
-                // Process responses
-                context.insert("responses", &operation.responses);
+                        if let Some(explode) = p.explode {
+                            param_obj.insert("explode".to_string(), json!(explode));
+                        }
 
-                // Add request body if present with sanitized properties
-                if let Some(request_body) = &operation.request_body {
-                    context.insert("has_request_body", &true);
-                    context.insert("request_body", request_body);
+                        // Add allow_empty_value with correct serialization name
+                        if let Some(allow_empty) = p.allow_empty_value {
+                            param_obj.insert("allowEmptyValue".to_string(), json!(allow_empty));
+                        }
 
-                    // Use the operation's method to extract request body properties
-                    match spec.extract_request_body_properties(operation) {
-                        Ok((props, _)) if !props.is_null() => {
-                            let property_info = OpenApiContext::extract_property_info(&props);
-                            context.insert("request_properties", &property_info);
+                        // Add allow_reserved with correct serialization name
+                        if let Some(allow_reserved) = p.allow_reserved {
+                            param_obj.insert("allowReserved".to_string(), json!(allow_reserved));
                         }
-                        _ => {
-                            // Fallback to basic property extraction if the above fails
-                            if let Some(content) = request_body
-                                .get("content")
-                                .and_then(serde_json::Value::as_object)
-                            {
-                                for (_content_type, media_type) in content {
-                                    if let Some(schema) = media_type.get("schema") {
-                                        let property_info =
-                                            OpenApiContext::extract_property_info(schema);
-                                        context.insert("request_properties", &property_info);
-                                        break;
-                                    }
+
+                        // Add any vendor extensions
+                        if !p.vendor_extensions.is_empty() {
+                            for (key, value) in &p.vendor_extensions {
+                                if key.starts_with("x-") {
+                                    param_obj.insert(key.clone(), value.clone());
                                 }
                             }
                         }
-                    }
-                } else {
-                    context.insert("has_request_body", &false);
-                }
-
-                // Add security requirements if present
-                if let Some(security) = &operation.security {
-                    context.insert("security", security);
-                }
 
-                // Add sanitized names for use in generated code
-                let sanitized_operation_name = operation
-                    .id
-                    .chars()
-                    .filter(|c| c.is_ascii_alphanumeric() || *c == '_')
-                    .collect::<String>();
-                context.insert("sanitized_operation_name", &sanitized_operation_name);
+                        json!(param_obj)
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        // Add raw parameters unless the builder supplied typed ones
+        if !endpoint_context
+            .as_object()
+            .is_some_and(|obj| obj.contains_key("parameters"))
+        {
+            context.insert(
+                "parameters",
+                &operation.parameters.clone().unwrap_or_default(),
+            );
+        }
+        context.insert("parameter_info", &parameter_info);
 
-                let endpoint_fs = if let Some(endpoint_val) = endpoint_context.get("endpoint_fs") {
-                    endpoint_val.as_str().unwrap_or(&operation.id)
-                } else {
-                    &operation.id
-                };
+        // Process responses
+        context.insert("responses", &operation.responses);
 
-                let endpoint_name = if let Some(endpoint_val) = endpoint_context.get("endpoint") {
-                    endpoint_val.as_str().unwrap_or(&operation.id)
-                } else {
-                    &operation.id
-                };
+        // Add request body if present with sanitized properties
+        if let Some(request_body) = &operation.request_body {
+            context.insert("has_request_body", &true);
+            context.insert("request_body", request_body);
 
-                let sanitized_filename = to_snake_case(endpoint_fs);
-                context.insert("sanitized_filename", &sanitized_filename);
+            // Use the operation's method to extract request body properties
+            match spec.extract_request_body_properties(operation) {
+                Ok((props, _)) if !props.is_null() => {
+                    let property_info = OpenApiContext::extract_property_info(&props);
+                    context.insert("request_properties", &property_info);
+                }
+                _ => {
+                    // Fallback to basic property extraction if the above fails
+                    if let Some(content) = request_body
+                        .get("content")
+                        .and_then(serde_json::Value::as_object)
+                    {
+                        for (_content_type, media_type) in content {
+                            if let Some(schema) = media_type.get("schema") {
+                                let property_info = OpenApiContext::extract_property_info(schema);
+                                context.insert("request_properties", &property_info);
+                                break;
+                            }
+                        }
+                    }
+                }
+            }
+        } else {
+            context.insert("has_request_body", &false);
+        }
 
-                log::debug!("Processing template for operation: {}", operation.id);
+        // Example inputs, e.g. for generated test stubs
+        context.insert("example_parameters", &operation.example_parameters());
+        context.insert("example_request_body", &operation.example_request_body());
 
-                // Generate schema file with proper schema extraction
-                // Use snake_case for the filename to match MCP conventions
-                let schema_filename = to_snake_case(&operation.id);
-                let schema_path = schemas_dir.join(format!("{}.json", schema_filename));
-                let mut schema_value = serde_json::to_value(operation)?;
+        // Add security requirements if present
+        if let Some(security) = &operation.security {
+            context.insert("security", security);
+        }
 
-                // Dereference all $ref in the schema
-                Self::dereference_schema_refs(&mut schema_value, spec)?;
+        // Add sanitized names for use in generated code
+        let sanitized_operation_name = operation
+            .id
+            .chars()
+            .filter(|c| c.is_ascii_alphanumeric() || *c == '_')
+            .collect::<String>();
+        context.insert("sanitized_operation_name", &sanitized_operation_name);
+
+        let filename_case = template_opts
+            .as_ref()
+            .map(|opts| opts.filename_case)
+            .unwrap_or_default();
+        let endpoint_fs = match filename_case {
+            // Snake case is the builder's own file-system name
+            FilenameCase::Snake => endpoint_context
+                .get("endpoint_fs")
+                .and_then(|v| v.as_str())
+                .map(String::from)
+                .unwrap_or_else(|| to_snake_case(&operation.id)),
+            case => case.apply(&operation.id),
+        };
+        context.insert("endpoint_fs", &endpoint_fs);
 
-                // Remove null values from the schema
-                schema_value
-                    .as_object_mut()
-                    .unwrap()
-                    .retain(|_, v| v != &json!(null));
+        let sanitized_filename = filename_case.apply(&endpoint_fs);
+        context.insert("sanitized_filename", &sanitized_filename);
 
-                let schema_json = serde_json::to_string_pretty(&schema_value)?;
-                tokio::fs::write(&schema_path, schema_json)
-                    .await
-                    .map_err(|e| {
-                        io::Error::other(format!(
-                            "Failed to write schema file {}: {}",
-                            schema_path.display(),
-                            e
-                        ))
-                    })?;
+        Ok(context)
+    }
 
-                // Generate the output path with sanitized operation_id
-                let output_file = file
-                    .destination
-                    .replace("{{operation_id}}", endpoint_fs)
-                    .replace("{operation_id}", endpoint_fs)
-                    .replace("{{endpoint}}", endpoint_name)
-                    .replace("{endpoint}", endpoint_name);
-                let output_path = output_path.join(&output_file);
-
-                // Create parent directories if they don't exist
-                if let Some(parent) = output_path.parent() {
-                    tokio::fs::create_dir_all(parent).await?;
+    /// Render one file per tag, each with the endpoints carrying that tag.
+    ///
+    /// Untagged operations are grouped under [`UNTAGGED`], or `default_2`,
+    /// `default_3`, ... when the spec has a tag of that name. The destination's
+    /// `{tag}` placeholder is replaced with the tag name in the configured
+    /// [`FilenameCase`].
+    #[allow(clippy::too_many_arguments)]
+    async fn process_tag_file(
+        &self,
+        file: &crate::manifest::TemplateFile,
+        base_context: &serde_json::Value,
+        output_dir: &Path,
+        operations: &[OpenApiOperation],
+        template_opts: &Option<TemplateOptions>,
+        spec: &OpenApiContext,
+        output: &mut Output,
+    ) -> Result<()> {
+        let mut by_tag: BTreeMap<String, Vec<OpenApiOperation>> = BTreeMap::new();
+        let mut untagged = Vec::new();
+        for operation in operations {
+            if !is_selected(operation, template_opts)? {
+                continue;
+            }
+            match operation.tags.as_deref() {
+                Some(tags) if !tags.is_empty() => {
+                    for tag in tags {
+                        by_tag
+                            .entry(tag.clone())
+                            .or_default()
+                            .push(operation.clone());
+                    }
                 }
+                _ => untagged.push(operation.clone()),
+            }
+        }
+        if !untagged.is_empty() {
+            // Keep untagged operations apart from a real tag named `default`
+            let name = std::iter::once(UNTAGGED.to_string())
+                .chain((2..).map(|n| format!("{}_{}", UNTAGGED, n)))
+                .find(|name| !by_tag.contains_key(name))
+                .expect("unbounded suffixes");
+            by_tag.insert(name, untagged);
+        }
 
-                // Render the template
-                let rendered = self.tera.render(&file.source, &context).map_err(|e| {
-                    io::Error::other(format!("Failed to render template {}: {}", file.source, e))
-                })?;
+        let filename_case = template_opts
+            .as_ref()
+            .map(|opts| opts.filename_case)
+            .unwrap_or_default();
+        for (tag, tag_operations) in by_tag {
+            let tag = tag.as_str();
+            let tag_fs = filename_case.apply(tag);
+            let mut endpoints = EndpointContext::transform_endpoints_with(
+                self.template_kind(),
+                tag_operations,
+                spec,
+                template_opts.as_ref(),
+            )?;
+            for endpoint in &mut endpoints {
+                apply_schema_file(endpoint, template_opts)?;
+            }
 
-                // Write the file
-                tokio::fs::write(&output_path, rendered)
-                    .await
-                    .map_err(|e| {
-                        io::Error::other(format!(
-                            "Failed to write file {}: {}",
-                            output_path.display(),
-                            e
-                        ))
-                    })?;
+            let file_context = self.create_file_context(base_context, file)?;
+            let mut context = Context::from_value(file_context)?;
+            context.insert("tag", tag);
+            context.insert("tag_fs", &tag_fs);
+            context.insert(
+                "tag_description",
+                &base_context
+                    .get("tag_descriptions")
+                    .and_then(|descriptions| descriptions.get(tag)),
+            );
+            context.insert("operations", &endpoints);
+
+            let output_path = output_dir.join(
+                file.destination
+                    .replace("{{tag}}", &tag_fs)
+                    .replace("{tag}", &tag_fs),
+            );
+
+            let rendered = self.tera().render(&file.source, &context).map_err(|e| {
+                crate::error::Error::template(format!(
+                    "Failed to render template '{}' for tag '{}': {}",
+                    file.source, tag, e
+                ))
+            })?;
+            let rendered = Self::finalize_output(rendered, &output_path, template_opts);
+            output
+                .write_rendered(template_opts, &output_path, rendered, true)
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Render a `for_each: all` file once, with `endpoints` holding the endpoint
+    /// contexts of every selected operation
+    #[allow(clippy::too_many_arguments)]
+    async fn process_all_file(
+        &self,
+        file: &crate::manifest::TemplateFile,
+        base_context: &serde_json::Value,
+        output_dir: &Path,
+        operations: &[OpenApiOperation],
+        template_opts: &Option<TemplateOptions>,
+        spec: &OpenApiContext,
+        output: &mut Output,
+    ) -> Result<()> {
+        let mut selected = Vec::new();
+        for operation in operations {
+            if is_selected(operation, template_opts)? {
+                selected.push(operation.clone());
             }
         }
+        let mut endpoints = EndpointContext::transform_endpoints_with(
+            self.template_kind(),
+            selected,
+            spec,
+            template_opts.as_ref(),
+        )?;
+        for endpoint in &mut endpoints {
+            apply_schema_file(endpoint, template_opts)?;
+        }
+
+        let file_context = self.create_file_context(base_context, file)?;
+        let mut context = Context::from_value(file_context)?;
+        context.insert("endpoints", &endpoints);
+
+        let output_path = output_dir.join(&file.destination);
+        let rendered = self.tera().render(&file.source, &context).map_err(|e| {
+            crate::error::Error::template(format!(
+                "Failed to render template '{}': {}",
+                file.source, e
+            ))
+        })?;
+        let rendered = Self::finalize_output(rendered, &output_path, template_opts);
+        output
+            .write_rendered(template_opts, &output_path, rendered, false)
+            .await?;
         Ok(())
     }
 
+    /// Apply output post-processing options to rendered template content
+    fn finalize_output(
+        rendered: String,
+        output_path: &Path,
+        template_opts: &Option<TemplateOptions>,
+    ) -> String {
+        let Some(opts) = template_opts else {
+            return rendered;
+        };
+        let rendered = match opts
+            .file_header
+            .as_deref()
+            .and_then(|header| comment_header(header, output_path))
+        {
+            Some(header) => header + &rendered,
+            None => rendered,
+        };
+        let rendered = if opts.trim_blank_lines {
+            trim_blank_lines(&rendered)
+        } else {
+            rendered
+        };
+        opts.line_ending.apply(&rendered)
+    }
+
     /// Validates that all required context variables are present
     fn validate_context(
         template: &str,
@@ -953,47 +1839,7 @@ impl TemplateManager {
         &self,
         output_path: &std::path::Path,
     ) -> crate::Result<()> {
-        use tokio::process::Command as AsyncCommand;
-
-        if !self.manifest.hooks.post_generate.is_empty() {
-            for command in &self.manifest.hooks.post_generate {
-                if command.contains(';') || command.contains('\n') || command.contains("&&") {
-                    return Err(io::Error::other(format!(
-                        "Invalid characters in post-generation hook: {}",
-                        command
-                    ))
-                    .into());
-                }
-                log::info!("Running post-generation hook: {}", command);
-                let mut parts = command.split_whitespace();
-                let program = parts
-                    .next()
-                    .ok_or_else(|| io::Error::other("Empty post-generation hook command"))?;
-                let output = AsyncCommand::new(program)
-                    .args(parts)
-                    .current_dir(output_path)
-                    .output()
-                    .await
-                    .map_err(|e| {
-                        io::Error::other(format!(
-                            "Failed to execute post-generation hook '{}': {}",
-                            command, e
-                        ))
-                    })?;
-
-                if !output.status.success() {
-                    return Err(io::Error::other(format!(
-                        "Post-generation hook '{}' failed with status {}\n{}{}",
-                        command,
-                        output.status,
-                        String::from_utf8_lossy(&output.stderr),
-                        String::from_utf8_lossy(&output.stdout)
-                    ))
-                    .into());
-                }
-            }
-        }
-        Ok(())
+        run_hooks(&self.manifest().hooks.post_generate, output_path).await
     }
 
     /// Merge base context with file context, giving precedence to file context keys
@@ -1017,41 +1863,59 @@ impl TemplateManager {
         Ok(serde_json::Value::Object(context))
     }
 
-    /// Dereference all $ref in a JSON value by replacing them with actual schema definitions
-    fn dereference_schema_refs(value: &mut serde_json::Value, spec: &OpenApiContext) -> Result<()> {
+    /// Dereference all $ref in a JSON value by replacing them with actual schema definitions.
+    ///
+    /// A ref already being expanded further up the same path (a cycle) is left
+    /// in place, and at most `max_depth` refs are expanded along any one path so
+    /// long non-cyclic chains stay bounded too.
+    fn dereference_schema_refs(
+        value: &mut serde_json::Value,
+        spec: &OpenApiContext,
+        max_depth: usize,
+    ) -> Result<()> {
+        Self::dereference_refs_on_path(value, spec, max_depth, &mut Vec::new())
+    }
+
+    /// [`Self::dereference_schema_refs`] below the refs in `expanding`
+    fn dereference_refs_on_path(
+        value: &mut serde_json::Value,
+        spec: &OpenApiContext,
+        max_depth: usize,
+        expanding: &mut Vec<String>,
+    ) -> Result<()> {
         match value {
             serde_json::Value::Object(map) => {
                 // Check if this object contains a $ref
-                if let Some(ref_value) = map.get("$ref") {
-                    if let Some(ref_str) = ref_value.as_str() {
-                        if ref_str.starts_with("#/components/schemas/") {
-                            let schema_name = ref_str.trim_start_matches("#/components/schemas/");
-
-                            // Get the actual schema definition
-                            if let Some(components) = spec.json.get("components") {
-                                if let Some(schemas) = components.get("schemas") {
-                                    if let Some(schema_def) = schemas.get(schema_name) {
-                                        // Replace the entire object with the dereferenced schema
-                                        *value = schema_def.clone();
-                                        // Continue dereferencing in the new value
-                                        Self::dereference_schema_refs(value, spec)?;
-                                        return Ok(());
-                                    }
-                                }
-                            }
+                if let Some(ref_str) = map.get("$ref").and_then(JsonValue::as_str) {
+                    if let Some(schema_def) = spec.resolve_schema_ref(ref_str) {
+                        if expanding.iter().any(|r| r == ref_str) {
+                            log::debug!("Leaving cyclic '{}' unexpanded", ref_str);
+                            return Ok(());
                         }
+                        if expanding.len() >= max_depth {
+                            log::warn!("Reached max_deref_depth, leaving '{}' unexpanded", ref_str);
+                            return Ok(());
+                        }
+                        expanding.push(ref_str.to_string());
+                        // Replace the entire object with the dereferenced schema
+                        *value = schema_def.clone();
+                        // Continue dereferencing in the new value
+                        let result =
+                            Self::dereference_refs_on_path(value, spec, max_depth, expanding);
+                        expanding.pop();
+                        return result;
                     }
                 }
 
                 // Recursively process all values in the object
                 for (_, v) in map.iter_mut() {
-                    Self::dereference_schema_refs(v, spec)?;
+                    Self::dereference_refs_on_path(v, spec, max_depth, expanding)?;
                 }
             }
             serde_json::Value::Array(arr) => {
                 // Recursively process all items in the array
                 for item in arr.iter_mut() {
-                    Self::dereference_schema_refs(item, spec)?;
+                    Self::dereference_refs_on_path(item, spec, max_depth, expanding)?;
                 }
             }
             _ => {} // Other types don't need processing
@@ -1060,100 +1924,872 @@ impl TemplateManager {
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::manifest::TemplateHooks;
-    use serde_json::{json, Map};
-    use tempfile;
-    use tokio;
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
 
-    #[test]
-    fn test_validate_context() {
-        let mut context = Map::new();
-        context.insert("foo".to_string(), json!("bar"));
-        context.insert("baz".to_string(), json!(123));
+/// Continue a 64-bit FNV-1a hash with `bytes`; unlike `DefaultHasher` it is
+/// stable across Rust releases, so hashes can be stored
+fn fnv1a_64(hash: u64, bytes: &[u8]) -> u64 {
+    bytes.iter().fold(hash, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
 
-        // Test with no required vars
-        assert!(TemplateManager::validate_context("test_template", &context, &[]).is_ok());
+/// Continue `hash` with the path (relative to `root`) and contents of every
+/// file below `dir`, in a fixed order
+fn hash_dir(mut hash: u64, root: &Path, dir: &Path) -> io::Result<u64> {
+    let mut entries = std::fs::read_dir(dir)?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<io::Result<Vec<_>>>()?;
+    entries.sort();
+    for path in entries {
+        if path.is_dir() {
+            hash = hash_dir(hash, root, &path)?;
+        } else {
+            let relative = path.strip_prefix(root).unwrap_or(&path);
+            hash = fnv1a_64(hash, relative.to_string_lossy().as_bytes());
+            hash = fnv1a_64(hash, &std::fs::read(&path)?);
+        }
+    }
+    Ok(hash)
+}
 
-        // Test with required vars that exist
-        assert!(TemplateManager::validate_context("test_template", &context, &["foo"]).is_ok());
-        assert!(TemplateManager::validate_context("test_template", &context, &["baz"]).is_ok());
-        assert!(
-            TemplateManager::validate_context("test_template", &context, &["foo", "baz"]).is_ok()
-        );
+/// Register the template functions that read the spec being generated:
+/// `resolve_schema(name)` returns a component schema by name or `$ref`
+fn register_spec_functions(tera: &mut Tera, spec: &OpenApiContext) {
+    let spec = OpenApiContext {
+        json: spec.json.clone(),
+    };
+    tera.register_function(
+        "resolve_schema",
+        move |args: &std::collections::HashMap<String, JsonValue>| {
+            let name = args
+                .get("name")
+                .and_then(JsonValue::as_str)
+                .ok_or_else(|| {
+                    tera::Error::msg("resolve_schema expects a `name` string argument")
+                })?;
+            let ref_str = if name.starts_with('#') {
+                name.to_string()
+            } else {
+                format!("#/components/schemas/{}", name)
+            };
+            spec.resolve_schema_ref(&ref_str).cloned().ok_or_else(|| {
+                tera::Error::msg(format!("resolve_schema: no schema named '{}'", name))
+            })
+        },
+    );
+}
 
-        // Test with missing required var
-        let result = TemplateManager::validate_context("test_template", &context, &["missing"]);
-        assert!(result.is_err());
-        if let Err(e) = result {
-            assert!(e.to_string().contains("Missing required context variables"));
-            assert!(e.to_string().contains("missing"));
-        }
+/// Whether generated schema files keep their `$ref`s rather than inlining them
+fn keep_schema_refs(template_opts: &Option<TemplateOptions>) -> bool {
+    template_opts
+        .as_ref()
+        .is_some_and(|opts| opts.keep_schema_refs)
+}
 
-        // Test with mix of existing and missing vars
-        let result =
-            TemplateManager::validate_context("test_template", &context, &["foo", "missing"]);
-        assert!(result.is_err());
+/// A copy of `spec` with the configured patch and preprocessor applied, if
+/// there are any
+fn preprocess_spec(
+    spec: &OpenApiContext,
+    template_opts: &Option<TemplateOptions>,
+) -> Result<Option<OpenApiContext>> {
+    let Some(opts) = template_opts
+        .as_ref()
+        .filter(|opts| opts.spec_patch.is_some() || opts.spec_preprocessor.is_some())
+    else {
+        return Ok(None);
+    };
+    let mut json = spec.json.clone();
+    if let Some(patch) = &opts.spec_patch {
+        crate::patch::apply_patch(&mut json, patch)?;
     }
+    if let Some(preprocessor) = &opts.spec_preprocessor {
+        (preprocessor.0)(&mut json)?;
+    }
+    Ok(Some(OpenApiContext { json }))
+}
 
-    #[tokio::test]
-    async fn test_template_manager() -> Result<()> {
-        let temp_dir = tempfile::tempdir()?;
-        let templates_base_dir = temp_dir.path().join("templates");
-        let template_dir = templates_base_dir.join("custom");
-        tokio::fs::create_dir_all(&template_dir).await?;
+/// Directory of the workspace member `crate_name` (a manifest file's `crate`),
+/// `None` when files are relative to the output directory itself
+fn crate_dir(
+    crate_name: Option<&str>,
+    output_dir: &Path,
+    base_context: &JsonValue,
+    template_opts: &Option<TemplateOptions>,
+) -> Option<PathBuf> {
+    let layout = template_opts
+        .as_ref()
+        .map(|opts| opts.layout)
+        .unwrap_or_default();
+    if layout != Layout::Workspace {
+        return None;
+    }
+    let project_name = base_context
+        .get("project_name")
+        .and_then(JsonValue::as_str)
+        .unwrap_or_default();
+    let crate_name = crate_name?.replace("{project_name}", project_name);
+    Some(output_dir.join("crates").join(crate_name))
+}
 
-        // Create a simple template
-        let template_content = "Hello {{ name }}!";
-        let template_path = template_dir.join("test.tera");
-        tokio::fs::write(&template_path, template_content).await?;
+/// Add `schema_file`, the endpoint's schema file path under `schemas/`, to an
+/// endpoint context
+fn apply_schema_file(
+    endpoint: &mut JsonValue,
+    template_opts: &Option<TemplateOptions>,
+) -> Result<()> {
+    let Some(endpoint) = endpoint.as_object_mut() else {
+        return Ok(());
+    };
+    let field = |key: &str| {
+        endpoint
+            .get(key)
+            .and_then(JsonValue::as_str)
+            .unwrap_or_default()
+    };
+    let tag = endpoint
+        .get("tags")
+        .and_then(|tags| tags.get(0))
+        .and_then(JsonValue::as_str);
+    let schema_file = schema_file_name(template_opts, field("endpoint"), tag, field("method"))?;
+    endpoint.insert("schema_file".to_string(), json!(schema_file));
+    Ok(())
+}
 
-        // Create a test manifest
-        let manifest = TemplateManifest {
-            name: "test".to_string(),
-            description: "Test template".to_string(),
-            version: "0.1.0".to_string(),
-            language: "rust".to_string(),
-            files: vec![],
-            hooks: TemplateHooks::default(),
-        };
-        let manifest_path = template_dir.join("manifest.toml");
-        let manifest_toml = toml::to_string_pretty(&manifest).map_err(|e| {
+/// An operation's schema file path under `schemas/`, from
+/// [`TemplateOptions::schema_filename_pattern`]
+fn schema_file_name(
+    template_opts: &Option<TemplateOptions>,
+    operation: &str,
+    tag: Option<&str>,
+    method: &str,
+) -> Result<String> {
+    let pattern = template_opts
+        .as_ref()
+        .and_then(|opts| opts.schema_filename_pattern.as_deref())
+        .unwrap_or(DEFAULT_SCHEMA_FILENAME_PATTERN);
+    let filename_case = template_opts
+        .as_ref()
+        .map(|opts| opts.filename_case)
+        .unwrap_or_default();
+    if !pattern.contains("{operation}") {
+        return Err(crate::error::Error::config(format!(
+            "schema_filename_pattern '{}' has no {{operation}}, so every operation would share one schema file",
+            pattern
+        )));
+    }
+    let tag = tag.map_or_else(|| UNTAGGED.to_string(), |tag| filename_case.apply(tag));
+    let name = pattern
+        .replace("{operation}", operation)
+        .replace("{tag}", &tag)
+        .replace("{method}", method);
+    // Keep schema files inside `schemas/`
+    let is_inside = Path::new(&name)
+        .components()
+        .all(|c| matches!(c, std::path::Component::Normal(_)));
+    if name.is_empty() || !is_inside {
+        return Err(crate::error::Error::config(format!(
+            "schema_filename_pattern '{}' gives '{}', which is not a relative path inside schemas/",
+            pattern, name
+        )));
+    }
+    Ok(name)
+}
+
+/// Warning for a manifest whose `language` isn't the one `kind`'s builder generates
+fn language_mismatch(manifest: &TemplateManifest, kind: TemplateKind) -> Option<Warning> {
+    let expected = kind.language()?;
+    if manifest.language.is_empty() || manifest.language.eq_ignore_ascii_case(expected) {
+        return None;
+    }
+    Some(Warning::new(
+        WarningKind::LanguageMismatch,
+        format!(
+            "Template '{}' targets {} but template kind {} generates {} code",
+            manifest.name, manifest.language, kind, expected
+        ),
+    ))
+}
+
+/// Read the template manifest, trying `manifest.yaml` first, then
+/// `manifest.toml`; a template without either gets an empty manifest
+async fn load_manifest(template_path: &Path) -> Result<TemplateManifest> {
+    let yaml_manifest_path = template_path.join("manifest.yaml");
+    let toml_manifest_path = template_path.join("manifest.toml");
+
+    let manifest = if yaml_manifest_path.exists() {
+        let manifest_content = tokio::fs::read_to_string(&yaml_manifest_path)
+            .await
+            .map_err(|e| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("Failed to read template manifest: {}", e),
+                )
+            })?;
+        serde_yaml::from_str(&manifest_content).map_err(|e| {
             io::Error::new(
                 io::ErrorKind::InvalidData,
-                format!("Failed to serialize manifest: {}", e),
+                format!("Failed to parse template manifest: {}", e),
             )
-        })?;
-        tokio::fs::write(&manifest_path, manifest_toml).await?;
-
-        // Test creating a new TemplateManager
-        let manager =
-            TemplateManager::new(TemplateKind::Custom, Some(templates_base_dir.clone())).await?;
-
-        // Test template_kind
-        assert_eq!(manager.template_kind(), TemplateKind::Custom);
+        })?
+    } else if toml_manifest_path.exists() {
+        let manifest_content = tokio::fs::read_to_string(&toml_manifest_path)
+            .await
+            .map_err(|e| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("Failed to read template manifest: {}", e),
+                )
+            })?;
+        toml::from_str(&manifest_content).map_err(|e| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Failed to parse template manifest: {}", e),
+            )
+        })?
+    } else {
+        TemplateManifest::default()
+    };
+    Ok(manifest)
+}
 
-        // Test template_dir_path
-        assert!(manager.template_dir_path().ends_with("custom"));
+/// Compile every file under the template directory
+fn load_tera(template_dir: &str) -> Result<Tera> {
+    let mut tera = Tera::new(&format!("{}/**/*", template_dir)).map_err(|e| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("Failed to parse templates: {}", e),
+        )
+    })?;
+    tera.register_filter("rust_string", rust_string_filter);
+    Ok(tera)
+}
 
-        // Test has_template
-        assert!(manager.has_template("test.tera"));
+/// `rust_string` filter: a string (or any other value, as JSON) as a Rust
+/// string literal, quotes and escapes included
+fn rust_string_filter(
+    value: &JsonValue,
+    _: &std::collections::HashMap<String, JsonValue>,
+) -> tera::Result<JsonValue> {
+    let text = match value {
+        JsonValue::String(text) => text.clone(),
+        other => other.to_string(),
+    };
+    Ok(json!(format!("{:?}", text)))
+}
 
-        // Test list_templates
-        let templates = manager.list_templates();
-        assert!(templates.is_empty()); // No files in manifest yet
+/// Newest modification time of `dir` and anything below it (directory mtimes
+/// catch added and removed files), or `None` if it can't be read
+fn newest_mtime(dir: &Path) -> Option<SystemTime> {
+    let mut newest = std::fs::metadata(dir).and_then(|m| m.modified()).ok()?;
+    for entry in std::fs::read_dir(dir).ok()?.flatten() {
+        let path = entry.path();
+        let modified = if path.is_dir() {
+            newest_mtime(&path)
+        } else {
+            entry.metadata().and_then(|m| m.modified()).ok()
+        };
+        newest = newest.max(modified.unwrap_or(newest));
+    }
+    Some(newest)
+}
 
-        // Test template_dir
-        assert!(manager.template_dir().exists());
+/// Where rendered files go
+enum Output {
+    /// Write to disk, creating parent directories as needed, and keep what was
+    /// rendered for each path (see [`GenerationManifest::files`])
+    Disk(BTreeMap<PathBuf, String>),
+    /// Collect `(path, content)` pairs, see [`TemplateManager::render_one`]
+    Memory(Vec<(PathBuf, String)>),
+}
 
-        // Test template rendering
-        let mut context = tera::Context::new();
-        context.insert("name", "World");
+impl Output {
+    /// Write freshly rendered template output to `path`, subject to
+    /// [`TemplateOptions::append_only`] (see [`append_only_content`])
+    async fn write_rendered(
+        &mut self,
+        template_opts: &Option<TemplateOptions>,
+        path: &Path,
+        rendered: String,
+        per_item: bool,
+    ) -> Result<()> {
+        if let Output::Disk(generated) = self {
+            generated.insert(path.to_path_buf(), rendered.clone());
+        }
+        if let Some(content) = append_only_content(template_opts, path, rendered, per_item).await? {
+            self.write(path, content).await?;
+        }
+        Ok(())
+    }
 
-        // Test rendering the template
-        let output = manager.tera.render("test.tera", &context).map_err(|e| {
+    async fn write(&mut self, path: &Path, content: String) -> Result<()> {
+        match self {
+            Output::Disk(_) => {
+                if let Some(parent) = path.parent() {
+                    tokio::fs::create_dir_all(parent).await.map_err(|e| {
+                        io::Error::other(format!(
+                            "Failed to create output directory {}: {}",
+                            parent.display(),
+                            e
+                        ))
+                    })?;
+                }
+                write_atomically(path, content.as_bytes())
+                    .await
+                    .map_err(|e| {
+                        io::Error::other(format!("Failed to write file {}: {}", path.display(), e))
+                    })?;
+            }
+            Output::Memory(files) => files.push((path.to_path_buf(), content)),
+        }
+        Ok(())
+    }
+}
+
+/// Write `content` to a `.tmp` sibling of `path` and rename it into place, so
+/// an interrupted generation leaves either the old or the new file, never a
+/// partial one
+async fn write_atomically(path: &Path, content: &[u8]) -> io::Result<()> {
+    let mut tmp_name = path.file_name().unwrap_or_default().to_os_string();
+    tmp_name.push(".tmp");
+    let tmp_path = path.with_file_name(tmp_name);
+    let result = match tokio::fs::write(&tmp_path, content).await {
+        Ok(()) => tokio::fs::rename(&tmp_path, path).await,
+        Err(e) => Err(e),
+    };
+    if result.is_err() {
+        let _ = tokio::fs::remove_file(&tmp_path).await;
+    }
+    result
+}
+
+/// Marker opening a generated region that append-only generation rewrites
+const REGION_BEGIN: &str = "// AGENTERRA:BEGIN";
+/// Marker closing a generated region
+const REGION_END: &str = "// AGENTERRA:END";
+
+/// What to write to `path` given the freshly rendered content, `None` to leave
+/// the file alone.
+///
+/// Outside [`TemplateOptions::append_only`] this is always the rendered content.
+/// In append-only mode, files that don't exist yet are written in full; existing
+/// per-item (operation or tag) files are kept, and other existing files only get
+/// their generated regions replaced.
+async fn append_only_content(
+    template_opts: &Option<TemplateOptions>,
+    path: &Path,
+    rendered: String,
+    per_item: bool,
+) -> Result<Option<String>> {
+    if !template_opts.as_ref().is_some_and(|opts| opts.append_only) {
+        return Ok(Some(rendered));
+    }
+    let existing = match tokio::fs::read_to_string(path).await {
+        Ok(existing) => existing,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Some(rendered)),
+        Err(e) => return Err(e.into()),
+    };
+    if per_item {
+        return Ok(None);
+    }
+    let merged = merge_generated_regions(&existing, &rendered).map_err(|name| {
+        crate::error::Error::template(format!(
+            "{}: `{} {}` has no matching `{} {}`, leaving the file alone",
+            path.display(),
+            REGION_BEGIN,
+            name,
+            REGION_END,
+            name
+        ))
+    })?;
+    Ok((merged != existing).then_some(merged))
+}
+
+/// Name of the region a `marker` line opens or closes
+fn region_marker<'a>(line: &'a str, marker: &str) -> Option<&'a str> {
+    line.trim().strip_prefix(marker).map(str::trim)
+}
+
+/// Replace the body of each generated region in `existing` with the body of the
+/// region of the same name in `rendered`, keeping everything else as it is
+///
+/// Fails with the region's name when `existing` opens a region it never closes,
+/// since everything after the marker would otherwise be replaced.
+fn merge_generated_regions<'a>(
+    existing: &'a str,
+    rendered: &str,
+) -> std::result::Result<String, &'a str> {
+    let mut regions: BTreeMap<&str, String> = BTreeMap::new();
+    let mut lines = rendered.split_inclusive('\n');
+    while let Some(line) = lines.next() {
+        if let Some(name) = region_marker(line, REGION_BEGIN) {
+            let body = lines
+                .by_ref()
+                .take_while(|l| region_marker(l, REGION_END) != Some(name))
+                .collect();
+            regions.insert(name, body);
+        }
+    }
+
+    let mut merged = String::with_capacity(existing.len());
+    let mut lines = existing.split_inclusive('\n');
+    while let Some(line) = lines.next() {
+        merged.push_str(line);
+        let Some(name) = region_marker(line, REGION_BEGIN) else {
+            continue;
+        };
+        let mut body = String::new();
+        let mut end = None;
+        for inner in lines.by_ref() {
+            if region_marker(inner, REGION_END) == Some(name) {
+                end = Some(inner);
+                break;
+            }
+            body.push_str(inner);
+        }
+        let Some(end) = end else {
+            return Err(name);
+        };
+        merged.push_str(regions.get(name).unwrap_or(&body));
+        merged.push_str(end);
+    }
+    Ok(merged)
+}
+
+/// Directory of the per-operation OpenAPI documents written with
+/// [`TemplateOptions::emit_operation_fragments`]
+const OPERATION_FRAGMENTS_DIR: &str = "openapi";
+
+/// Write the standalone OpenAPI document of every selected operation when
+/// [`TemplateOptions::emit_operation_fragments`] is set
+async fn write_operation_fragments(
+    spec: &OpenApiContext,
+    operations: &[OpenApiOperation],
+    template_opts: &Option<TemplateOptions>,
+    output_dir: &Path,
+) -> Result<()> {
+    if !template_opts
+        .as_ref()
+        .is_some_and(|opts| opts.emit_operation_fragments)
+    {
+        return Ok(());
+    }
+    for operation in operations {
+        if !is_selected(operation, template_opts)? {
+            continue;
+        }
+        let Some(fragment) = spec.operation_fragment(&operation.path, &operation.method) else {
+            continue;
+        };
+        let path = output_dir
+            .join(OPERATION_FRAGMENTS_DIR)
+            .join(format!("{}.json", to_snake_case(&operation.id)));
+        Output::Disk(BTreeMap::new())
+            .write(&path, serde_json::to_string_pretty(&fragment)?)
+            .await?;
+    }
+    Ok(())
+}
+
+/// File name of the MCP server descriptor, written next to the server's
+/// `Cargo.toml`
+const MCP_DESCRIPTOR_FILE: &str = "mcp.json";
+
+/// Components written beside schema files that keep their `$ref`s
+const COMPONENTS_SCHEMA_FILE: &str = "components.json";
+
+/// MCP server descriptor for the generated server: its name and version (from
+/// the spec's `info`), the capabilities it serves and one tool per selected operation
+fn mcp_descriptor(
+    spec: &OpenApiContext,
+    operations: &[OpenApiOperation],
+    base_context: &JsonValue,
+    template_opts: &Option<TemplateOptions>,
+    config: &Config,
+) -> Result<JsonValue> {
+    // `endpoints` holds one context per operation, in the same order
+    let endpoints = base_context
+        .get("endpoints")
+        .and_then(JsonValue::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    let mut tools = Vec::new();
+    for (i, operation) in operations.iter().enumerate() {
+        if !is_selected(operation, template_opts)? {
+            continue;
+        }
+        let description = operation
+            .summary
+            .as_deref()
+            .or(operation.description.as_deref())
+            .unwrap_or_default();
+        // Tools are named after their handler functions
+        let name = endpoints
+            .get(i)
+            .and_then(|endpoint| endpoint.get("fn_name"))
+            .and_then(JsonValue::as_str)
+            .map_or_else(|| to_snake_case(&operation.id), String::from);
+        tools.push(json!({
+            "name": name,
+            "description": description,
+        }));
+    }
+    let mut descriptor = json!({
+        "name": spec.title().unwrap_or(&config.project_name),
+        "version": spec.version().unwrap_or("0.1.0"),
+        "capabilities": {"tools": {}, "resources": {}},
+        "tools": tools,
+    });
+    if let Some(description) = spec.as_json().pointer("/info/description") {
+        descriptor["description"] = description.clone();
+    }
+    Ok(descriptor)
+}
+
+/// Number of operations rendered at once: [`TemplateOptions::max_concurrency`],
+/// else the number of CPUs
+fn max_concurrency(template_opts: &Option<TemplateOptions>) -> usize {
+    template_opts
+        .as_ref()
+        .and_then(|opts| opts.max_concurrency)
+        .unwrap_or_else(|| std::thread::available_parallelism().map_or(1, usize::from))
+        .max(1)
+}
+
+/// Whether an operation passes the include/exclude filters in the template options
+fn is_selected(
+    operation: &OpenApiOperation,
+    template_opts: &Option<TemplateOptions>,
+) -> Result<bool> {
+    let Some(opts) = template_opts else {
+        return Ok(true);
+    };
+    let include = opts.all_operations
+        || opts.include_operations.is_empty()
+        || opts.include_operations.contains(&operation.id);
+    let exclude = opts.exclude_operations.contains(&operation.id);
+    let include_path =
+        opts.include_paths.is_empty() || path_matches(&opts.include_paths, &operation.path)?;
+    let exclude_path = path_matches(&opts.exclude_paths, &operation.path)?;
+    Ok(include && !exclude && include_path && !exclude_path)
+}
+
+/// Whether a path matches any of the globs; `*` stays within one segment, `**` spans segments
+fn path_matches(patterns: &[String], path: &str) -> Result<bool> {
+    for pattern in patterns {
+        let glob = globset::GlobBuilder::new(pattern)
+            .literal_separator(true)
+            .build()
+            .map_err(|e| {
+                crate::error::Error::config(format!("Invalid path glob '{}': {}", pattern, e))
+            })?;
+        if glob.compile_matcher().is_match(path) {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// Whether a manifest file's `when` flag (if any) is set in the base context
+fn is_enabled(file: &crate::manifest::TemplateFile, base_context: &serde_json::Value) -> bool {
+    match &file.when {
+        Some(flag) => base_context
+            .get(flag)
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false),
+        None => true,
+    }
+}
+
+/// Run hook commands in order inside `output_path`, failing on the first non-zero exit
+async fn run_hooks(commands: &[String], output_path: &Path) -> Result<()> {
+    use tokio::process::Command as AsyncCommand;
+
+    for command in commands {
+        if command.contains(';') || command.contains('\n') || command.contains("&&") {
+            return Err(io::Error::other(format!(
+                "Invalid characters in post-generation hook: {}",
+                command
+            ))
+            .into());
+        }
+        log::info!("Running post-generation hook: {}", command);
+        let mut parts = command.split_whitespace();
+        let program = parts
+            .next()
+            .ok_or_else(|| io::Error::other("Empty post-generation hook command"))?;
+        let output = AsyncCommand::new(program)
+            .args(parts)
+            .current_dir(output_path)
+            .output()
+            .await
+            .map_err(|e| {
+                io::Error::other(format!(
+                    "Failed to execute post-generation hook '{}': {}",
+                    command, e
+                ))
+            })?;
+
+        if !output.status.success() {
+            return Err(io::Error::other(format!(
+                "Post-generation hook '{}' failed with status {}\n{}{}",
+                command,
+                output.status,
+                String::from_utf8_lossy(&output.stderr),
+                String::from_utf8_lossy(&output.stdout)
+            ))
+            .into());
+        }
+    }
+    Ok(())
+}
+
+/// Fail unless `cargo clippy -- -D warnings` passes in `output_path`, for
+/// [`TemplateOptions::lint_output`]; passes with a warning without clippy
+async fn run_clippy(output_path: &Path) -> Result<()> {
+    use tokio::process::Command as AsyncCommand;
+
+    let installed = AsyncCommand::new("cargo")
+        .args(["clippy", "--version"])
+        .output()
+        .await
+        .is_ok_and(|output| output.status.success());
+    if !installed {
+        log::warn!("cargo clippy is not installed, skipping lint_output");
+        return Ok(());
+    }
+
+    log::info!("Running cargo clippy in {}", output_path.display());
+    let output = AsyncCommand::new("cargo")
+        .args(["clippy", "--", "-D", "warnings"])
+        .current_dir(output_path)
+        .output()
+        .await
+        .map_err(|e| io::Error::other(format!("Failed to run cargo clippy: {}", e)))?;
+    if !output.status.success() {
+        return Err(crate::Error::template(format!(
+            "Generated code in {} is not clippy-clean:\n{}",
+            output_path.display(),
+            String::from_utf8_lossy(&output.stderr)
+        )));
+    }
+    Ok(())
+}
+
+/// Keys removed from schema files by [`TemplateOptions::minify_schemas`]
+const DOCUMENTATION_KEYS: [&str; 4] = ["description", "title", "example", "examples"];
+
+/// Objects whose keys are names (properties, headers, media types, status
+/// codes, ...) rather than keywords
+const NAME_KEYED_MAPS: [&str; 8] = [
+    "properties",
+    "patternProperties",
+    "headers",
+    "mapping",
+    "content",
+    "responses",
+    "callbacks",
+    "links",
+];
+
+/// Remove the keys matching `remove` from every object in `value`, leaving
+/// names (e.g. of properties or headers) and example or default data alone
+fn prune_schema_keys(value: &mut JsonValue, remove: &dyn Fn(&str) -> bool) {
+    match value {
+        JsonValue::Object(map) => {
+            map.retain(|key, _| !remove(key));
+            for (key, child) in map.iter_mut() {
+                match key.as_str() {
+                    "example" | "examples" | "default" | "enum" | "const" => {}
+                    key if NAME_KEYED_MAPS.contains(&key) => {
+                        for schema in child
+                            .as_object_mut()
+                            .into_iter()
+                            .flat_map(|m| m.values_mut())
+                        {
+                            prune_schema_keys(schema, remove);
+                        }
+                    }
+                    _ => prune_schema_keys(child, remove),
+                }
+            }
+        }
+        JsonValue::Array(items) => items
+            .iter_mut()
+            .for_each(|item| prune_schema_keys(item, remove)),
+        _ => {}
+    }
+}
+
+/// Prefix every local `$ref` (`#/...`) in `value` with `file`, so the refs of
+/// a schema file resolve against the components written to `file`
+fn rebase_local_refs(value: &mut JsonValue, file: &str) {
+    match value {
+        JsonValue::Object(map) => {
+            for (key, child) in map.iter_mut() {
+                match (key.as_str(), &*child) {
+                    ("example" | "examples" | "default" | "enum" | "const", _) => {}
+                    ("$ref", JsonValue::String(target)) if target.starts_with('#') => {
+                        *child = json!(format!("{}{}", file, target));
+                    }
+                    _ => rebase_local_refs(child, file),
+                }
+            }
+        }
+        JsonValue::Array(items) => items
+            .iter_mut()
+            .for_each(|item| rebase_local_refs(item, file)),
+        _ => {}
+    }
+}
+
+/// Render a file header as comments in the syntax of the output file's language.
+///
+/// Returns `None` for files without a known comment syntax.
+fn comment_header(header: &str, output_path: &Path) -> Option<String> {
+    let file_name = output_path.file_name()?.to_str()?;
+    let extension = output_path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or(file_name);
+    let prefix = match extension.trim_start_matches('.').to_lowercase().as_str() {
+        "rs" | "ts" | "tsx" | "js" | "jsx" | "go" | "java" | "kt" | "swift" | "c" | "h" | "cpp"
+        | "cs" | "scala" | "proto" => "//",
+        "py" | "toml" | "yaml" | "yml" | "sh" | "rb" | "env" | "example" | "gitignore"
+        | "dockerignore" | "dockerfile" | "makefile" => "#",
+        "sql" | "lua" => "--",
+        "md" | "html" | "xml" => {
+            return Some(format!("<!--\n{}\n-->\n", header.trim_end()));
+        }
+        _ => return None,
+    };
+    let mut commented = String::new();
+    for line in header.trim_end().lines() {
+        if line.is_empty() {
+            commented.push_str(prefix);
+        } else {
+            commented.push_str(&format!("{} {}", prefix, line));
+        }
+        commented.push('\n');
+    }
+    Some(commented)
+}
+
+/// Strip trailing whitespace and collapse runs of three or more blank lines into one
+fn trim_blank_lines(content: &str) -> String {
+    let mut lines = Vec::new();
+    let mut blank_run = 0;
+    for line in content.split('\n') {
+        let line = line.trim_end();
+        if line.is_empty() {
+            blank_run += 1;
+            continue;
+        }
+        let keep = if blank_run >= 3 { 1 } else { blank_run };
+        lines.extend(std::iter::repeat_n("", keep));
+        blank_run = 0;
+        lines.push(line);
+    }
+    let keep = if blank_run >= 3 { 1 } else { blank_run };
+    lines.extend(std::iter::repeat_n("", keep));
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::manifest::TemplateHooks;
+    use crate::templates::{LineEnding, RequestBodyNaming, SchemaJsonStyle, SpecPreprocessor};
+    use serde_json::{json, Map};
+    use tempfile;
+    use tokio;
+
+    #[test]
+    fn test_validate_context() {
+        let mut context = Map::new();
+        context.insert("foo".to_string(), json!("bar"));
+        context.insert("baz".to_string(), json!(123));
+
+        // Test with no required vars
+        assert!(TemplateManager::validate_context("test_template", &context, &[]).is_ok());
+
+        // Test with required vars that exist
+        assert!(TemplateManager::validate_context("test_template", &context, &["foo"]).is_ok());
+        assert!(TemplateManager::validate_context("test_template", &context, &["baz"]).is_ok());
+        assert!(
+            TemplateManager::validate_context("test_template", &context, &["foo", "baz"]).is_ok()
+        );
+
+        // Test with missing required var
+        let result = TemplateManager::validate_context("test_template", &context, &["missing"]);
+        assert!(result.is_err());
+        if let Err(e) = result {
+            assert!(e.to_string().contains("Missing required context variables"));
+            assert!(e.to_string().contains("missing"));
+        }
+
+        // Test with mix of existing and missing vars
+        let result =
+            TemplateManager::validate_context("test_template", &context, &["foo", "missing"]);
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_template_manager() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let templates_base_dir = temp_dir.path().join("templates");
+        let template_dir = templates_base_dir.join("custom");
+        tokio::fs::create_dir_all(&template_dir).await?;
+
+        // Create a simple template
+        let template_content = "Hello {{ name }}!";
+        let template_path = template_dir.join("test.tera");
+        tokio::fs::write(&template_path, template_content).await?;
+
+        // Create a test manifest
+        let manifest = TemplateManifest {
+            name: "test".to_string(),
+            description: "Test template".to_string(),
+            version: "0.1.0".to_string(),
+            language: "rust".to_string(),
+            files: vec![],
+            hooks: TemplateHooks::default(),
+        };
+        let manifest_path = template_dir.join("manifest.toml");
+        let manifest_toml = toml::to_string_pretty(&manifest).map_err(|e| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Failed to serialize manifest: {}", e),
+            )
+        })?;
+        tokio::fs::write(&manifest_path, manifest_toml).await?;
+
+        // Test creating a new TemplateManager
+        let manager =
+            TemplateManager::new(TemplateKind::Custom, Some(templates_base_dir.clone())).await?;
+
+        // Test template_kind
+        assert_eq!(manager.template_kind(), TemplateKind::Custom);
+
+        // Test template_dir_path
+        assert!(manager.template_dir_path().ends_with("custom"));
+
+        // Test has_template
+        assert!(manager.has_template("test.tera"));
+
+        // Test list_templates
+        let templates = manager.list_templates();
+        assert!(templates.is_empty()); // No files in manifest yet
+
+        // Test template_dir
+        assert!(manager.template_dir().exists());
+
+        // Test template rendering
+        let mut context = tera::Context::new();
+        context.insert("name", "World");
+
+        // Test rendering the template
+        let output = manager.tera().render("test.tera", &context).map_err(|e| {
             io::Error::new(
                 io::ErrorKind::InvalidData,
                 format!("Failed to render template: {}", e),
@@ -1164,4 +2800,2265 @@ mod tests {
 
         Ok(())
     }
+
+    /// Write a rust_axum-kind template directory containing the given files
+    /// and return a manager loaded from it
+    async fn setup_template(temp_dir: &Path, files: &[(&str, &str)]) -> Result<TemplateManager> {
+        let templates_base_dir = temp_dir.join("templates");
+        let template_dir = templates_base_dir.join(TemplateKind::RustAxum.as_str());
+        tokio::fs::create_dir_all(&template_dir).await?;
+        for (name, content) in files {
+            let path = template_dir.join(name);
+            if let Some(parent) = path.parent() {
+                tokio::fs::create_dir_all(parent).await?;
+            }
+            tokio::fs::write(path, content).await?;
+        }
+        TemplateManager::new(TemplateKind::RustAxum, Some(templates_base_dir)).await
+    }
+
+    /// `manifest.yaml` for a test template rendering `files` (YAML list items)
+    fn test_manifest(files: &str) -> String {
+        format!(
+            "name: t\ndescription: t\nversion: \"0.1.0\"\nlanguage: rust\nfiles:\n{}",
+            files
+        )
+    }
+
+    /// Manager for the repository's built-in `rust_axum` templates
+    async fn builtin_manager() -> Result<TemplateManager> {
+        let templates_base = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .parent()
+            .and_then(|p| p.parent())
+            .unwrap()
+            .join("templates");
+        TemplateManager::new(TemplateKind::RustAxum, Some(templates_base)).await
+    }
+
+    /// Minimal spec with a single server so `build_context` succeeds
+    fn test_spec(paths: JsonValue) -> OpenApiContext {
+        OpenApiContext {
+            json: json!({
+                "openapi": "3.0.0",
+                "info": {"title": "Test API", "version": "1.0.0"},
+                "servers": [{"url": "https://api.example.com"}],
+                "paths": paths
+            }),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_generate_is_deterministic() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        // `responses_by_status` and `response_schema` are built by iterating the
+        // operation's responses in Rust, so an unordered map would show here
+        let manager = setup_template(
+            temp_dir.path(),
+            &[
+                (
+                    "manifest.yaml",
+                    &test_manifest("  - source: op.tera\n    destination: \"{endpoint}.txt\"\n    for_each: endpoint\n"),
+                ),
+                (
+                    "op.tera",
+                    "{% for r in responses_by_status %}{{ r.status }} {% endfor %}| {{ response_schema | json_encode() }}",
+                ),
+            ],
+        )
+        .await?;
+        let statuses = [
+            "201", "202", "203", "204", "206", "207", "400", "401", "403", "404", "409", "500",
+            "default",
+        ];
+        let responses: serde_json::Map<String, JsonValue> = statuses
+            .iter()
+            .map(|status| {
+                let schema = json!({"type": "object", "title": format!("R{}", status)});
+                let response = json!({
+                    "description": status,
+                    "x-status": status,
+                    "content": {"application/json": {"schema": schema}}
+                });
+                (status.to_string(), response)
+            })
+            .collect();
+        let spec = test_spec(json!({
+            "/pets": {"get": {
+                "operationId": "listPets",
+                "tags": ["pets"],
+                "x-alpha": 1, "x-beta": 2, "x-gamma": 3, "x-delta": 4,
+                "responses": responses
+            }}
+        }));
+
+        let mut outputs = Vec::new();
+        for run in 0..5 {
+            let out = temp_dir.path().join(run.to_string());
+            let config = Config::new("test", "openapi.json", out.to_string_lossy());
+            manager.generate(&spec, &config, None).await?;
+            outputs.push(tokio::fs::read_to_string(out.join("list_pets.txt")).await?);
+        }
+        let expected = format!(
+            "{} | {{\"title\":\"R201\",\"type\":\"object\"}}",
+            statuses.join(" ")
+        );
+        for output in outputs {
+            assert_eq!(output, expected);
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_handler_fn_names_lists_every_endpoint() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let manager = setup_template(
+            temp_dir.path(),
+            &[
+                (
+                    "manifest.yaml",
+                    &test_manifest("  - source: mod.tera\n    destination: mod.txt\n"),
+                ),
+                ("mod.tera", "{{ handler_fn_names | join(sep=\",\") }}"),
+            ],
+        )
+        .await?;
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("../..");
+        let spec =
+            OpenApiContext::from_file(root.join("tests/fixtures/openapi/petstore.openapi.v3.json"))
+                .await?;
+        let out = temp_dir.path().join("out");
+        let mut config = Config::new("test", "openapi.json", out.to_string_lossy());
+        config.base_url = Some("https://petstore3.swagger.io".parse().unwrap());
+        manager.generate(&spec, &config, None).await?;
+
+        // Only GET and POST operations are generated
+        assert_eq!(
+            tokio::fs::read_to_string(out.join("mod.txt")).await?,
+            [
+                "add_pet",
+                "create_user",
+                "create_users_with_list_input",
+                "find_pets_by_status",
+                "find_pets_by_tags",
+                "get_inventory",
+                "get_order_by_id",
+                "get_pet_by_id",
+                "get_user_by_name",
+                "login_user",
+                "logout_user",
+                "place_order",
+                "update_pet_with_form",
+                "upload_file",
+            ]
+            .join(",")
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_lint_output_surfaces_clippy_errors() -> Result<()> {
+        let has_clippy = std::process::Command::new("cargo")
+            .args(["clippy", "--version"])
+            .output()
+            .is_ok_and(|output| output.status.success());
+        if !has_clippy {
+            return Ok(());
+        }
+        let temp_dir = tempfile::tempdir()?;
+        let manager = setup_template(
+            temp_dir.path(),
+            &[
+                (
+                    "manifest.yaml",
+                    &test_manifest("  - source: cargo.tera\n    destination: Cargo.toml\n  - source: main.tera\n    destination: src/main.rs\n"),
+                ),
+                (
+                    "cargo.tera",
+                    "[package]\nname = \"linted\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n[workspace]\n",
+                ),
+                ("main.tera", "fn main() {\n    let count = {{ endpoints | length }};\n}\n"),
+            ],
+        )
+        .await?;
+        let spec = test_spec(json!({"/a": {"get": {"operationId": "getA"}}}));
+        let out = temp_dir.path().join("out");
+        let config = Config::new("test", "openapi.json", out.to_string_lossy());
+        let options = TemplateOptions {
+            lint_output: true,
+            ..Default::default()
+        };
+
+        let err = manager
+            .generate(&spec, &config, Some(options))
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("is not clippy-clean"), "{}", err);
+        assert!(err.contains("unused variable: `count`"), "{}", err);
+
+        // Without the flag the same output is accepted
+        manager.generate(&spec, &config, None).await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_unchanged_inputs_skip_generation() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let manager = setup_template(
+            temp_dir.path(),
+            &[
+                (
+                    "manifest.yaml",
+                    &test_manifest("  - source: index.tera\n    destination: index.txt\n"),
+                ),
+                ("index.tera", "{{ endpoints | length }}"),
+            ],
+        )
+        .await?;
+        let spec = test_spec(json!({"/a": {"get": {"operationId": "getA"}}}));
+        let out = temp_dir.path().join("out");
+        let config = Config::new("test", "openapi.json", out.to_string_lossy());
+
+        assert!(!manager.generate(&spec, &config, None).await?.skipped);
+        tokio::fs::write(out.join("index.txt"), "stale").await?;
+        let report = manager.generate(&spec, &config, None).await?;
+        assert!(report.skipped);
+        assert_eq!(
+            tokio::fs::read_to_string(out.join("index.txt")).await?,
+            "stale"
+        );
+
+        // Forcing, or changing the spec or options, regenerates
+        let force = TemplateOptions {
+            force: true,
+            ..Default::default()
+        };
+        assert!(!manager.generate(&spec, &config, Some(force)).await?.skipped);
+        assert_eq!(tokio::fs::read_to_string(out.join("index.txt")).await?, "1");
+        // A forced run records the same hash as an ordinary one
+        assert!(manager.generate(&spec, &config, None).await?.skipped);
+
+        // Partial regeneration skips the hooks, so it doesn't count
+        manager
+            .generate_operations(&spec, &config, None, &["getA".to_string()])
+            .await?;
+        assert!(!manager.generate(&spec, &config, None).await?.skipped);
+        let trimmed = TemplateOptions {
+            trim_blank_lines: true,
+            ..Default::default()
+        };
+        assert!(
+            !manager
+                .generate(&spec, &config, Some(trimmed))
+                .await?
+                .skipped
+        );
+        let changed = test_spec(json!({"/b": {"get": {"operationId": "getB"}}}));
+        assert!(!manager.generate(&changed, &config, None).await?.skipped);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_generate_operations_only_rerenders_changed() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let manager = setup_template(
+            temp_dir.path(),
+            &[
+                (
+                    "manifest.yaml",
+                    &test_manifest("  - source: op.tera\n    destination: \"{endpoint}.txt\"\n    for_each: operation\n  - source: index.tera\n    destination: index.txt\n"),
+                ),
+                ("op.tera", "{{ summary }}"),
+                ("index.tera", "{{ endpoints | length }}"),
+            ],
+        )
+        .await?;
+        let previous = test_spec(json!({
+            "/a": {"get": {"operationId": "getA", "summary": "first"}},
+            "/b": {"get": {"operationId": "getB", "summary": "second"}}
+        }));
+        let out = temp_dir.path().join("out");
+        let config = Config::new("test", "openapi.json", out.to_string_lossy());
+        manager.generate(&previous, &config, None).await?;
+
+        // Mark every output so re-rendered files can be told apart
+        for name in ["get_a.txt", "get_b.txt", "index.txt"] {
+            tokio::fs::write(out.join(name), "stale").await?;
+        }
+
+        let mut json = previous.json.clone();
+        json["paths"]["/a"]["get"]["summary"] = json!("updated");
+        let spec = OpenApiContext { json };
+        let changed = spec.changed_operations(&previous).await?.unwrap();
+        manager
+            .generate_operations(&spec, &config, None, &changed)
+            .await?;
+
+        assert_eq!(
+            tokio::fs::read_to_string(out.join("get_a.txt")).await?,
+            "updated"
+        );
+        assert_eq!(
+            tokio::fs::read_to_string(out.join("get_b.txt")).await?,
+            "stale"
+        );
+        // Single-file outputs list every operation, so they're rendered again
+        assert_eq!(tokio::fs::read_to_string(out.join("index.txt")).await?, "2");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_dereference_schema_refs_resolves_defs() -> Result<()> {
+        let spec = OpenApiContext {
+            json: json!({"$defs": {
+                "Widget": {"type": "object", "properties": {"owner": {"$ref": "#/$defs/Owner"}}},
+                "Owner": {"type": "string"}
+            }}),
+        };
+        let mut value = json!({"schema": {"$ref": "#/$defs/Widget"}});
+        TemplateManager::dereference_schema_refs(&mut value, &spec, DEFAULT_MAX_DEREF_DEPTH)?;
+        assert_eq!(
+            value,
+            json!({"schema": {"type": "object", "properties": {"owner": {"type": "string"}}}})
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_dereference_schema_refs_stops_at_max_depth() -> Result<()> {
+        let spec = OpenApiContext {
+            json: json!({"components": {"schemas": {
+                "A": {"type": "object", "properties": {"next": {"$ref": "#/components/schemas/B"}}},
+                "B": {"type": "object", "properties": {"next": {"$ref": "#/components/schemas/C"}}},
+                "C": {"type": "object", "properties": {"next": {"$ref": "#/components/schemas/A"}}}
+            }}}),
+        };
+        let mut value = json!({"$ref": "#/components/schemas/A"});
+        TemplateManager::dereference_schema_refs(&mut value, &spec, 2)?;
+        assert_eq!(
+            value,
+            json!({"type": "object", "properties": {"next": {
+                "type": "object", "properties": {"next": {"$ref": "#/components/schemas/C"}}
+            }}})
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_dereference_schema_refs_leaves_cycles_in_place() -> Result<()> {
+        let spec = OpenApiContext {
+            json: json!({"components": {"schemas": {
+                "Tree": {"type": "object", "properties": {"root": {"$ref": "#/components/schemas/Node"}}},
+                "Node": {"type": "object", "properties": {
+                    "left": {"$ref": "#/components/schemas/Node"},
+                    "right": {"$ref": "#/components/schemas/Node"}
+                }}
+            }}}),
+        };
+        let mut value = json!({"$ref": "#/components/schemas/Tree"});
+        TemplateManager::dereference_schema_refs(&mut value, &spec, DEFAULT_MAX_DEREF_DEPTH)?;
+        let node_ref = json!({"$ref": "#/components/schemas/Node"});
+        assert_eq!(
+            value,
+            json!({"type": "object", "properties": {"root": {
+                "type": "object", "properties": {"left": node_ref, "right": node_ref}
+            }}})
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_schema_refs_are_kept_when_requested() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let manager = setup_template(
+            temp_dir.path(),
+            &[
+                (
+                    "manifest.yaml",
+                    &test_manifest("  - source: op.tera\n    destination: \"{endpoint}.txt\"\n    for_each: operation\n"),
+                ),
+                ("op.tera", "{{ endpoint }}"),
+            ],
+        )
+        .await?;
+        let mut spec = test_spec(json!({
+            "/pets": {"get": {
+                "operationId": "listPets",
+                "responses": {"200": {"description": "ok", "content": {"application/json": {
+                    "schema": {"$ref": "#/components/schemas/Pet"}
+                }}}}
+            }}
+        }));
+        spec.json["components"] = json!({"schemas": {"Pet": {"type": "object"}}});
+
+        let out = temp_dir.path().join("kept");
+        let config = Config::new("test", "openapi.json", out.to_string_lossy());
+        let options = TemplateOptions {
+            keep_schema_refs: true,
+            ..Default::default()
+        };
+        manager.generate(&spec, &config, Some(options)).await?;
+        let schema: JsonValue = serde_json::from_str(
+            &tokio::fs::read_to_string(out.join("schemas/list_pets.json")).await?,
+        )?;
+        assert_eq!(
+            schema.pointer("/responses/200/content/application~1json/schema"),
+            Some(&json!({"$ref": "components.json#/components/schemas/Pet"}))
+        );
+        let components: JsonValue = serde_json::from_str(
+            &tokio::fs::read_to_string(out.join("schemas/components.json")).await?,
+        )?;
+        assert_eq!(
+            components,
+            json!({"components": {"schemas": {"Pet": {"type": "object"}}}})
+        );
+
+        // Nested schema files point back up to the components file
+        let out = temp_dir.path().join("nested");
+        let config = Config::new("test", "openapi.json", out.to_string_lossy());
+        let options = TemplateOptions {
+            keep_schema_refs: true,
+            schema_filename_pattern: Some("ops/{operation}.json".to_string()),
+            ..Default::default()
+        };
+        manager.generate(&spec, &config, Some(options)).await?;
+        let schema: JsonValue = serde_json::from_str(
+            &tokio::fs::read_to_string(out.join("schemas/ops/list_pets.json")).await?,
+        )?;
+        assert_eq!(
+            schema.pointer("/responses/200/content/application~1json/schema"),
+            Some(&json!({"$ref": "../components.json#/components/schemas/Pet"}))
+        );
+        assert!(out.join("schemas/components.json").exists());
+
+        // Dereferencing stays the default
+        let out = temp_dir.path().join("inlined");
+        let config = Config::new("test", "openapi.json", out.to_string_lossy());
+        manager.generate(&spec, &config, None).await?;
+        let schema: JsonValue = serde_json::from_str(
+            &tokio::fs::read_to_string(out.join("schemas/list_pets.json")).await?,
+        )?;
+        assert_eq!(
+            schema.pointer("/responses/200/content/application~1json/schema"),
+            Some(&json!({"type": "object"}))
+        );
+        assert!(!out.join("schemas/components.json").exists());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_schema_filename_pattern_nests_schema_files() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let manager = setup_template(
+            temp_dir.path(),
+            &[
+                (
+                    "manifest.yaml",
+                    &test_manifest("  - source: op.tera\n    destination: \"{endpoint}.txt\"\n    for_each: operation\n"),
+                ),
+                ("op.tera", "{{ schema_file }}"),
+            ],
+        )
+        .await?;
+        let spec = test_spec(json!({
+            "/pets": {
+                "get": {"operationId": "listPets", "tags": ["Pets"], "responses": {}},
+                "post": {"operationId": "createPet", "tags": ["Pets"], "responses": {}}
+            },
+            "/health": {"get": {"operationId": "getHealth", "responses": {}}}
+        }));
+
+        let out = temp_dir.path().join("nested");
+        let config = Config::new("test", "openapi.json", out.to_string_lossy());
+        let options = TemplateOptions {
+            schema_filename_pattern: Some("{tag}/{operation}.schema.json".to_string()),
+            ..Default::default()
+        };
+        manager.generate(&spec, &config, Some(options)).await?;
+        for schema_file in [
+            "pets/list_pets.schema.json",
+            "pets/create_pet.schema.json",
+            "default/get_health.schema.json",
+        ] {
+            assert!(
+                out.join("schemas").join(schema_file).is_file(),
+                "{}",
+                schema_file
+            );
+        }
+        assert!(!out.join("schemas/list_pets.json").exists());
+        assert_eq!(
+            tokio::fs::read_to_string(out.join("list_pets.txt")).await?,
+            "pets/list_pets.schema.json"
+        );
+
+        // Patterns can't escape `schemas/`
+        let out = temp_dir.path().join("escaped");
+        let config = Config::new("test", "openapi.json", out.to_string_lossy());
+        let options = TemplateOptions {
+            schema_filename_pattern: Some("../{operation}.json".to_string()),
+            ..Default::default()
+        };
+        let err = manager
+            .generate(&spec, &config, Some(options))
+            .await
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("not a relative path inside schemas/"));
+
+        // Every operation needs its own schema file
+        let options = TemplateOptions {
+            schema_filename_pattern: Some("{tag}.json".to_string()),
+            ..Default::default()
+        };
+        let err = manager
+            .generate(&spec, &config, Some(options))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("has no {operation}"), "{}", err);
+
+        // Tags never add path segments of their own
+        let spec = test_spec(json!({
+            "/pets": {"get": {"operationId": "listPets", "tags": ["pets/dogs"], "responses": {}}}
+        }));
+        let out = temp_dir.path().join("slashed");
+        let config = Config::new("test", "openapi.json", out.to_string_lossy());
+        let options = TemplateOptions {
+            schema_filename_pattern: Some("{tag}/{operation}.json".to_string()),
+            filename_case: FilenameCase::AsIs,
+            ..Default::default()
+        };
+        manager.generate(&spec, &config, Some(options)).await?;
+        assert!(out.join("schemas/pets_dogs/list_pets.json").is_file());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_handler_result_type_reaches_context() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let manager = setup_template(
+            temp_dir.path(),
+            &[
+                (
+                    "manifest.yaml",
+                    &test_manifest("  - source: handler.tera\n    destination: \"{endpoint}.rs\"\n    for_each: operation\n"),
+                ),
+                (
+                    "handler.tera",
+                    "fn handler() -> {{ handler_result_type }} // {{ handler_error_type }}",
+                ),
+            ],
+        )
+        .await?;
+        let spec = test_spec(json!({"/pets": {"get": {"operationId": "listPets"}}}));
+
+        let out = temp_dir.path().join("default");
+        let config = Config::new("test", "openapi.json", out.to_string_lossy());
+        manager.generate(&spec, &config, None).await?;
+        assert_eq!(
+            tokio::fs::read_to_string(out.join("list_pets.rs")).await?,
+            "fn handler() -> Result<CallToolResult, rmcp::Error> // rmcp::Error"
+        );
+
+        let out = temp_dir.path().join("custom");
+        let config = Config::new("test", "openapi.json", out.to_string_lossy());
+        let opts = TemplateOptions {
+            handler_error_type: Some("crate::ApiError".to_string()),
+            handler_result_type: Some("ApiResult<CallToolResult>".to_string()),
+            ..Default::default()
+        };
+        manager.generate(&spec, &config, Some(opts)).await?;
+        assert_eq!(
+            tokio::fs::read_to_string(out.join("list_pets.rs")).await?,
+            "fn handler() -> ApiResult<CallToolResult> // crate::ApiError"
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_custom_handler_error_type_is_converted() -> Result<()> {
+        let manager = builtin_manager().await?;
+        let spec = test_spec(json!({
+            "/pets": {"get": {"operationId": "listPets", "tags": ["pets"], "responses": {}}}
+        }));
+        let temp_dir = tempfile::tempdir()?;
+
+        let out = temp_dir.path().join("custom");
+        let config = Config::new("test", "openapi.json", out.to_string_lossy());
+        let opts = TemplateOptions {
+            handler_error_type: Some("crate::ApiError".to_string()),
+            ..Default::default()
+        };
+        manager.generate(&spec, &config, Some(opts)).await?;
+        let handler = tokio::fs::read_to_string(out.join("src/handlers/list_pets.rs")).await?;
+        assert!(handler.contains("-> Result<CallToolResult, crate::ApiError> {"));
+        assert!(
+            handler.contains("resp.and_then(|r| r.into_call_tool_result()).map_err(Into::into)")
+        );
+        // The MCP tool still returns `rmcp::Error`
+        let handlers_mod = tokio::fs::read_to_string(out.join("src/handlers/mod.rs")).await?;
+        assert!(handlers_mod.contains("-> Result<CallToolResult, rmcp::Error> {"));
+        assert!(handlers_mod.contains("response.map_err(Into::into)"));
+
+        // No conversions by default
+        let out = temp_dir.path().join("default");
+        let config = Config::new("test", "openapi.json", out.to_string_lossy());
+        manager.generate(&spec, &config, None).await?;
+        let handler = tokio::fs::read_to_string(out.join("src/handlers/list_pets.rs")).await?;
+        assert!(!handler.contains("map_err(Into::into)"));
+        let handlers_mod = tokio::fs::read_to_string(out.join("src/handlers/mod.rs")).await?;
+        assert!(!handlers_mod.contains("map_err(Into::into)"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_trim_blank_lines() {
+        assert_eq!(trim_blank_lines("a  \n\n\n\n\nb\t\n"), "a\n\nb\n");
+        assert_eq!(trim_blank_lines("a\n\n\nb\n"), "a\n\n\nb\n");
+    }
+
+    #[tokio::test]
+    async fn test_trim_blank_lines_option() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let manager = setup_template(
+            temp_dir.path(),
+            &[
+                (
+                    "manifest.yaml",
+                    &test_manifest("  - source: main.tera\n    destination: main.rs\n"),
+                ),
+                ("main.tera", "fn a() {}   \n\n\n\n\nfn b() {}\n"),
+            ],
+        )
+        .await?;
+        let spec = test_spec(json!({}));
+
+        let out = temp_dir.path().join("raw");
+        let config = Config::new("test", "openapi.json", out.to_string_lossy());
+        manager.generate(&spec, &config, None).await?;
+        assert_eq!(
+            tokio::fs::read_to_string(out.join("main.rs")).await?,
+            "fn a() {}   \n\n\n\n\nfn b() {}\n"
+        );
+
+        let out = temp_dir.path().join("trimmed");
+        let config = Config::new("test", "openapi.json", out.to_string_lossy());
+        let opts = TemplateOptions {
+            trim_blank_lines: true,
+            ..Default::default()
+        };
+        manager.generate(&spec, &config, Some(opts)).await?;
+        assert_eq!(
+            tokio::fs::read_to_string(out.join("main.rs")).await?,
+            "fn a() {}\n\nfn b() {}\n"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_comment_header() {
+        let header = "Copyright Example\n\nSPDX-License-Identifier: MIT\n";
+        assert_eq!(
+            comment_header(header, Path::new("src/main.rs")).unwrap(),
+            "// Copyright Example\n//\n// SPDX-License-Identifier: MIT\n"
+        );
+        assert_eq!(
+            comment_header("MIT", Path::new("Cargo.toml")).unwrap(),
+            "# MIT\n"
+        );
+        assert_eq!(
+            comment_header("MIT", Path::new("Dockerfile")).unwrap(),
+            "# MIT\n"
+        );
+        assert!(comment_header("MIT", Path::new("schemas/pet.json")).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_file_header_prepended_to_sources() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let manager = setup_template(
+            temp_dir.path(),
+            &[
+                (
+                    "manifest.yaml",
+                    &test_manifest("  - source: main.tera\n    destination: src/main.rs\n  - source: data.tera\n    destination: data.json\n"),
+                ),
+                ("main.tera", "fn main() {}\n"),
+                ("data.tera", "{}"),
+            ],
+        )
+        .await?;
+        let spec = test_spec(json!({}));
+        let out = temp_dir.path().join("out");
+        let config = Config::new("test", "openapi.json", out.to_string_lossy());
+        let opts = TemplateOptions {
+            file_header: Some("Licensed under MIT".to_string()),
+            ..Default::default()
+        };
+        manager.generate(&spec, &config, Some(opts)).await?;
+
+        assert_eq!(
+            tokio::fs::read_to_string(out.join("src/main.rs")).await?,
+            "// Licensed under MIT\nfn main() {}\n"
+        );
+        assert_eq!(
+            tokio::fs::read_to_string(out.join("data.json")).await?,
+            "{}"
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_project_name_from_digit_leading_title() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let manager = setup_template(
+            temp_dir.path(),
+            &[
+                (
+                    "manifest.yaml",
+                    &test_manifest("  - source: name.tera\n    destination: name.txt\n"),
+                ),
+                ("name.tera", "{{ project_name }}"),
+            ],
+        )
+        .await?;
+        let mut spec = test_spec(json!({}));
+        spec.json["info"]["title"] = json!("2048 Game API");
+
+        let out = temp_dir.path().join("configured");
+        let config = Config::new("game-server", "openapi.json", out.to_string_lossy());
+        manager.generate(&spec, &config, None).await?;
+        assert_eq!(
+            tokio::fs::read_to_string(out.join("name.txt")).await?,
+            "game_server"
+        );
+
+        // An empty configured name falls back to the spec title when it's valid
+        let out = temp_dir.path().join("title");
+        let config = Config::new("", "openapi.json", out.to_string_lossy());
+        manager
+            .generate(&test_spec(json!({})), &config, None)
+            .await?;
+        assert_eq!(
+            tokio::fs::read_to_string(out.join("name.txt")).await?,
+            "test_api"
+        );
+
+        let out = temp_dir.path().join("default");
+        let config = Config::new("", "openapi.json", out.to_string_lossy());
+        manager.generate(&spec, &config, None).await?;
+        assert_eq!(
+            tokio::fs::read_to_string(out.join("name.txt")).await?,
+            DEFAULT_PROJECT_NAME
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_for_each_tag_renders_one_file_per_tag() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let manager = setup_template(
+            temp_dir.path(),
+            &[
+                (
+                    "manifest.yaml",
+                    &test_manifest("  - source: tag.tera\n    destination: \"handlers/{tag}.rs\"\n    for_each: tag\n"),
+                ),
+                (
+                    "tag.tera",
+                    "{{ tag }}:{% for op in operations %} {{ op.fn_name }}{% endfor %}",
+                ),
+            ],
+        )
+        .await?;
+        let spec = test_spec(json!({
+            "/pets": {
+                "get": {"operationId": "listPets", "tags": ["pets"]},
+                "post": {"operationId": "addPet", "tags": ["pets"]}
+            },
+            "/store": {"get": {"operationId": "getInventory", "tags": ["store"]}},
+            "/health": {"get": {"operationId": "health"}},
+            "/ping": {"get": {"operationId": "ping", "tags": ["default"]}}
+        }));
+        let out = temp_dir.path().join("out");
+        let config = Config::new("test", "openapi.json", out.to_string_lossy());
+        manager.generate(&spec, &config, None).await?;
+
+        assert_eq!(
+            tokio::fs::read_to_string(out.join("handlers/pets.rs")).await?,
+            "pets: add_pet list_pets"
+        );
+        assert_eq!(
+            tokio::fs::read_to_string(out.join("handlers/store.rs")).await?,
+            "store: get_inventory"
+        );
+        // Untagged operations don't join the operations tagged `default`
+        assert_eq!(
+            tokio::fs::read_to_string(out.join("handlers/default.rs")).await?,
+            "default: ping"
+        );
+        assert_eq!(
+            tokio::fs::read_to_string(out.join("handlers/default_2.rs")).await?,
+            "default_2: health"
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_for_each_all_renders_every_handler_into_one_file() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let manager = setup_template(
+            temp_dir.path(),
+            &[
+                (
+                    "manifest.yaml",
+                    &test_manifest("  - source: handlers.tera\n    destination: src/handlers.rs\n    for_each: all\n"),
+                ),
+                (
+                    "handlers.tera",
+                    "{% for ep in endpoints %}pub async fn {{ ep.fn_name }}() {}\n{% endfor %}",
+                ),
+            ],
+        )
+        .await?;
+        let spec = test_spec(json!({
+            "/pets": {
+                "get": {"operationId": "listPets"},
+                "post": {"operationId": "addPet"}
+            },
+            "/admin": {"get": {"operationId": "admin"}}
+        }));
+        let out = temp_dir.path().join("out");
+        let config = Config::new("test", "openapi.json", out.to_string_lossy());
+        let opts = TemplateOptions {
+            exclude_paths: vec!["/admin".to_string()],
+            ..Default::default()
+        };
+        manager.generate(&spec, &config, Some(opts.clone())).await?;
+
+        let expected = "pub async fn add_pet() {}\npub async fn list_pets() {}\n";
+        let handlers = out.join("src/handlers.rs");
+        assert_eq!(tokio::fs::read_to_string(&handlers).await?, expected);
+
+        // Regenerating one operation keeps every handler in the file
+        tokio::fs::remove_file(&handlers).await?;
+        manager
+            .generate_operations(&spec, &config, Some(opts), &["addPet".to_string()])
+            .await?;
+        assert_eq!(tokio::fs::read_to_string(&handlers).await?, expected);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_exclude_paths_skips_matching_operations() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let manager = setup_template(
+            temp_dir.path(),
+            &[
+                (
+                    "manifest.yaml",
+                    &test_manifest("  - source: op.tera\n    destination: \"{endpoint}.txt\"\n    for_each: operation\n"),
+                ),
+                ("op.tera", "{{ path }}"),
+            ],
+        )
+        .await?;
+        let spec = test_spec(json!({
+            "/admin/users": {"get": {"operationId": "listUsers"}},
+            "/admin/users/{id}": {"get": {"operationId": "getUser"}},
+            "/pets": {"get": {"operationId": "listPets"}}
+        }));
+        let out = temp_dir.path().join("out");
+        let config = Config::new("test", "openapi.json", out.to_string_lossy());
+        let opts = TemplateOptions {
+            exclude_paths: vec!["/admin/*".to_string()],
+            ..Default::default()
+        };
+        manager.generate(&spec, &config, Some(opts)).await?;
+
+        assert!(!out.join("list_users.txt").exists());
+        // `*` doesn't cross segments, so nested admin paths need `/admin/**`
+        assert!(out.join("get_user.txt").exists());
+        assert!(out.join("list_pets.txt").exists());
+
+        let out = temp_dir.path().join("included");
+        let config = Config::new("test", "openapi.json", out.to_string_lossy());
+        let opts = TemplateOptions {
+            include_paths: vec!["/admin/**".to_string()],
+            exclude_paths: vec!["/admin/users".to_string()],
+            ..Default::default()
+        };
+        manager.generate(&spec, &config, Some(opts)).await?;
+        assert!(!out.join("list_users.txt").exists());
+        assert!(out.join("get_user.txt").exists());
+        assert!(!out.join("list_pets.txt").exists());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_append_only_rewrites_only_generated_regions() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let manager = setup_template(
+            temp_dir.path(),
+            &[
+                (
+                    "manifest.yaml",
+                    &test_manifest("  - source: op.tera\n    destination: \"{endpoint}.rs\"\n    for_each: operation\n  - source: mod.tera\n    destination: mod.rs\n"),
+                ),
+                ("op.tera", "// {{ summary }}\n"),
+                (
+                    "mod.tera",
+                    "// header\n// AGENTERRA:BEGIN modules\n{% for ep in endpoints %}pub mod {{ ep.endpoint }};\n{% endfor %}// AGENTERRA:END modules\n\nfn main() {}\n",
+                ),
+            ],
+        )
+        .await?;
+        let out = temp_dir.path().join("out");
+        let config = Config::new("test", "openapi.json", out.to_string_lossy());
+        let opts = TemplateOptions {
+            append_only: true,
+            ..Default::default()
+        };
+        let spec = test_spec(json!({
+            "/a": {"get": {"operationId": "getA", "summary": "first"}}
+        }));
+        manager.generate(&spec, &config, Some(opts.clone())).await?;
+        assert_eq!(
+            tokio::fs::read_to_string(out.join("mod.rs")).await?,
+            "// header\n// AGENTERRA:BEGIN modules\npub mod get_a;\n// AGENTERRA:END modules\n\nfn main() {}\n"
+        );
+
+        // Hand edits outside the generated region and to an existing handler
+        tokio::fs::write(
+            out.join("mod.rs"),
+            "// my header\n// AGENTERRA:BEGIN modules\npub mod get_a;\n// AGENTERRA:END modules\n\nfn main() { run(); }\n",
+        )
+        .await?;
+        tokio::fs::write(out.join("get_a.rs"), "// edited\n").await?;
+
+        let spec = test_spec(json!({
+            "/a": {"get": {"operationId": "getA", "summary": "changed"}},
+            "/b": {"get": {"operationId": "getB", "summary": "second"}}
+        }));
+        manager.generate(&spec, &config, Some(opts.clone())).await?;
+
+        assert_eq!(
+            tokio::fs::read_to_string(out.join("mod.rs")).await?,
+            "// my header\n// AGENTERRA:BEGIN modules\npub mod get_a;\npub mod get_b;\n// AGENTERRA:END modules\n\nfn main() { run(); }\n"
+        );
+        assert_eq!(
+            tokio::fs::read_to_string(out.join("get_a.rs")).await?,
+            "// edited\n"
+        );
+        assert_eq!(
+            tokio::fs::read_to_string(out.join("get_b.rs")).await?,
+            "// second\n"
+        );
+
+        // A region that is never closed leaves the file alone
+        let unclosed =
+            "// my header\n// AGENTERRA:BEGIN modules\npub mod get_a;\n\nfn main() { run(); }\n";
+        tokio::fs::write(out.join("mod.rs"), unclosed).await?;
+        let spec = test_spec(json!({
+            "/c": {"get": {"operationId": "getC", "summary": "third"}}
+        }));
+        let err = manager
+            .generate(&spec, &config, Some(opts))
+            .await
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("`// AGENTERRA:BEGIN modules` has no matching `// AGENTERRA:END modules`"));
+        assert_eq!(
+            tokio::fs::read_to_string(out.join("mod.rs")).await?,
+            unclosed
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_emit_mcp_descriptor() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let manager = setup_template(
+            temp_dir.path(),
+            &[(
+                "manifest.yaml",
+                "name: t\ndescription: t\nversion: \"0.1.0\"\nlanguage: rust\nfiles: []\n",
+            )],
+        )
+        .await?;
+        let out = temp_dir.path().join("out");
+        let config = Config::new("test", "openapi.json", out.to_string_lossy());
+        let spec = test_spec(json!({
+            "/pets": {
+                "get": {"operationId": "listPets", "summary": "List pets"},
+                "post": {"operationId": "type", "summary": "Create a pet"}
+            }
+        }));
+
+        manager.generate(&spec, &config, None).await?;
+        assert!(!out.join(MCP_DESCRIPTOR_FILE).exists());
+
+        let opts = TemplateOptions {
+            emit_mcp_descriptor: true,
+            ..Default::default()
+        };
+        manager.generate(&spec, &config, Some(opts)).await?;
+        let descriptor: JsonValue =
+            serde_json::from_str(&tokio::fs::read_to_string(out.join("mcp.json")).await?)?;
+        assert_eq!(descriptor["name"], "Test API");
+        assert_eq!(descriptor["version"], "1.0.0");
+        let tools = descriptor["tools"].as_array().unwrap();
+        assert_eq!(tools.len(), 2);
+        assert!(tools
+            .iter()
+            .any(|tool| tool["name"] == "list_pets" && tool["description"] == "List pets"));
+        // Named after the escaped handler function
+        assert!(tools.iter().any(|tool| tool["name"] == "r#type"));
+        // Written like any other output, so it's listed in the generation manifest
+        let manifest = GenerationManifest::load(&out).await?.unwrap();
+        assert!(manifest.files.keys().any(|path| path.ends_with("mcp.json")));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_max_concurrency_one_renders_every_operation() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let manager = setup_template(
+            temp_dir.path(),
+            &[
+                (
+                    "manifest.yaml",
+                    &test_manifest("  - source: op.tera\n    destination: \"{endpoint}.txt\"\n    for_each: operation\n"),
+                ),
+                ("op.tera", "{{ summary }}"),
+            ],
+        )
+        .await?;
+        let out = temp_dir.path().join("out");
+        let config = Config::new("test", "openapi.json", out.to_string_lossy());
+        let paths: Map<String, JsonValue> = (0..64)
+            .map(|i| {
+                (
+                    format!("/items{}", i),
+                    json!({"get": {"operationId": format!("getItem{}", i), "summary": format!("item {}", i)}}),
+                )
+            })
+            .collect();
+        let opts = TemplateOptions {
+            max_concurrency: Some(1),
+            ..Default::default()
+        };
+        manager
+            .generate(&test_spec(JsonValue::Object(paths)), &config, Some(opts))
+            .await?;
+
+        for i in 0..64 {
+            assert_eq!(
+                tokio::fs::read_to_string(out.join(format!("get_item{}.txt", i))).await?,
+                format!("item {}", i)
+            );
+            assert!(out.join(format!("schemas/get_item{}.json", i)).is_file());
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_disk_output_leaves_no_temp_files() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let path = temp_dir.path().join("src/large.rs");
+        let content = "// generated\n".repeat(512 * 1024);
+
+        let mut output = Output::Disk(BTreeMap::new());
+        output.write(&path, "old".to_string()).await?;
+        output.write(&path, content.clone()).await?;
+
+        assert_eq!(tokio::fs::read_to_string(&path).await?, content);
+        let entries: Vec<_> = std::fs::read_dir(temp_dir.path().join("src"))?
+            .map(|entry| entry.map(|e| e.file_name()))
+            .collect::<std::io::Result<_>>()?;
+        assert_eq!(entries, vec![std::ffi::OsString::from("large.rs")]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_strip_vendor_extensions() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let manager = setup_template(
+            temp_dir.path(),
+            &[
+                (
+                    "manifest.yaml",
+                    &test_manifest("  - source: op.tera\n    destination: \"{endpoint}.txt\"\n    for_each: operation\n"),
+                ),
+                ("op.tera", ""),
+            ],
+        )
+        .await?;
+        let spec = test_spec(json!({
+            "/pets": {"get": {
+                "operationId": "listPets",
+                "tags": ["pets"],
+                "x-internal-owner": "team-a",
+                "responses": {"200": {
+                    "description": "ok",
+                    "x-cache": true,
+                    "headers": {"x-request-id": {"schema": {"type": "string", "x-max": 1}}},
+                    "links": {"x-next": {"operationId": "listPets"}},
+                    "content": {"application/json": {"schema": {
+                        "type": "object",
+                        "x-rust-type": "Pets",
+                        "properties": {"x-count": {"type": "integer", "x-format": "u32"}}
+                    }}}
+                }}
+            }}
+        }));
+
+        let mut schemas = Vec::new();
+        for strip in [false, true] {
+            let out = temp_dir.path().join(format!("strip-{}", strip));
+            let config = Config::new("test", "openapi.json", out.to_string_lossy());
+            let opts = TemplateOptions {
+                strip_vendor_extensions: strip,
+                ..Default::default()
+            };
+            manager.generate(&spec, &config, Some(opts)).await?;
+            schemas.push(tokio::fs::read_to_string(out.join("schemas/list_pets.json")).await?);
+        }
+
+        assert!(schemas[0].contains("x-internal-owner"));
+        assert!(schemas[0].contains("x-rust-type"));
+        let stripped = &schemas[1];
+        for key in [
+            "x-internal-owner",
+            "x-cache",
+            "x-rust-type",
+            "x-format",
+            "x-max",
+        ] {
+            assert!(!stripped.contains(key), "{} left in {}", key, stripped);
+        }
+        // Properties, headers and links that merely start with `x-` are not extensions
+        for name in ["\"x-count\"", "\"x-request-id\"", "\"x-next\""] {
+            assert!(
+                stripped.contains(name),
+                "{} missing from {}",
+                name,
+                stripped
+            );
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_minify_schemas() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let manager = setup_template(
+            temp_dir.path(),
+            &[
+                (
+                    "manifest.yaml",
+                    &test_manifest("  - source: op.tera\n    destination: \"{endpoint}.txt\"\n    for_each: operation\n"),
+                ),
+                ("op.tera", ""),
+            ],
+        )
+        .await?;
+        let spec = test_spec(json!({
+            "/pets": {"get": {
+                "operationId": "listPets",
+                "description": "List every pet",
+                "responses": {"200": {
+                    "description": "The pets",
+                    "content": {"application/json": {"schema": {
+                        "type": "object",
+                        "title": "Pets",
+                        "example": {"description": "Fluffy"},
+                        "properties": {"description": {
+                            "type": "string",
+                            "description": "What the pet is like"
+                        }}
+                    }}}
+                }}
+            }}
+        }));
+
+        let mut schemas = Vec::new();
+        for minify in [false, true] {
+            let out = temp_dir.path().join(format!("minify-{}", minify));
+            let config = Config::new("test", "openapi.json", out.to_string_lossy());
+            let opts = TemplateOptions {
+                minify_schemas: minify,
+                ..Default::default()
+            };
+            manager.generate(&spec, &config, Some(opts)).await?;
+            let schema = tokio::fs::read_to_string(out.join("schemas/list_pets.json")).await?;
+            schemas.push(serde_json::from_str::<JsonValue>(&schema)?);
+        }
+
+        let response = "/responses/200/content/application~1json/schema";
+        assert_eq!(schemas[0]["description"], "List every pet");
+        assert_eq!(schemas[0].pointer(response).unwrap()["title"], "Pets");
+        let minified = &schemas[1];
+        assert!(minified.get("description").is_none());
+        assert!(minified.pointer("/responses/200/description").is_none());
+        let schema = minified.pointer(response).unwrap();
+        assert_eq!(
+            schema,
+            &json!({"type": "object", "properties": {"description": {"type": "string"}}})
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_schema_json_style() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let manager = setup_template(
+            temp_dir.path(),
+            &[
+                (
+                    "manifest.yaml",
+                    &test_manifest("  - source: op.tera\n    destination: \"{endpoint}.txt\"\n    for_each: operation\n"),
+                ),
+                ("op.tera", ""),
+            ],
+        )
+        .await?;
+        let spec = test_spec(json!({
+            "/pets": {"get": {"operationId": "listPets", "summary": "List", "tags": ["pets"]}}
+        }));
+
+        let mut outputs = Vec::new();
+        for style in [SchemaJsonStyle::Compact, SchemaJsonStyle::Pretty] {
+            let out = temp_dir.path().join(format!("{:?}", style));
+            let config = Config::new("test", "openapi.json", out.to_string_lossy());
+            let opts = TemplateOptions {
+                schema_json_style: style,
+                ..Default::default()
+            };
+            manager.generate(&spec, &config, Some(opts)).await?;
+            outputs.push(tokio::fs::read_to_string(out.join("schemas/list_pets.json")).await?);
+        }
+        let (compact, pretty) = (&outputs[0], &outputs[1]);
+
+        assert!(!compact.contains('\n'));
+        assert!(pretty.contains('\n'));
+        assert_eq!(
+            serde_json::from_str::<JsonValue>(compact)?,
+            serde_json::from_str::<JsonValue>(pretty)?
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_line_ending_crlf() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let manager = setup_template(
+            temp_dir.path(),
+            &[
+                (
+                    "manifest.yaml",
+                    &test_manifest("  - source: main.tera\n    destination: main.txt\n  - source: op.tera\n    destination: \"{endpoint}.txt\"\n    for_each: operation\n"),
+                ),
+                ("main.tera", "first\nsecond\r\nthird\n"),
+                ("op.tera", "{{ endpoint }}\ndone\n"),
+            ],
+        )
+        .await?;
+        let spec = test_spec(json!({
+            "/pets": {"get": {"operationId": "listPets", "summary": "List", "tags": ["pets"]}}
+        }));
+        let out = temp_dir.path().join("out");
+        let config = Config::new("test", "openapi.json", out.to_string_lossy());
+        let opts = TemplateOptions {
+            line_ending: LineEnding::CrLf,
+            ..Default::default()
+        };
+
+        manager.generate(&spec, &config, Some(opts)).await?;
+
+        assert_eq!(
+            tokio::fs::read_to_string(out.join("main.txt")).await?,
+            "first\r\nsecond\r\nthird\r\n"
+        );
+        assert_eq!(
+            tokio::fs::read_to_string(out.join("list_pets.txt")).await?,
+            "list_pets\r\ndone\r\n"
+        );
+        assert_eq!(LineEnding::Lf.apply("a\r\nb\n"), "a\nb\n");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_post_generate_once_runs_only_on_first_generation() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let manager = setup_template(
+            temp_dir.path(),
+            &[
+                (
+                    "manifest.yaml",
+                    &test_manifest("  - source: main.tera\n    destination: main.txt\nhooks:\n  post_generate_once: mkdir first-run\n"),
+                ),
+                ("main.tera", "main"),
+            ],
+        )
+        .await?;
+        let spec = test_spec(json!({}));
+        let out = temp_dir.path().join("out");
+        let config = Config::new("test", "openapi.json", out.to_string_lossy());
+
+        manager.generate(&spec, &config, None).await?;
+        assert!(out.join("first-run").is_dir());
+        assert!(GenerationManifest::load(&out).await?.is_some());
+
+        tokio::fs::remove_dir(out.join("first-run")).await?;
+        manager.generate(&spec, &config, None).await?;
+        assert!(!out.join("first-run").exists());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_generation_report_warns_about_synthesized_operation_id() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let manager = setup_template(
+            temp_dir.path(),
+            &[
+                (
+                    "manifest.yaml",
+                    &test_manifest("  - source: main.tera\n    destination: main.txt\n"),
+                ),
+                ("main.tera", "main"),
+            ],
+        )
+        .await?;
+        let spec = test_spec(json!({
+            "/pets": {"get": {"operationId": "listPets", "responses": {}}},
+            "/pets/{id}": {"get": {"responses": {}}}
+        }));
+        let out = temp_dir.path().join("out");
+        let config = Config::new("test", "openapi.json", out.to_string_lossy());
+
+        let report = manager.generate(&spec, &config, None).await?;
+        let synthesized: Vec<_> = report
+            .warnings
+            .iter()
+            .filter(|w| w.kind == WarningKind::SynthesizedOperationId)
+            .collect();
+        assert_eq!(synthesized.len(), 1);
+        assert!(synthesized[0].message.contains("GET /pets/{id}"));
+        assert!(synthesized[0].message.contains("'get_pets_id'"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_language_mismatch_warns() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let manager = setup_template(
+            temp_dir.path(),
+            &[
+                (
+                    "manifest.yaml",
+                    "name: py\ndescription: t\nversion: \"0.1.0\"\nlanguage: python\nfiles:\n  - source: main.tera\n    destination: main.py\n",
+                ),
+                ("main.tera", "main"),
+            ],
+        )
+        .await?;
+        let warnings = manager.warnings();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].kind, WarningKind::LanguageMismatch);
+        assert!(warnings[0].message.contains("python"));
+        assert!(warnings[0].message.contains("rust_axum"));
+
+        let spec = test_spec(json!({}));
+        let out = temp_dir.path().join("out");
+        let config = Config::new("test", "openapi.json", out.to_string_lossy());
+        let report = manager.generate(&spec, &config, None).await?;
+        assert_eq!(report.warnings, warnings);
+
+        let strict = TemplateOptions {
+            strict: true,
+            ..Default::default()
+        };
+        let err = manager
+            .generate(&spec, &config, Some(strict))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("targets python"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_operation_context() -> Result<()> {
+        let manager = builtin_manager().await?;
+        let spec = test_spec(json!({
+            "/pets/{petId}": {
+                "parameters": [{"name": "petId", "in": "path", "required": true,
+                                "schema": {"type": "integer"}}],
+                "get": {"operationId": "getPetById", "tags": ["pets"],
+                        "x-foo": {"bar": 1}}
+            }
+        }));
+        let config = Config::new("test", "openapi.json", "out");
+
+        let context = manager
+            .operation_context(&spec, "getPetById", &config, None)
+            .await?;
+        assert_eq!(context["fn_name"], "get_pet_by_id");
+        assert_eq!(context["path_params"], json!(["petId"]));
+        assert_eq!(context["vendor_extensions"], json!({"x-foo": {"bar": 1}}));
+        assert_eq!(context["project_name"], "test");
+
+        let err = manager
+            .operation_context(&spec, "missing", &config, None)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("missing"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_partials_are_not_listed_as_templates() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let manager = setup_template(
+            temp_dir.path(),
+            &[
+                (
+                    "manifest.yaml",
+                    &test_manifest("  - source: main.tera\n    destination: src/main.rs\n  - source: main.tera\n    destination: src/lib.rs\n"),
+                ),
+                (
+                    "main.tera",
+                    "{% import \"macros.tera\" as m %}{{ m::greet(name=\"x\") }}",
+                ),
+                (
+                    "macros.tera",
+                    "{% macro greet(name) %}hello {{ name }}{% endmacro greet %}",
+                ),
+            ],
+        )
+        .await?;
+
+        assert_eq!(
+            manager.list_templates(),
+            vec![
+                ("main.tera".to_string(), "src/main.rs".to_string()),
+                ("main.tera".to_string(), "src/lib.rs".to_string())
+            ]
+        );
+        assert_eq!(manager.template_names(), vec!["main.tera".to_string()]);
+        assert_eq!(manager.partials(), vec!["macros.tera".to_string()]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_tag_descriptions_reach_context() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let manager = setup_template(
+            temp_dir.path(),
+            &[
+                (
+                    "manifest.yaml",
+                    &test_manifest("  - source: lib.tera\n    destination: lib.txt\n  - source: tag.tera\n    destination: \"{tag}.txt\"\n    for_each: tag\n"),
+                ),
+                ("lib.tera", "{{ tag_descriptions.pets }}"),
+                ("tag.tera", "//! {{ tag_description }}"),
+            ],
+        )
+        .await?;
+        let mut spec = test_spec(json!({
+            "/pets": {"get": {"operationId": "listPets", "tags": ["pets"]}}
+        }));
+        spec.json["tags"] = json!([
+            {"name": "pets", "description": "Everything about your pets"},
+            {"name": "store"}
+        ]);
+        let out = temp_dir.path().join("out");
+        let config = Config::new("test", "openapi.json", out.to_string_lossy());
+        manager.generate(&spec, &config, None).await?;
+
+        assert_eq!(
+            tokio::fs::read_to_string(out.join("lib.txt")).await?,
+            "Everything about your pets"
+        );
+        assert_eq!(
+            tokio::fs::read_to_string(out.join("pets.txt")).await?,
+            "//! Everything about your pets"
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_resolve_schema_function() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let manager = setup_template(
+            temp_dir.path(),
+            &[
+                (
+                    "manifest.yaml",
+                    &test_manifest("  - source: lib.tera\n    destination: lib.txt\n"),
+                ),
+                (
+                    "lib.tera",
+                    "{% set pet = resolve_schema(name=\"Pet\") %}{% for name, _ in pet.properties %}{{ name }} {% endfor %}\n{% set by_ref = resolve_schema(name=\"#/components/schemas/Pet\") %}{{ by_ref.required | join(sep=\",\") }}",
+                ),
+            ],
+        )
+        .await?;
+        let mut spec = test_spec(json!({}));
+        spec.json["components"] = json!({"schemas": {"Pet": {
+            "type": "object",
+            "required": ["id"],
+            "properties": {"id": {"type": "integer"}, "name": {"type": "string"}}
+        }}});
+        let out = temp_dir.path().join("out");
+        let config = Config::new("test", "openapi.json", out.to_string_lossy());
+        manager.generate(&spec, &config, None).await?;
+        assert_eq!(
+            tokio::fs::read_to_string(out.join("lib.txt")).await?,
+            "id name \nid"
+        );
+
+        // Partial renders see the spec too
+        let files = manager.render_one("lib.tera", &spec, &config, None).await?;
+        assert_eq!(
+            files,
+            [(PathBuf::from("lib.txt"), "id name \nid".to_string())]
+        );
+
+        // Each call resolves against its own spec, not one registered earlier
+        spec.json["components"]["schemas"] = json!({});
+        assert!(manager.generate(&spec, &config, None).await.is_err());
+        assert!(manager
+            .render_one("lib.tera", &spec, &config, None)
+            .await
+            .is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_spec_preprocessor_edits_spec_before_parsing() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let manager = setup_template(
+            temp_dir.path(),
+            &[
+                (
+                    "manifest.yaml",
+                    &test_manifest("  - source: lib.tera\n    destination: lib.txt\n"),
+                ),
+                (
+                    "lib.tera",
+                    "{{ base_api_url }}{% for e in endpoints %} {{ e.endpoint }}{% endfor %}",
+                ),
+            ],
+        )
+        .await?;
+        let mut spec = test_spec(json!({
+            "/pets": {"get": {"operationId": "listPets", "responses": {}}},
+            "/admin": {"get": {"operationId": "admin", "responses": {}}}
+        }));
+        spec.json.as_object_mut().unwrap().remove("servers");
+        let out = temp_dir.path().join("out");
+        let config = Config::new("test", "openapi.json", out.to_string_lossy());
+
+        assert!(manager.generate(&spec, &config, None).await.is_err());
+        let opts = TemplateOptions {
+            spec_preprocessor: Some(SpecPreprocessor::new(|spec| {
+                spec["servers"] = json!([{"url": "https://patched.example.com"}]);
+                spec["paths"].as_object_mut().unwrap().remove("/admin");
+                Ok(())
+            })),
+            ..Default::default()
+        };
+        manager.generate(&spec, &config, Some(opts)).await?;
+        assert_eq!(
+            tokio::fs::read_to_string(out.join("lib.txt")).await?,
+            "https://patched.example.com list_pets"
+        );
+        // The caller's spec is left alone
+        assert!(spec.json.get("servers").is_none());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_server_index_selects_server() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let manager = setup_template(
+            temp_dir.path(),
+            &[
+                (
+                    "manifest.yaml",
+                    &test_manifest("  - source: lib.tera\n    destination: lib.txt\n"),
+                ),
+                ("lib.tera", "{{ base_api_url }}"),
+            ],
+        )
+        .await?;
+        let mut spec = test_spec(json!({}));
+        spec.json["servers"] = json!([
+            {"url": "https://prod.example.com"},
+            {"url": "https://staging.example.com"},
+            {"url": "https://sandbox.example.com/v2"}
+        ]);
+        let out = temp_dir.path().join("out");
+        let config = Config::new("test", "openapi.json", out.to_string_lossy());
+        let opts = |index| TemplateOptions {
+            server_index: Some(index),
+            ..Default::default()
+        };
+
+        manager.generate(&spec, &config, None).await?;
+        assert_eq!(
+            tokio::fs::read_to_string(out.join("lib.txt")).await?,
+            "https://prod.example.com"
+        );
+        manager.generate(&spec, &config, Some(opts(2))).await?;
+        assert_eq!(
+            tokio::fs::read_to_string(out.join("lib.txt")).await?,
+            "https://sandbox.example.com/v2"
+        );
+        let err = manager
+            .generate(&spec, &config, Some(opts(3)))
+            .await
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Server index 3 is out of range: the spec declares 3 server(s)"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_emit_operation_fragments() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let manager = setup_template(
+            temp_dir.path(),
+            &[
+                (
+                    "manifest.yaml",
+                    &test_manifest("  - source: lib.tera\n    destination: lib.txt\n"),
+                ),
+                ("lib.tera", ""),
+            ],
+        )
+        .await?;
+        let mut spec = test_spec(json!({
+            "/pets/{petId}": {
+                "parameters": [{"$ref": "#/components/parameters/PetId"}],
+                "get": {
+                    "operationId": "getPetById",
+                    "security": [{"api_key": []}],
+                    "responses": {
+                        "200": {"description": "The pet", "content": {"application/json": {
+                            "schema": {"$ref": "#/components/schemas/Pet"}
+                        }}},
+                        "default": {"description": "Error", "content": {"application/json": {
+                            "schema": {"$ref": "#/components/schemas/Error"}
+                        }}}
+                    }
+                },
+                "delete": {"operationId": "deletePet"}
+            },
+            "/owners": {"get": {"operationId": "listOwners"}}
+        }));
+        spec.json["components"] = json!({
+            "parameters": {"PetId": {"name": "petId", "in": "path", "required": true,
+                                     "schema": {"type": "integer"}}},
+            "schemas": {
+                "Pet": {"type": "object", "properties": {
+                    "name": {"type": "string"},
+                    "category": {"$ref": "#/components/schemas/Category"}
+                }},
+                "Category": {"type": "object", "properties": {"name": {"type": "string"}}},
+                "Error": {"type": "object", "properties": {"message": {"type": "string"}}},
+                "Owner": {"type": "object"}
+            },
+            "securitySchemes": {
+                "api_key": {"type": "apiKey", "name": "api_key", "in": "header"},
+                "oauth": {"type": "oauth2", "flows": {}}
+            }
+        });
+        let out = temp_dir.path().join("out");
+        let config = Config::new("test", "openapi.json", out.to_string_lossy());
+
+        manager.generate(&spec, &config, None).await?;
+        assert!(!out.join("openapi").exists());
+
+        let opts = TemplateOptions {
+            emit_operation_fragments: true,
+            ..Default::default()
+        };
+        manager.generate(&spec, &config, Some(opts)).await?;
+        let fragment: JsonValue = serde_json::from_str(
+            &tokio::fs::read_to_string(out.join("openapi/get_pet_by_id.json")).await?,
+        )?;
+        let path_item = &fragment["paths"]["/pets/{petId}"];
+        assert_eq!(path_item["get"]["operationId"], "getPetById");
+        assert!(path_item.get("delete").is_none());
+        assert!(fragment["paths"].get("/owners").is_none());
+        let schemas: Vec<&String> = fragment["components"]["schemas"]
+            .as_object()
+            .unwrap()
+            .keys()
+            .collect();
+        assert_eq!(schemas, ["Category", "Error", "Pet"]);
+        assert_eq!(
+            fragment["components"]["parameters"]["PetId"]["name"],
+            "petId"
+        );
+        let schemes: Vec<&String> = fragment["components"]["securitySchemes"]
+            .as_object()
+            .unwrap()
+            .keys()
+            .collect();
+        assert_eq!(schemes, ["api_key"]);
+        assert_eq!(fragment["servers"], spec.json["servers"]);
+        assert!(out.join("openapi/list_owners.json").is_file());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_filename_case_styles() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let manager = setup_template(
+            temp_dir.path(),
+            &[
+                (
+                    "manifest.yaml",
+                    &test_manifest("  - source: op.tera\n    destination: \"{operation_id}.ts\"\n    for_each: operation\n"),
+                ),
+                ("op.tera", "{{ sanitized_filename }}"),
+            ],
+        )
+        .await?;
+        let spec = test_spec(json!({
+            "/pet/findByStatus": {"get": {"operationId": "findPetsByStatus"}},
+            "/pet/{id}": {"get": {"operationId": "pets/get:one", "parameters": [
+                {"name": "id", "in": "path", "required": true, "schema": {"type": "string"}}
+            ]}}
+        }));
+
+        // As-is names still can't leave the output directory
+        let out = temp_dir.path().join("as_is_sanitized");
+        let config = Config::new("test", "openapi.json", out.to_string_lossy());
+        let opts = TemplateOptions {
+            filename_case: FilenameCase::AsIs,
+            ..Default::default()
+        };
+        manager.generate(&spec, &config, Some(opts)).await?;
+        assert!(out.join("pets_get_one.ts").is_file());
+
+        for (case, expected) in [
+            (FilenameCase::Snake, "find_pets_by_status"),
+            (FilenameCase::Camel, "findPetsByStatus"),
+            (FilenameCase::Pascal, "FindPetsByStatus"),
+            (FilenameCase::AsIs, "findPetsByStatus"),
+        ] {
+            let out = temp_dir.path().join(format!("{:?}", case));
+            let config = Config::new("test", "openapi.json", out.to_string_lossy());
+            let opts = TemplateOptions {
+                filename_case: case,
+                ..Default::default()
+            };
+            manager.generate(&spec, &config, Some(opts)).await?;
+
+            let rendered = tokio::fs::read_to_string(out.join(format!("{}.ts", expected))).await?;
+            assert_eq!(rendered, expected, "{:?}", case);
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_include_tests_emits_stub_per_operation() -> Result<()> {
+        let manager = builtin_manager().await?;
+        let spec = test_spec(json!({
+            "/pets/{petId}": {
+                "parameters": [{"name": "petId", "in": "path", "required": true,
+                                "schema": {"type": "integer", "example": 42}}],
+                "get": {"operationId": "getPet", "tags": ["pets"]}
+            },
+            "/pets": {"post": {
+                "operationId": "addPet",
+                "tags": ["pets"],
+                "requestBody": {"content": {"application/json": {
+                    "schema": {"type": "object"},
+                    "example": {"name": "Rex \"#1\""}
+                }}}
+            }}
+        }));
+        let temp_dir = tempfile::tempdir()?;
+
+        let without = temp_dir.path().join("without");
+        let config = Config::new("test", "openapi.json", without.to_string_lossy());
+        manager.generate(&spec, &config, None).await?;
+        assert!(!without.join("tests").exists());
+
+        let with = temp_dir.path().join("with");
+        let config = Config::new("test", "openapi.json", with.to_string_lossy());
+        let opts = TemplateOptions {
+            include_tests: true,
+            ..Default::default()
+        };
+        manager.generate(&spec, &config, Some(opts)).await?;
+        let get_pet = tokio::fs::read_to_string(with.join("tests/get_pet.rs")).await?;
+        assert!(get_pet.contains("\"/pets/{petId}\""));
+        assert!(get_pet.contains("url_value(\"42\")"), "{}", get_pet);
+        // Examples are escaped string literals, so `"#` can't end them early
+        let add_pet = tokio::fs::read_to_string(with.join("tests/add_pet.rs")).await?;
+        assert!(
+            add_pet.contains(r##"serde_json::from_str("{\"name\":\"Rex \\\"#1\\\"\"}")"##),
+            "{}",
+            add_pet
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_agent_instructions_are_escaped() -> Result<()> {
+        let manager = builtin_manager().await?;
+        let spec = test_spec(json!({
+            "/pets": {"get": {"operationId": "listPets", "tags": ["pets"]}}
+        }));
+        let temp_dir = tempfile::tempdir()?;
+        let config = Config::new("test", "openapi.json", temp_dir.path().to_string_lossy());
+        let opts = TemplateOptions {
+            agent_instructions: Some(json!("Say \"hi\"#, then \\ list pets")),
+            ..Default::default()
+        };
+
+        manager.generate(&spec, &config, Some(opts)).await?;
+        let handlers =
+            tokio::fs::read_to_string(temp_dir.path().join("src/handlers/mod.rs")).await?;
+        assert!(
+            handlers.contains(r##"instructions: Some("Say \"hi\"#, then \\ list pets".into()),"##)
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_generate_client_emits_client_module() -> Result<()> {
+        let manager = builtin_manager().await?;
+        let spec = test_spec(json!({
+            "/pets/{petId}": {
+                "parameters": [{"name": "petId", "in": "path", "required": true,
+                                "schema": {"type": "integer"}}],
+                "get": {"operationId": "getPet", "tags": ["pets"]}
+            },
+            "/pets": {"post": {
+                "operationId": "addPet",
+                "tags": ["pets"],
+                "requestBody": {"content": {"application/json": {
+                    "schema": {"type": "object", "properties": {"name": {"type": "string"}}}
+                }}}
+            }},
+            "/photos": {"post": {
+                "operationId": "uploadPhoto",
+                "tags": ["pets"],
+                "requestBody": {"content": {"image/png": {
+                    "schema": {"type": "string", "format": "binary"}
+                }}}
+            }}
+        }));
+        let temp_dir = tempfile::tempdir()?;
+
+        let without = temp_dir.path().join("without");
+        let config = Config::new("test", "openapi.json", without.to_string_lossy());
+        manager.generate(&spec, &config, None).await?;
+        assert!(!without.join("src/client.rs").exists());
+        let main_rs = tokio::fs::read_to_string(without.join("src/main.rs")).await?;
+        assert!(!main_rs.contains("mod client;"));
+
+        let with = temp_dir.path().join("with");
+        let config = Config::new("test", "openapi.json", with.to_string_lossy());
+        let opts = TemplateOptions {
+            generate_client: true,
+            ..Default::default()
+        };
+        manager.generate(&spec, &config, Some(opts)).await?;
+        let client = tokio::fs::read_to_string(with.join("src/client.rs")).await?;
+        assert!(client.contains("pub async fn get_pet("));
+        assert!(client.contains("handlers::get_pet::GetPetParams"));
+        assert!(client.contains("reqwest::Method::GET,\n            params,\n            None,"));
+        // Path parameters can't inject `/`, `?` or `#` into the URL
+        assert!(client.contains("path.replace(&placeholder, &urlencoding::encode(value));"));
+        // POST operations send their body, typed after `request_body_type`, as JSON
+        assert!(client.contains(
+            "handlers::add_pet::AddPetParams,\n        body: &handlers::add_pet::AddPetRequestBody,"
+        ));
+        let handler = tokio::fs::read_to_string(with.join("src/handlers/add_pet.rs")).await?;
+        assert!(handler.contains("pub type AddPetRequestBody = serde_json::Value;"));
+        assert!(handler.contains("Verb: POST\nPath: /pets\n"));
+        assert!(client.contains(
+            "reqwest::Method::POST,\n            params,\n            Some(Body::Json(body)),"
+        ));
+        assert!(client.contains("Some(Body::Json(body)) => request = request.json(body),"));
+        // Binary bodies are sent as raw bytes with their media type
+        assert!(client.contains(
+            "handlers::upload_photo::UploadPhotoParams,\n        body: handlers::upload_photo::UploadPhotoBody,"
+        ));
+        assert!(client.contains("Some(Body::Raw(body, \"image/png\")),"));
+        let handler = tokio::fs::read_to_string(with.join("src/handlers/upload_photo.rs")).await?;
+        assert!(handler.contains("pub type UploadPhotoBody = axum::body::Bytes;"));
+        assert!(!client.contains(".get(&url)"));
+        let main_rs = tokio::fs::read_to_string(with.join("src/main.rs")).await?;
+        assert!(main_rs.contains("mod client;"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_array_parameters_are_joined() -> Result<()> {
+        let manager = builtin_manager().await?;
+        let spec = test_spec(json!({
+            "/pets": {"get": {
+                "operationId": "findPets",
+                "tags": ["pets"],
+                "parameters": [
+                    {"name": "tags", "in": "query", "required": true,
+                     "schema": {"type": "array", "items": {"type": "string"}}},
+                    {"name": "ids", "in": "query", "style": "pipeDelimited",
+                     "schema": {"type": "array", "items": {"type": "integer"}}},
+                    {"name": "limit", "in": "query", "schema": {"type": "integer"}}
+                ],
+                "responses": {}
+            }}
+        }));
+        let temp_dir = tempfile::tempdir()?;
+        let out = temp_dir.path().join("out");
+        let config = Config::new("test", "openapi.json", out.to_string_lossy());
+        manager.generate(&spec, &config, None).await?;
+
+        let handler = tokio::fs::read_to_string(out.join("src/handlers/find_pets.rs")).await?;
+        assert!(handler.contains(
+            r#"params.insert("tags".to_string(), self.tags.iter().map(ToString::to_string).collect::<Vec<_>>().join(","));"#
+        ));
+        assert!(handler.contains(
+            r#"params.insert("ids".to_string(), val.iter().map(ToString::to_string).collect::<Vec<_>>().join("|"));"#
+        ));
+        assert!(handler.contains(r#"params.insert("limit".to_string(), val.to_string());"#));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_keyword_operation_id_gets_escaped_module() -> Result<()> {
+        let manager = builtin_manager().await?;
+        let spec = test_spec(json!({
+            "/pets/move": {"get": {"operationId": "move", "tags": ["pets"], "responses": {}}}
+        }));
+        let temp_dir = tempfile::tempdir()?;
+        let out = temp_dir.path().join("out");
+        let config = Config::new("test", "openapi.json", out.to_string_lossy());
+        manager.generate(&spec, &config, None).await?;
+
+        let handlers_mod = tokio::fs::read_to_string(out.join("src/handlers/mod.rs")).await?;
+        assert!(handlers_mod.contains("pub mod r#move;"));
+        assert!(handlers_mod.contains("params: r#move::MoveParams"));
+        assert!(handlers_mod.contains("r#move::move_handler(&config, &params)"));
+        assert!(!handlers_mod.contains("pub mod move;"));
+        // File names and resource URIs keep the raw name
+        assert!(out.join("src/handlers/move.rs").is_file());
+        assert!(handlers_mod.contains(r#"name: "move".to_string()"#));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_with_env_example_lists_server_variables() -> Result<()> {
+        let manager = builtin_manager().await?;
+        let spec = test_spec(json!({
+            "/pets": {"get": {"operationId": "listPets", "tags": ["pets"]}}
+        }));
+        let temp_dir = tempfile::tempdir()?;
+        let config = Config::new("test", "openapi.json", temp_dir.path().to_string_lossy());
+
+        manager.generate(&spec, &config, None).await?;
+        assert!(!temp_dir.path().join(".env.example").exists());
+
+        let opts = TemplateOptions {
+            with_env_example: true,
+            ..Default::default()
+        };
+        manager.generate(&spec, &config, Some(opts)).await?;
+        let env = tokio::fs::read_to_string(temp_dir.path().join(".env.example")).await?;
+        assert!(env.contains("LOG_FILE=test-mcp.log\n"));
+
+        let opts = TemplateOptions {
+            with_env_example: true,
+            server_port: Some(9090),
+            log_file: Some("pets".to_string()),
+            ..Default::default()
+        };
+        manager.generate(&spec, &config, Some(opts)).await?;
+        let env = tokio::fs::read_to_string(temp_dir.path().join(".env.example")).await?;
+        assert!(env.contains("SERVER_PORT=9090\n"));
+        assert!(env.contains("BASE_API_URL=https://api.example.com\n"));
+        assert!(env.contains("LOG_FILE=pets\n"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_request_body_naming_per_operation() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let manager = setup_template(
+            temp_dir.path(),
+            &[
+                (
+                    "manifest.yaml",
+                    &test_manifest("  - source: body.tera\n    for_each: endpoint\n    destination: \"{endpoint}.txt\"\n"),
+                ),
+                ("body.tera", "{{ request_body_type }}"),
+            ],
+        )
+        .await?;
+        let body = json!({"content": {"application/json": {
+            "schema": {"$ref": "#/components/schemas/NewPet"}
+        }}});
+        let spec = test_spec(json!({
+            "/pets": {"post": {"operationId": "createPet", "requestBody": body}}
+        }));
+        let out = temp_dir.path().join("out");
+        let config = Config::new("test", "openapi.json", out.to_string_lossy());
+
+        manager.generate(&spec, &config, None).await?;
+        assert_eq!(
+            tokio::fs::read_to_string(out.join("create_pet.txt")).await?,
+            "NewPet"
+        );
+
+        let opts = TemplateOptions {
+            request_body_naming: RequestBodyNaming::Operation,
+            ..Default::default()
+        };
+        manager.generate(&spec, &config, Some(opts)).await?;
+        assert_eq!(
+            tokio::fs::read_to_string(out.join("create_pet.txt")).await?,
+            "CreatePetRequestBody"
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_workspace_layout() -> Result<()> {
+        let manager = builtin_manager().await?;
+        let spec = test_spec(json!({
+            "/pets": {"get": {"operationId": "listPets", "tags": ["pets"]}}
+        }));
+        let temp_dir = tempfile::tempdir()?;
+        let out = temp_dir.path();
+        let config = Config::new("petstore", "openapi.json", out.to_string_lossy());
+        let opts = TemplateOptions {
+            layout: Layout::Workspace,
+            ..Default::default()
+        };
+        manager.generate(&spec, &config, Some(opts)).await?;
+
+        let root = tokio::fs::read_to_string(out.join("Cargo.toml")).await?;
+        assert!(root.contains("[workspace]"));
+        assert!(root.contains("members = [\"crates/*\"]"));
+        assert!(!root.contains("[package]"));
+
+        let member = out.join("crates/petstore");
+        let member_manifest = tokio::fs::read_to_string(member.join("Cargo.toml")).await?;
+        assert!(member_manifest.contains("name = \"petstore\""));
+        assert!(!member_manifest.contains("[workspace]"));
+        assert!(member.join("src/main.rs").is_file());
+        assert!(member.join("src/handlers/list_pets.rs").is_file());
+        assert!(member.join("schemas/list_pets.json").is_file());
+        assert!(!out.join("src").exists());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_fail_on_unmapped_type() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let manager = setup_template(
+            temp_dir.path(),
+            &[
+                (
+                    "manifest.yaml",
+                    &test_manifest("  - source: main.tera\n    destination: main.txt\n"),
+                ),
+                ("main.tera", "main"),
+            ],
+        )
+        .await?;
+        let spec = test_spec(json!({
+            "/pets": {
+                "parameters": [{"name": "filter", "in": "query",
+                                "schema": {"anyOf": [{"type": "string"}, {"type": "integer"}]}}],
+                "get": {"operationId": "listPets", "tags": ["pets"]}
+            }
+        }));
+        let out = temp_dir.path().join("out");
+        let config = Config::new("test", "openapi.json", out.to_string_lossy());
+
+        manager.generate(&spec, &config, None).await?;
+
+        let opts = TemplateOptions {
+            fail_on_unmapped_type: true,
+            ..Default::default()
+        };
+        let message = manager
+            .generate(&spec, &config, Some(opts))
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(
+            message.contains("listPets.filter: anyOf schema (falls back to String)"),
+            "unexpected error: {}",
+            message
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_changed_template_is_reloaded() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let manager = setup_template(
+            temp_dir.path(),
+            &[
+                (
+                    "manifest.yaml",
+                    &test_manifest("  - source: main.tera\n    destination: main.txt\n"),
+                ),
+                ("main.tera", "first version"),
+            ],
+        )
+        .await?;
+        let spec = test_spec(json!({}));
+        let out = temp_dir.path().join("out");
+        let config = Config::new("test", "openapi.json", out.to_string_lossy());
+
+        manager.generate(&spec, &config, None).await?;
+        assert_eq!(
+            tokio::fs::read_to_string(out.join("main.txt")).await?,
+            "first version"
+        );
+
+        let template = manager.template_dir_path().join("main.tera");
+        std::fs::write(&template, "second version")?;
+        // Don't rely on the filesystem's timestamp resolution
+        std::fs::File::options()
+            .write(true)
+            .open(&template)?
+            .set_modified(SystemTime::now() + std::time::Duration::from_secs(60))?;
+
+        manager.generate(&spec, &config, None).await?;
+        assert_eq!(
+            tokio::fs::read_to_string(out.join("main.txt")).await?,
+            "second version"
+        );
+
+        let manifest = manager.template_dir_path().join("manifest.yaml");
+        std::fs::write(
+            &manifest,
+            test_manifest("  - source: main.tera\n    destination: renamed.txt\n"),
+        )?;
+        std::fs::File::options()
+            .write(true)
+            .open(&manifest)?
+            .set_modified(SystemTime::now() + std::time::Duration::from_secs(120))?;
+
+        manager.generate(&spec, &config, None).await?;
+        assert_eq!(manager.manifest().files[0].destination, "renamed.txt");
+        assert_eq!(
+            tokio::fs::read_to_string(out.join("renamed.txt")).await?,
+            "second version"
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_new_rejects_directory_without_templates() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let err = setup_template(
+            temp_dir.path(),
+            &[
+                (
+                    "manifest.yaml",
+                    &test_manifest("  - source: main.tera\n    destination: src/main.rs\n"),
+                ),
+                ("main.rs.tmpl", "fn main() {}\n"),
+            ],
+        )
+        .await
+        .unwrap_err();
+
+        let message = err.to_string();
+        assert!(
+            message.contains("appears empty or templates have the wrong extension"),
+            "unexpected error: {}",
+            message
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_with_dockerfile_generates_dockerfile() -> Result<()> {
+        let manager = builtin_manager().await?;
+        let spec = test_spec(json!({}));
+        let temp_dir = tempfile::tempdir()?;
+
+        let without = temp_dir.path().join("without");
+        let config = Config::new("test", "openapi.json", without.to_string_lossy());
+        manager.generate(&spec, &config, None).await?;
+        assert!(!without.join("Dockerfile").exists());
+
+        let with = temp_dir.path().join("with");
+        let config = Config::new("test", "openapi.json", with.to_string_lossy());
+        let opts = TemplateOptions {
+            server_port: Some(9123),
+            with_dockerfile: true,
+            ..Default::default()
+        };
+        manager.generate(&spec, &config, Some(opts)).await?;
+        let dockerfile = tokio::fs::read_to_string(with.join("Dockerfile")).await?;
+        assert!(dockerfile.contains("EXPOSE 9123"));
+        assert!(dockerfile.contains("SSE_ADDR=0.0.0.0:9123"));
+        assert!(dockerfile.contains("/usr/local/bin/test"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_render_one_renders_only_the_named_file() -> Result<()> {
+        let manager = builtin_manager().await?;
+        let spec = test_spec(json!({}));
+        let temp_dir = tempfile::tempdir()?;
+        let out = temp_dir.path().join("out");
+        let config = Config::new("petstore", "openapi.json", out.to_string_lossy());
+
+        let files = manager
+            .render_one("Cargo.toml.tera", &spec, &config, None)
+            .await?;
+
+        assert_eq!(files.len(), 1);
+        let (path, content) = &files[0];
+        assert_eq!(path, Path::new("Cargo.toml"));
+        assert!(content.contains("name = \"petstore\""));
+        assert!(!out.exists());
+        assert!(manager
+            .render_one("missing.tera", &spec, &config, None)
+            .await
+            .is_err());
+
+        Ok(())
+    }
 }
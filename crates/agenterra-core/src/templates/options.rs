@@ -26,16 +26,216 @@
 // Re-exports (alphabetized)
 pub use serde_json::Value as JsonValue;
 
+use std::{fmt, sync::Arc};
+
+use serde::{Deserialize, Serialize};
+
+use crate::utils::{
+    sanitize_path_component, to_lower_camel_case, to_snake_case, to_upper_camel_case,
+};
+
+/// Naming style for per-operation file names (`{operation_id}` and `sanitized_filename`)
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FilenameCase {
+    /// `find_pets_by_status`
+    #[default]
+    Snake,
+    /// `findPetsByStatus`
+    Camel,
+    /// `FindPetsByStatus`
+    Pascal,
+    /// The operationId as written in the spec, except for characters file
+    /// names can't hold
+    AsIs,
+}
+
+impl FilenameCase {
+    /// Apply this naming style to an operation ID (or tag). The result is a
+    /// single path component: separators and other characters file names
+    /// can't hold become `_` (see [`sanitize_path_component`]).
+    pub fn apply(self, operation_id: &str) -> String {
+        let name = match self {
+            FilenameCase::Snake => to_snake_case(operation_id),
+            FilenameCase::Camel => to_lower_camel_case(operation_id),
+            FilenameCase::Pascal => to_upper_camel_case(operation_id),
+            FilenameCase::AsIs => operation_id.to_string(),
+        };
+        sanitize_path_component(&name)
+    }
+}
+
+/// How request body struct names (`request_body_type`) are chosen
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RequestBodyNaming {
+    /// After the referenced component schema, shared by every operation using
+    /// it; `{Op}RequestBody` for inline schemas
+    #[default]
+    Component,
+    /// Always `{Op}RequestBody`, one struct per operation
+    Operation,
+}
+
+/// How generated files are organized into crates
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Layout {
+    /// Everything in one crate at the output directory
+    #[default]
+    SingleCrate,
+    /// A Cargo workspace: files whose manifest entry names a `crate` go to
+    /// `crates/{crate}/`, next to a root workspace `Cargo.toml`. The built-in
+    /// `rust_axum` template keeps the whole server in one member crate.
+    Workspace,
+}
+
+impl Layout {
+    /// Name exposed to templates as `layout`
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Layout::SingleCrate => "single_crate",
+            Layout::Workspace => "workspace",
+        }
+    }
+}
+
+/// How operationIds are synthesized for operations that don't declare one
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OperationIdStrategy {
+    /// `get_pets_petId` for `GET /pets/{petId}`
+    #[default]
+    MethodPath,
+    /// `pets_petId_get` for `GET /pets/{petId}`
+    PathMethod,
+    /// The operation's summary in snake_case, e.g. `find_a_pet` for
+    /// "Find a pet"; operations without a summary fall back to `MethodPath`
+    Summary,
+}
+
+impl OperationIdStrategy {
+    /// Build an operationId for `method path` (e.g. `get`, `/pets/{petId}`).
+    ///
+    /// Every strategy joins the alphanumeric runs of its parts with single
+    /// underscores, so path punctuation never leaks into the ID. Earlier
+    /// releases kept it (`get_pets_{petId}`), so specs relying on synthesized
+    /// IDs get new tool and file names when regenerated.
+    pub fn synthesize(self, method: &str, path: &str, summary: Option<&str>) -> String {
+        let words = |text: &str| -> Vec<String> {
+            text.split(|c: char| !c.is_ascii_alphanumeric())
+                .filter(|word| !word.is_empty())
+                .map(String::from)
+                .collect()
+        };
+        let summary_words = summary
+            .map(|summary| words(&summary.to_lowercase()))
+            .unwrap_or_default();
+        let parts = match self {
+            OperationIdStrategy::Summary if !summary_words.is_empty() => summary_words,
+            OperationIdStrategy::PathMethod => [words(path), words(method)].concat(),
+            _ => [words(method), words(path)].concat(),
+        };
+        parts.join("_")
+    }
+}
+
+/// Serialization style for generated `schemas/*.json` files; object keys are
+/// always written in sorted order
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SchemaJsonStyle {
+    /// Indented JSON
+    #[default]
+    Pretty,
+    /// Single-line JSON without whitespace
+    Compact,
+}
+
+impl SchemaJsonStyle {
+    /// Serialize a schema in this style
+    pub fn to_string(self, value: &JsonValue) -> serde_json::Result<String> {
+        match self {
+            SchemaJsonStyle::Pretty => serde_json::to_string_pretty(value),
+            SchemaJsonStyle::Compact => serde_json::to_string(value),
+        }
+    }
+}
+
+/// Line endings written to rendered output files
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LineEnding {
+    /// `\n`
+    #[default]
+    Lf,
+    /// `\r\n`
+    CrLf,
+    /// `\r\n` on Windows, `\n` elsewhere
+    Native,
+}
+
+impl LineEnding {
+    /// Normalize every line break in `content` to this style
+    pub fn apply(self, content: &str) -> String {
+        let lf = content.replace("\r\n", "\n");
+        match self {
+            LineEnding::Lf => lf,
+            LineEnding::CrLf => lf.replace('\n', "\r\n"),
+            LineEnding::Native if cfg!(windows) => lf.replace('\n', "\r\n"),
+            LineEnding::Native => lf,
+        }
+    }
+}
+
+/// Transformation of the raw spec JSON applied before operations are parsed,
+/// e.g. to inject a server or drop an endpoint without editing the spec file.
+/// An error stops generation.
+#[derive(Clone)]
+pub struct SpecPreprocessor(pub Arc<PreprocessFn>);
+
+/// Signature of a [`SpecPreprocessor`] closure
+pub type PreprocessFn = dyn Fn(&mut JsonValue) -> crate::Result<()> + Send + Sync;
+
+impl SpecPreprocessor {
+    /// Wrap a closure editing the spec in place
+    pub fn new(
+        preprocess: impl Fn(&mut JsonValue) -> crate::Result<()> + Send + Sync + 'static,
+    ) -> Self {
+        Self(Arc::new(preprocess))
+    }
+
+    /// A preprocessor applying `patch` with [`apply_patch`](crate::patch::apply_patch)
+    pub fn from_patch(patch: JsonValue) -> Self {
+        Self::new(move |spec| crate::patch::apply_patch(spec, &patch))
+    }
+}
+
+impl fmt::Debug for SpecPreprocessor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SpecPreprocessor(..)")
+    }
+}
+
+/// Preprocessors are equal when they are the same closure
+impl PartialEq for SpecPreprocessor {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
 /// Configuration struct for controlling template-based code generation.
 ///
 /// Provides options to customize which operations are included, whether to generate tests,
 /// file overwrite behavior, and additional template context.
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct TemplateOptions {
     /// Whether to include all operations by default
     pub all_operations: bool,
 
-    /// Whether to generate tests
+    /// Emit a smoke-test stub per operation (templates opt in via
+    /// `when: include_tests` in their manifest)
     pub include_tests: bool,
 
     /// Whether to overwrite existing files
@@ -50,9 +250,141 @@ pub struct TemplateOptions {
     /// Operations to exclude
     pub exclude_operations: Vec<String>,
 
+    /// Path globs (e.g. `/pets/**`) an operation's path must match to be generated
+    pub include_paths: Vec<String>,
+
+    /// Path globs (e.g. `/admin/**`) excluding matching operations
+    pub exclude_paths: Vec<String>,
+
     /// Server port for the generated application
     pub server_port: Option<u16>,
 
     /// Log file path for the generated application
     pub log_file: Option<String>,
+
+    /// Error type returned by generated handlers (defaults to `rmcp::Error`); the
+    /// built-in template converts to it with `Into` and back for the MCP tool,
+    /// so it needs `From<rmcp::Error>` and `rmcp::Error: From<it>`
+    pub handler_error_type: Option<String>,
+
+    /// Banner (e.g. a license header) prepended to every generated source file,
+    /// commented using the syntax of the file's language. Files without a known
+    /// comment syntax (JSON, unknown extensions) are left untouched.
+    pub file_header: Option<String>,
+
+    /// Strip trailing whitespace and collapse runs of three or more blank lines
+    /// in rendered output (off by default to preserve exact template output)
+    pub trim_blank_lines: bool,
+
+    /// Keep hand edits to an existing output directory: per-operation and
+    /// per-tag files are only created when missing, and other existing files
+    /// only have their `// AGENTERRA:BEGIN <name>` / `// AGENTERRA:END <name>`
+    /// regions replaced (files without such regions are left alone)
+    pub append_only: bool,
+
+    /// Full return type of generated handlers, e.g. a project `ApiResult<CallToolResult>`
+    /// alias (defaults to `Result<CallToolResult, {handler_error_type}>`); must
+    /// name that same `Result` type
+    pub handler_result_type: Option<String>,
+
+    /// Ship a multi-stage `Dockerfile` for the generated server (templates opt
+    /// in via `when: with_dockerfile` in their manifest)
+    pub with_dockerfile: bool,
+
+    /// Ship a `.env.example` listing the server's environment variables with
+    /// their configured values (templates opt in via `when: with_env_example`)
+    pub with_env_example: bool,
+
+    /// Emit a `reqwest`-based client module for the generated endpoints
+    /// (templates opt in via `when: generate_client` in their manifest)
+    pub generate_client: bool,
+
+    /// Write an `mcp.json` server descriptor (name, version, capabilities and
+    /// tools) at the output root, or in the server crate with
+    /// [`Layout::Workspace`]
+    pub emit_mcp_descriptor: bool,
+
+    /// Entry of the spec's `servers` array used as the base API URL (defaults
+    /// to the first)
+    pub server_index: Option<usize>,
+
+    /// Write a standalone OpenAPI document per operation to
+    /// `openapi/<operation>.json`, with the components it references
+    pub emit_operation_fragments: bool,
+
+    /// Patch applied to the spec JSON before anything is generated from it, as
+    /// with [`apply_patch`](crate::patch::apply_patch). Recorded in the
+    /// generation manifest, so upgrades replay it.
+    pub spec_patch: Option<JsonValue>,
+
+    /// Edits applied to the spec JSON after [`spec_patch`](Self::spec_patch).
+    /// Not recorded in the generation manifest, so upgrades don't replay it.
+    #[serde(skip)]
+    pub spec_preprocessor: Option<SpecPreprocessor>,
+
+    /// How operationIds are synthesized for operations that don't declare
+    /// one (defaults to `method_path`)
+    pub operation_id_strategy: OperationIdStrategy,
+
+    /// Naming style for per-operation output files (defaults to snake_case)
+    pub filename_case: FilenameCase,
+
+    /// How request body struct names are chosen (defaults to the component name)
+    pub request_body_naming: RequestBodyNaming,
+
+    /// Single crate or Cargo workspace output (defaults to a single crate)
+    pub layout: Layout,
+
+    /// Maximum number of operations rendered at once (defaults to the number of CPUs)
+    pub max_concurrency: Option<usize>,
+
+    /// Maximum nesting of `$ref` expansions in generated schema files; deeper
+    /// refs, and refs back to a schema being expanded, are left as-is (defaults to [`DEFAULT_MAX_DEREF_DEPTH`](crate::templates::DEFAULT_MAX_DEREF_DEPTH))
+    pub max_deref_depth: Option<usize>,
+
+    /// Path of each operation's schema file under `schemas/`, with
+    /// `{operation}` (the snake_case operationId, required), `{tag}` (the first tag,
+    /// styled like [`filename_case`](Self::filename_case)) and `{method}`
+    /// replaced, e.g. `{tag}/{operation}.json` (defaults to
+    /// [`DEFAULT_SCHEMA_FILENAME_PATTERN`](crate::templates::DEFAULT_SCHEMA_FILENAME_PATTERN))
+    pub schema_filename_pattern: Option<String>,
+
+    /// Keep `$ref`s in generated schema files instead of inlining them; the
+    /// spec's components are written to `schemas/components.json` and the
+    /// refs point into it (e.g. `components.json#/components/schemas/Pet`)
+    pub keep_schema_refs: bool,
+
+    /// How schema files are serialized (defaults to pretty-printed)
+    pub schema_json_style: SchemaJsonStyle,
+
+    /// Remove `x-` vendor extensions from generated schema files
+    pub strip_vendor_extensions: bool,
+
+    /// Remove documentation-only keys (`description`, `title`, `example`,
+    /// `examples`) from generated schema files, keeping the structural schema
+    pub minify_schemas: bool,
+
+    /// Line endings of rendered files (defaults to LF)
+    pub line_ending: LineEnding,
+
+    /// Fail generation when a parameter or response field can only be mapped
+    /// to a catch-all type such as `String` or `serde_json::Value`
+    pub fail_on_unmapped_type: bool,
+
+    /// Fail generation when the template manifest's `language` doesn't match
+    /// the template kind, or when [`OpenApiContext::validate`](crate::OpenApiContext::validate)
+    /// finds path parameter mismatches or operations without a 2xx response,
+    /// instead of only warning
+    pub strict: bool,
+
+    /// Regenerate even when the spec, template and options match the
+    /// last generation into the output directory. Not recorded in the
+    /// generation manifest.
+    #[serde(skip)]
+    pub force: bool,
+
+    /// For Rust templates, run `cargo clippy -- -D warnings` in the output
+    /// directory after generation and fail with its output unless it's clean
+    /// (skipped with a warning when clippy isn't installed)
+    pub lint_output: bool,
 }
@@ -16,9 +16,70 @@ pub enum ParameterKind {
 /// Language-agnostic parameter info with target language type
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TemplateParameterInfo {
+    /// Name as written in the spec
     pub name: String,
+    /// `name` as a field name of the target language, escaped when it's a keyword
+    /// (e.g. `r#type` in Rust)
+    pub ident: String,
+    /// Name identifying the parameter; lowercased for headers since HTTP header names are
+    /// case-insensitive
+    pub canonical_name: String,
     pub target_type: String,
+    /// Whether a value must be given: `required: true`, or any path parameter
+    pub required: bool,
+    /// Type of the generated struct field: `target_type`, wrapped in `Option<>` unless required
+    pub field_type: String,
     pub description: Option<String>,
     pub example: Option<JsonValue>,
     pub kind: ParameterKind,
+    /// Wire format of array parameters and matrix/label path parameters (`None` otherwise)
+    pub serialization: Option<ParameterSerialization>,
+    /// Allowed values of an integer/number `enum` (`None` when unconstrained)
+    pub enum_values: Option<Vec<JsonValue>>,
+}
+
+/// Effective OpenAPI `style`/`explode` of an array or matrix/label path parameter, with the spec
+/// defaults for its location filled in
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ParameterSerialization {
+    /// e.g. "form", "simple", "spaceDelimited", "pipeDelimited", "matrix", "label"
+    pub style: String,
+    /// `true` for repeated keys (`?a=1&a=2`), `false` for one delimited value (`?a=1,2`)
+    pub explode: bool,
+}
+
+/// One declared response of an operation, with its body mapped to the target language
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TemplateResponseInfo {
+    /// Status code as written in the spec, e.g. "200", "4XX" or "default"
+    pub status: String,
+    pub description: Option<String>,
+    /// Schema of the body (JSON preferred when several media types are listed)
+    pub schema: Option<JsonValue>,
+    /// Target language type of the body (`None` for responses without one)
+    pub target_type: Option<String>,
+    /// Whether this is the `default` response, covering every status the
+    /// operation doesn't list explicitly
+    pub is_default: bool,
+}
+
+/// A `oneOf`/`anyOf` schema mapped to a target language enum
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TemplateEnumInfo {
+    /// Type name of the enum
+    pub name: String,
+    /// Discriminator property of an internally tagged enum (`None` for untagged enums)
+    pub tag: Option<String>,
+    pub variants: Vec<TemplateEnumVariant>,
+}
+
+/// One subschema of a [`TemplateEnumInfo`]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TemplateEnumVariant {
+    /// Variant name, from the referenced schema or the subschema's `title`
+    pub name: String,
+    /// Discriminator value selecting this variant (`None` for untagged enums)
+    pub tag_value: Option<String>,
+    /// Target language type of the variant's payload
+    pub target_type: String,
 }
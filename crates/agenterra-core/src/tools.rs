@@ -0,0 +1,315 @@
+//! Tools file import.
+//!
+//! A tools file lists the MCP tools to generate directly instead of deriving
+//! them from an API's paths. Each tool names the HTTP call it proxies and
+//! describes its input (and optionally output) with a JSON Schema:
+//!
+//! ```json
+//! {
+//!   "name": "Pet tools",
+//!   "base_url": "https://petstore.example.com",
+//!   "tools": [
+//!     {
+//!       "name": "findPets",
+//!       "description": "Find pets by status",
+//!       "method": "GET",
+//!       "path": "/pets",
+//!       "input_schema": {"type": "object", "properties": {"status": {"type": "string"}}}
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! Like Postman collections, the file is converted into an OpenAPI document
+//! so the tools go through the regular generation pipeline: every tool
+//! becomes one operation.
+
+use serde::Deserialize;
+use serde_json::{json, Map, Value as JsonValue};
+use std::{collections::BTreeMap, path::Path};
+use tokio::fs;
+
+use crate::{
+    openapi::{path_template_names, OpenApiContext},
+    Error,
+};
+
+/// Tag of tools that don't declare any
+const DEFAULT_TOOL_TAG: &str = "tools";
+
+/// Top level structure of a tools file.
+#[derive(Debug, Deserialize)]
+struct ToolsFile {
+    name: String,
+    #[serde(default)]
+    version: Option<String>,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    base_url: Option<String>,
+    tools: Vec<ToolDefinition>,
+}
+
+/// One tool and the HTTP call behind it.
+#[derive(Debug, Deserialize)]
+struct ToolDefinition {
+    name: String,
+    #[serde(default)]
+    description: Option<String>,
+    /// HTTP method, `GET` or `POST` (the methods operations are generated
+    /// for); `POST` when omitted
+    #[serde(default)]
+    method: Option<String>,
+    /// Request path, `/{name}` when omitted; `{param}` segments are filled from
+    /// the input properties of the same name
+    #[serde(default)]
+    path: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default, alias = "inputSchema")]
+    input_schema: Option<JsonValue>,
+    #[serde(default, alias = "outputSchema")]
+    output_schema: Option<JsonValue>,
+}
+
+impl ToolDefinition {
+    /// Lowercase HTTP method of the tool
+    fn method(&self) -> String {
+        self.method.as_deref().unwrap_or("post").to_lowercase()
+    }
+
+    /// Request path of the tool
+    fn path(&self) -> String {
+        self.path
+            .clone()
+            .unwrap_or_else(|| format!("/{}", self.name))
+    }
+}
+
+/// Parsed representation of a tools file.
+pub struct ToolsContext {
+    file: ToolsFile,
+}
+
+impl ToolsContext {
+    /// Load a tools file from disk.
+    pub async fn from_file<P: AsRef<Path>>(path: P) -> crate::Result<Self> {
+        let content = fs::read_to_string(&path).await?;
+        let file: ToolsFile = serde_json::from_str(&content).map_err(|e| {
+            Error::config(format!(
+                "Failed to parse tools file {}: {}",
+                path.as_ref().display(),
+                e
+            ))
+        })?;
+        if let Some(tool) = file.tools.iter().find(|tool| {
+            tool.method
+                .as_deref()
+                .is_some_and(|m| !m.eq_ignore_ascii_case("get") && !m.eq_ignore_ascii_case("post"))
+        }) {
+            return Err(Error::config(format!(
+                "Tool '{}' uses method {}; only GET and POST tools are supported",
+                tool.name,
+                tool.method.as_deref().unwrap_or_default()
+            )));
+        }
+        // Each path and method becomes one operation, so two tools can't share them
+        let mut routes = BTreeMap::new();
+        for tool in &file.tools {
+            let route = format!("{} {}", tool.method().to_uppercase(), tool.path());
+            if let Some(other) = routes.insert(route.clone(), &tool.name) {
+                return Err(Error::config(format!(
+                    "Tools '{}' and '{}' both call {}",
+                    other, tool.name, route
+                )));
+            }
+        }
+        Ok(Self { file })
+    }
+
+    /// Convert the tools into an OpenAPI 3.0 document.
+    ///
+    /// Input properties named in the path become path parameters. The rest
+    /// become query parameters of `GET` tools and the JSON request body of
+    /// `POST` tools.
+    pub fn to_openapi(&self) -> OpenApiContext {
+        let mut paths = Map::new();
+        for tool in &self.file.tools {
+            let method = tool.method();
+            let path = tool.path();
+            let path_item = paths
+                .entry(path.clone())
+                .or_insert_with(|| JsonValue::Object(Map::new()));
+            if let Some(path_item) = path_item.as_object_mut() {
+                path_item.insert(method.clone(), tool_operation(tool, &method, &path));
+            }
+        }
+
+        let mut info = json!({
+            "title": self.file.name,
+            "version": self.file.version.as_deref().unwrap_or("1.0.0"),
+        });
+        if let Some(description) = &self.file.description {
+            info["description"] = json!(description);
+        }
+
+        OpenApiContext {
+            json: json!({
+                "openapi": "3.0.0",
+                "info": info,
+                "servers": [{"url": self.file.base_url.as_deref().unwrap_or("/")}],
+                "paths": paths,
+            }),
+        }
+    }
+}
+
+/// OpenAPI operation for one tool
+fn tool_operation(tool: &ToolDefinition, method: &str, path: &str) -> JsonValue {
+    let mut operation = Map::new();
+    operation.insert("operationId".into(), json!(tool.name));
+    if let Some(description) = &tool.description {
+        operation.insert("summary".into(), json!(description));
+    }
+    let tags = if tool.tags.is_empty() {
+        vec![DEFAULT_TOOL_TAG.to_string()]
+    } else {
+        tool.tags.clone()
+    };
+    operation.insert("tags".into(), json!(tags));
+
+    let input = tool.input_schema.as_ref();
+    let properties = input
+        .and_then(|schema| schema.get("properties"))
+        .and_then(JsonValue::as_object)
+        .cloned()
+        .unwrap_or_default();
+    let required: Vec<&str> = input
+        .and_then(|schema| schema.get("required"))
+        .and_then(JsonValue::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(JsonValue::as_str)
+        .collect();
+    let path_params = path_template_names(path);
+
+    let mut parameters: Vec<JsonValue> = path_params
+        .iter()
+        .map(|name| {
+            json!({
+                "name": name,
+                "in": "path",
+                "required": true,
+                "schema": properties.get(name).cloned().unwrap_or_else(|| json!({"type": "string"})),
+            })
+        })
+        .collect();
+    let mut body_properties = properties;
+    body_properties.retain(|name, _| !path_params.contains(name));
+
+    if method == "get" {
+        parameters.extend(body_properties.iter().map(|(name, schema)| {
+            let mut parameter = json!({
+                "name": name,
+                "in": "query",
+                "required": required.contains(&name.as_str()),
+                "schema": schema,
+            });
+            if let Some(description) = schema.get("description") {
+                parameter["description"] = description.clone();
+            }
+            parameter
+        }));
+    } else if !body_properties.is_empty() {
+        let mut schema = json!({"type": "object", "properties": body_properties});
+        let body_required: Vec<&str> = required
+            .iter()
+            .copied()
+            .filter(|name| !path_params.iter().any(|p| p == name))
+            .collect();
+        if !body_required.is_empty() {
+            schema["required"] = json!(body_required);
+        }
+        operation.insert(
+            "requestBody".into(),
+            json!({"required": true, "content": {"application/json": {"schema": schema}}}),
+        );
+    }
+    if !parameters.is_empty() {
+        operation.insert("parameters".into(), json!(parameters));
+    }
+
+    let mut response = json!({"description": "Successful response"});
+    if let Some(output) = &tool.output_schema {
+        response["content"] = json!({"application/json": {"schema": output}});
+    }
+    operation.insert("responses".into(), json!({"200": response}));
+    JsonValue::Object(operation)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{templates::TemplateKind, Config, TemplateManager};
+    use std::path::PathBuf;
+
+    #[tokio::test]
+    async fn test_tools_become_handlers() -> crate::Result<()> {
+        let manifest = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        let base = manifest.parent().unwrap().parent().unwrap();
+        let tools =
+            ToolsContext::from_file(base.join("tests/fixtures/tools/petstore.tools.json")).await?;
+        let spec = tools.to_openapi();
+
+        let find_pets = &spec.json["paths"]["/pets"]["get"];
+        assert_eq!(find_pets["operationId"], "findPets");
+        let status = find_pets["parameters"]
+            .as_array()
+            .and_then(|params| params.iter().find(|p| p["name"] == "status"))
+            .unwrap();
+        assert_eq!(status["in"], "query");
+        assert_eq!(status["required"], true);
+        let rename_pet = &spec.json["paths"]["/pets/{petId}/name"]["post"];
+        assert_eq!(rename_pet["parameters"][0]["name"], "petId");
+        assert_eq!(rename_pet["parameters"][0]["in"], "path");
+        let body = &rename_pet["requestBody"]["content"]["application/json"]["schema"];
+        assert!(body["properties"].get("petId").is_none());
+        assert_eq!(body["required"], json!(["name"]));
+        assert_eq!(
+            spec.json["paths"]["/adoptPet"]["post"]["tags"],
+            json!(["tools"])
+        );
+
+        let temp_dir = tempfile::tempdir()?;
+        let out = temp_dir.path();
+        let manager =
+            TemplateManager::new(TemplateKind::RustAxum, Some(base.join("templates"))).await?;
+        let config = Config::new("pet_tools", "petstore.tools.json", out.to_string_lossy());
+        manager.generate(&spec, &config, None).await?;
+        for handler in ["find_pets", "rename_pet", "adopt_pet"] {
+            assert!(out.join(format!("src/handlers/{}.rs", handler)).is_file());
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_tools_sharing_a_route_are_rejected() -> crate::Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let path = temp_dir.path().join("tools.json");
+        std::fs::write(
+            &path,
+            r#"{"name": "t", "tools": [
+                {"name": "adoptPet", "path": "/pets"},
+                {"name": "createPet", "method": "post", "path": "/pets"}
+            ]}"#,
+        )?;
+
+        let err = ToolsContext::from_file(&path)
+            .await
+            .err()
+            .unwrap()
+            .to_string();
+        assert!(err.contains("'adoptPet' and 'createPet' both call POST /pets"));
+        Ok(())
+    }
+}
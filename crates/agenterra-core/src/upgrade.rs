@@ -0,0 +1,283 @@
+//! Re-applying the current template to a previously generated project.
+//!
+//! Every generation records its configuration, template options and rendered
+//! files in the output directory's [`GenerationManifest`]. An upgrade
+//! regenerates the project from those into a scratch directory with the
+//! current template and returns the difference between the recorded and the
+//! new output as a unified diff, which can be reviewed and applied with
+//! `git apply` or `patch -p1`.
+//!
+//! Since the diff only holds what the template changed, hand edits to the
+//! project are kept; hunks touching edited lines may need resolving by hand.
+
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use similar::TextDiff;
+use tokio::fs;
+
+use crate::{
+    error::{Error, Result},
+    generate::generate,
+    manifest::{GenerationManifest, GENERATION_MANIFEST_FILE},
+};
+
+/// Lines of unchanged context around each hunk
+const DIFF_CONTEXT: usize = 3;
+
+/// What an [`upgrade`] would change in a project
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UpgradeReport {
+    /// Unified diff from the recorded output of the last generation to the
+    /// current template's output (empty when the output didn't change)
+    pub patch: String,
+
+    /// Files the patch touches that were edited since they were generated,
+    /// relative to the project; their hunks may not apply cleanly
+    pub edited_files: Vec<PathBuf>,
+
+    /// Files the last generation wrote that the current template no longer
+    /// produces, relative to the project; they are left in place
+    pub removed_files: Vec<PathBuf>,
+}
+
+impl UpgradeReport {
+    /// Whether the project already matches the current template
+    pub fn is_up_to_date(&self) -> bool {
+        self.patch.is_empty() && self.removed_files.is_empty()
+    }
+}
+
+/// Regenerate the project in `project_dir` with the current template and
+/// report the changes against its last generation.
+///
+/// `template_dir` overrides the template directory recorded for the project.
+///
+/// # Errors
+///
+/// Returns an error if the project has no generation manifest, or one written
+/// before generation inputs and files were recorded, or if regeneration fails.
+pub async fn upgrade(project_dir: &Path, template_dir: Option<&Path>) -> Result<UpgradeReport> {
+    let manifest = GenerationManifest::load(project_dir)
+        .await?
+        .ok_or_else(|| {
+            Error::config(format!(
+                "{} has no {}; was it generated by agenterra?",
+                project_dir.display(),
+                GENERATION_MANIFEST_FILE
+            ))
+        })?;
+    let (Some(mut config), false) = (manifest.config, manifest.files.is_empty()) else {
+        return Err(Error::config(format!(
+            "{} does not record how {} was generated; scaffold it again once to enable upgrades",
+            GENERATION_MANIFEST_FILE,
+            project_dir.display()
+        )));
+    };
+
+    let stamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_nanos());
+    let scratch = std::env::temp_dir().join(format!(
+        "agenterra-upgrade-{}-{}",
+        std::process::id(),
+        stamp
+    ));
+    if fs::try_exists(&scratch).await? {
+        fs::remove_dir_all(&scratch).await?;
+    }
+    config.output_dir = scratch.to_string_lossy().into_owned();
+    if let Some(template_dir) = template_dir {
+        config.template_dir = Some(template_dir.to_string_lossy().into_owned());
+    }
+
+    let result = async {
+        generate(&config, manifest.template_options).await?;
+        let regenerated = GenerationManifest::load(&scratch)
+            .await?
+            .map(|manifest| manifest.files)
+            .unwrap_or_default();
+        compare(project_dir, &manifest.files, &regenerated).await
+    }
+    .await;
+    fs::remove_dir_all(&scratch).await.ok();
+    result
+}
+
+/// Report turning the `baseline` output into the `regenerated` one
+async fn compare(
+    project_dir: &Path,
+    baseline: &BTreeMap<String, String>,
+    regenerated: &BTreeMap<String, String>,
+) -> Result<UpgradeReport> {
+    let mut report = UpgradeReport::default();
+    for (name, new) in regenerated {
+        let old = baseline.get(name).map(String::as_str);
+        if old == Some(new.as_str()) {
+            continue;
+        }
+        report.patch.push_str(&unified_diff(name, old, new));
+        let current = match fs::read_to_string(project_dir.join(name)).await {
+            Ok(current) => Some(current),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+            Err(e) => return Err(e.into()),
+        };
+        if current.as_deref() != old {
+            report.edited_files.push(PathBuf::from(name));
+        }
+    }
+    report.removed_files = baseline
+        .keys()
+        .filter(|name| !regenerated.contains_key(*name))
+        .map(PathBuf::from)
+        .collect();
+    Ok(report)
+}
+
+/// Unified diff of one file; `old` is `None` for a file that didn't exist.
+/// Empty when the contents are equal.
+fn unified_diff(name: &str, old: Option<&str>, new: &str) -> String {
+    let old_header = match old {
+        Some(_) => format!("a/{}", name),
+        None => "/dev/null".to_string(),
+    };
+    TextDiff::from_lines(old.unwrap_or_default(), new)
+        .unified_diff()
+        .context_radius(DIFF_CONTEXT)
+        .header(&old_header, &format!("b/{}", name))
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{templates::TemplateOptions, Config};
+
+    #[tokio::test]
+    async fn test_upgrade_diffs_template_changes() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let template_dir = temp_dir.path().join("templates/rust_axum");
+        std::fs::create_dir_all(&template_dir)?;
+        std::fs::write(
+            template_dir.join("manifest.yaml"),
+            "name: t\ndescription: t\nversion: \"0.1.0\"\nlanguage: rust\nfiles:\n  - source: main.tera\n    destination: src/main.rs\n  - source: extra.tera\n    destination: src/extra.rs\n",
+        )?;
+        std::fs::write(
+            template_dir.join("main.tera"),
+            "// {{ project_name }}\nfn main() {\n    println!(\"hello\");\n}\n",
+        )?;
+        std::fs::write(template_dir.join("extra.tera"), "// extra\n")?;
+        let spec_path = temp_dir.path().join("openapi.json");
+        std::fs::write(
+            &spec_path,
+            r#"{"openapi": "3.0.0", "info": {"title": "Pets", "version": "1.0.0"}, "servers": [{"url": "https://api.example.com"}], "paths": {}}"#,
+        )?;
+
+        let project = temp_dir.path().join("project");
+        let mut config = Config::new(
+            "pets",
+            spec_path.to_string_lossy(),
+            project.to_string_lossy(),
+        );
+        config.template_dir = Some(template_dir.to_string_lossy().into_owned());
+        generate(&config, Some(TemplateOptions::default())).await?;
+        let report = upgrade(&project, None).await?;
+        assert!(report.is_up_to_date());
+
+        std::fs::write(
+            template_dir.join("main.tera"),
+            "// {{ project_name }}\nfn main() {\n    println!(\"hello, world\");\n}\n",
+        )?;
+        let report = upgrade(&project, None).await?;
+        assert_eq!(
+            report.patch,
+            "--- a/src/main.rs\n+++ b/src/main.rs\n@@ -1,4 +1,4 @@\n // pets\n fn main() {\n-    println!(\"hello\");\n+    println!(\"hello, world\");\n }\n"
+        );
+        assert!(report.edited_files.is_empty());
+        // The project itself is left alone
+        assert!(std::fs::read_to_string(project.join("src/main.rs"))?.contains("\"hello\""));
+
+        // Hand edits stay out of the patch, and dropped files are reported
+        std::fs::write(
+            project.join("src/main.rs"),
+            "// pets\nfn main() {\n    println!(\"hello\");\n}\n\nfn helper() {}\n",
+        )?;
+        std::fs::write(
+            template_dir.join("manifest.yaml"),
+            "name: t\ndescription: t\nversion: \"0.1.0\"\nlanguage: rust\nfiles:\n  - source: main.tera\n    destination: src/main.rs\n",
+        )?;
+        let report = upgrade(&project, None).await?;
+        assert!(!report.patch.contains("helper"));
+        assert!(report.patch.contains("+    println!(\"hello, world\");"));
+        assert_eq!(report.edited_files, vec![PathBuf::from("src/main.rs")]);
+        assert_eq!(report.removed_files, vec![PathBuf::from("src/extra.rs")]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_upgrade_replays_spec_patch() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let template_dir = temp_dir.path().join("templates/rust_axum");
+        std::fs::create_dir_all(&template_dir)?;
+        std::fs::write(
+            template_dir.join("manifest.yaml"),
+            "name: t\ndescription: t\nversion: \"0.1.0\"\nlanguage: rust\nfiles:\n  - source: main.tera\n    destination: src/main.rs\n",
+        )?;
+        std::fs::write(template_dir.join("main.tera"), "// {{ base_api_url }}\n")?;
+        // No servers: generation only works with the patch applied
+        let spec_path = temp_dir.path().join("openapi.json");
+        std::fs::write(
+            &spec_path,
+            r#"{"openapi": "3.0.0", "info": {"title": "Pets", "version": "1.0.0"}, "paths": {}}"#,
+        )?;
+
+        let project = temp_dir.path().join("project");
+        let mut config = Config::new(
+            "pets",
+            spec_path.to_string_lossy(),
+            project.to_string_lossy(),
+        );
+        config.template_dir = Some(template_dir.to_string_lossy().into_owned());
+        let options = TemplateOptions {
+            spec_patch: Some(
+                serde_json::json!({"servers": [{"url": "https://patched.example.com"}]}),
+            ),
+            ..Default::default()
+        };
+        generate(&config, Some(options)).await?;
+        assert!(upgrade(&project, None).await?.is_up_to_date());
+        Ok(())
+    }
+
+    #[test]
+    fn test_recorded_paths_are_absolute() {
+        let mut config = Config::new("pets", "specs/openapi.yaml", "out");
+        config.template_dir = Some("templates".to_string());
+        let manifest = GenerationManifest::new(&Default::default()).with_inputs(&config, &None);
+        let recorded = manifest.config.unwrap();
+        let schema_path = Path::new(&recorded.openapi_schema_path);
+        assert!(schema_path.is_absolute());
+        assert!(schema_path.ends_with("specs/openapi.yaml"));
+        assert!(Path::new(&recorded.template_dir.unwrap()).is_absolute());
+
+        let config = Config::new("pets", "https://example.com/openapi.json", "out");
+        let manifest = GenerationManifest::new(&Default::default()).with_inputs(&config, &None);
+        assert_eq!(
+            manifest.config.unwrap().openapi_schema_path,
+            "https://example.com/openapi.json"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_upgrade_needs_recorded_files() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let mut manifest = GenerationManifest::new(&Default::default());
+        manifest.config = Some(Config::new("pets", "openapi.json", "out"));
+        manifest.save(temp_dir.path()).await?;
+        let err = upgrade(temp_dir.path(), None).await.unwrap_err();
+        assert!(err.to_string().contains("scaffold it again once"));
+        Ok(())
+    }
+}
@@ -77,6 +77,51 @@ pub fn to_lower_camel_case(s: &str) -> String {
     }
 }
 
+/// Rust keywords (strict, reserved and 2018+ edition keywords)
+pub const RUST_KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "crate",
+    "do", "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl",
+    "in", "let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref",
+    "return", "self", "Self", "static", "struct", "super", "trait", "true", "try", "type",
+    "typeof", "unsafe", "unsized", "use", "virtual", "where", "while", "yield",
+];
+
+/// Escape an identifier that is a Rust keyword: `type` becomes `r#type`; `self`,
+/// `Self`, `super` and `crate`, which can't be raw identifiers, get a trailing underscore
+pub fn escape_rust_keyword(ident: &str) -> String {
+    match ident {
+        "self" | "Self" | "super" | "crate" => format!("{}_", ident),
+        _ if RUST_KEYWORDS.contains(&ident) => format!("r#{}", ident),
+        _ => ident.to_string(),
+    }
+}
+
+/// Whether a name is usable as a package name: non-empty ASCII letters, digits
+/// and underscores, starting with a letter
+pub fn is_valid_package_name(name: &str) -> bool {
+    name.chars()
+        .next()
+        .is_some_and(|first| first.is_ascii_alphabetic())
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Make a name safe to use as a single path component: path separators and
+/// characters Windows rejects become `_`, and so do the names `.` and `..`
+pub fn sanitize_path_component(name: &str) -> String {
+    let sanitized: String = name
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+    match sanitized.as_str() {
+        "" | "." | ".." => "_".to_string(),
+        _ => sanitized,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -127,4 +172,31 @@ mod tests {
         );
         assert_eq!(to_lower_camel_case("http_response"), "httpResponse");
     }
+
+    #[test]
+    fn test_escape_keywords() {
+        assert_eq!(escape_rust_keyword("type"), "r#type");
+        assert_eq!(escape_rust_keyword("move"), "r#move");
+        assert_eq!(escape_rust_keyword("self"), "self_");
+        assert_eq!(escape_rust_keyword("status"), "status");
+    }
+
+    #[test]
+    fn test_is_valid_package_name() {
+        assert!(is_valid_package_name("my_api_2_0"));
+        assert!(is_valid_package_name("petstore"));
+        assert!(!is_valid_package_name(""));
+        assert!(!is_valid_package_name("2048_game"));
+        assert!(!is_valid_package_name("_private"));
+        assert!(!is_valid_package_name("café"));
+    }
+
+    #[test]
+    fn test_sanitize_path_component() {
+        assert_eq!(sanitize_path_component("pets"), "pets");
+        assert_eq!(sanitize_path_component("a/b"), "a_b");
+        assert_eq!(sanitize_path_component("c:\\x"), "c__x");
+        assert_eq!(sanitize_path_component(".."), "_");
+        assert_eq!(sanitize_path_component(""), "_");
+    }
 }